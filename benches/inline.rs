@@ -0,0 +1,55 @@
+//! Counts the allocations made copying tokens into `RcSubstring`s, to compare builds with
+//! and without the `inline` feature:
+//!
+//! ```text
+//! cargo bench --bench inline
+//! cargo bench --bench inline --features inline
+//! ```
+use rcsubstring::{IntoRcSubstring, RcSubstring};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+struct Counting;
+
+// SAFETY: defers to the system allocator, only counting the calls
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn main() {
+    let source = "let total = price(item, 42) + shipping_cost;\n".repeat(10_000);
+    let words: Vec<&str> = source.split_whitespace().collect();
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let tokens: Vec<RcSubstring> = words
+        .iter()
+        .map(|word| black_box(*word).into_rc_substring())
+        .collect();
+    let elapsed = start.elapsed();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "inline feature {}: {} tokens, {} allocations, {:?}",
+        if cfg!(feature = "inline") {
+            "on"
+        } else {
+            "off"
+        },
+        tokens.len(),
+        allocations,
+        elapsed
+    );
+}
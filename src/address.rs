@@ -0,0 +1,116 @@
+//! Splitting email addresses and host and port pairs into [RcSubstring]s
+use crate::RcSubstring;
+
+impl RcSubstring {
+    /// Split an email address into its local part and domain, at the last `@`
+    ///
+    /// This is only a rough check: it returns `None` if either part is empty, or the
+    /// domain contains whitespace or starts or ends with a `.`. The local part is not
+    /// checked, so quoted local parts such as `"a@b"@example.com` work.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let address = RcSubstring::from(String::from("jo.bloggs+news@example.org"));
+    /// let (local, domain) = address.split_email().unwrap();
+    /// assert_eq!(local, "jo.bloggs+news");
+    /// assert_eq!(domain, "example.org");
+    /// assert!(RcSubstring::from_static("@example.org").split_email().is_none());
+    /// ```
+    pub fn split_email(&self) -> Option<(RcSubstring, RcSubstring)> {
+        let at = self.rfind('@')?;
+        let domain = &self[at + 1..];
+        if at == 0
+            || domain.is_empty()
+            || domain.starts_with('.')
+            || domain.ends_with('.')
+            || domain.contains(char::is_whitespace)
+        {
+            return None;
+        }
+        Some((self.reslice(0..at), self.reslice(at + 1..self.len())))
+    }
+
+    /// Split `host:port` into the host and the port, if there is one
+    ///
+    /// An IPv6 address in brackets, as in `[::1]:8080`, is returned without the brackets.
+    /// Text with more than one colon and no brackets is taken to be a bare IPv6 address with
+    /// no port. An empty port, as in `host:`, is `None`. The port is not checked to be a
+    /// number.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let (host, port) = RcSubstring::from_static("example.com:443").split_host_port();
+    /// assert_eq!(host, "example.com");
+    /// assert_eq!(port.unwrap(), "443");
+    /// let (host, port) = RcSubstring::from_static("[2001:db8::1]:53").split_host_port();
+    /// assert_eq!(host, "2001:db8::1");
+    /// assert_eq!(port.unwrap(), "53");
+    /// ```
+    pub fn split_host_port(&self) -> (RcSubstring, Option<RcSubstring>) {
+        let port_from =
+            |colon: usize| (colon + 1 < self.len()).then(|| self.reslice(colon + 1..self.len()));
+        if self.starts_with('[')
+            && let Some(close) = self.find(']')
+        {
+            let host = self.reslice(1..close);
+            return match &self[close + 1..] {
+                "" => (host, None),
+                rest if rest.starts_with(':') => (host, port_from(close + 1)),
+                // Not a bracketed address after all
+                _ => (self.clone(), None),
+            };
+        }
+        match self.find(':') {
+            Some(colon) if self[colon + 1..].contains(':') => (self.clone(), None),
+            Some(colon) => (self.reslice(0..colon), port_from(colon)),
+            None => (self.clone(), None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_port(text: &str) -> (String, Option<String>) {
+        let (host, port) = RcSubstring::from(String::from(text)).split_host_port();
+        (host.to_string(), port.map(|port| port.to_string()))
+    }
+
+    #[test]
+    fn test_split_email() {
+        let email = |text: &str| {
+            let (local, domain) = RcSubstring::from(String::from(text)).split_email()?;
+            Some((local.to_string(), domain.to_string()))
+        };
+        assert_eq!(
+            email("\"a@b\"@exämple.com"),
+            Some((String::from("\"a@b\""), String::from("exämple.com")))
+        );
+        assert_eq!(email("nobody"), None);
+        assert_eq!(email("user@"), None);
+        assert_eq!(email("user@.com"), None);
+        assert_eq!(email("user@com."), None);
+        assert_eq!(email("user@exa mple.com"), None);
+    }
+
+    #[test]
+    fn test_split_host_port() {
+        let some = |host: &str, port: &str| (String::from(host), Some(String::from(port)));
+        let none = |host: &str| (String::from(host), None);
+        assert_eq!(host_port("localhost:8080"), some("localhost", "8080"));
+        assert_eq!(host_port("localhost"), none("localhost"));
+        assert_eq!(host_port("localhost:"), none("localhost"));
+        assert_eq!(host_port("[::1]:80"), some("::1", "80"));
+        assert_eq!(host_port("[::1]"), none("::1"));
+        assert_eq!(host_port("[::1]:"), none("::1"));
+        assert_eq!(host_port("::1"), none("::1"));
+        assert_eq!(host_port("[::1"), none("[::1"));
+        assert_eq!(host_port("[::1]x"), none("[::1]x"));
+        assert_eq!(host_port(""), none(""));
+        let source = RcSubstring::from(String::from("[fe80::1]:22"));
+        let (host, port) = source.split_host_port();
+        assert_eq!(host.range, 1..8);
+        assert_eq!(port.unwrap().range, 10..12);
+    }
+}
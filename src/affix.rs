@@ -0,0 +1,166 @@
+//! Taking prefixes and suffixes off an [RcSubstring]
+use crate::{RcPattern, RcSubstring};
+
+impl RcSubstring {
+    /// The start of this substring if it matches `prefix`, which can be any [RcPattern]
+    ///
+    /// Unlike [str::strip_prefix] this returns the part that matched rather than the rest,
+    /// which is handy for peeking at delimiters and operators in a lexer.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let input = RcSubstring::from(String::from("->next"));
+    /// let arrow = input.match_prefix("->").unwrap();
+    /// assert_eq!(arrow, "->");
+    /// assert!(input.match_prefix("=>").is_none());
+    /// ```
+    pub fn match_prefix(&self, mut prefix: impl RcPattern) -> Option<RcSubstring> {
+        let len = prefix.prefix_len(self)?;
+        Some(self.reslice(0..len))
+    }
+
+    /// The end of this substring if it matches `suffix`
+    ///
+    /// Unlike [str::strip_suffix] this returns the part that matched rather than the rest.
+    pub fn match_suffix(&self, mut suffix: impl RcPattern) -> Option<RcSubstring> {
+        let len = suffix.suffix_len(self)?;
+        Some(self.reslice(self.len() - len..self.len()))
+    }
+
+    /// Split off and return the first `n` bytes, leaving the rest in this substring
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than the length or not on a char boundary, as [str::split_at] does
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let mut input = RcSubstring::from(String::from("GET /path"));
+    /// let method = input.take_prefix(3);
+    /// assert_eq!(method, "GET");
+    /// assert_eq!(input, " /path");
+    /// ```
+    #[track_caller]
+    pub fn take_prefix(&mut self, n: usize) -> RcSubstring {
+        self.check_split(n);
+        let prefix = self.reslice(0..n);
+        self.set_range(self.range.start + n..self.range.end);
+        prefix
+    }
+
+    /// Split off and return the last `n` bytes, leaving the rest in this substring
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than the length or the split is not on a char boundary
+    #[track_caller]
+    pub fn take_suffix(&mut self, n: usize) -> RcSubstring {
+        let mid = self.len().checked_sub(n).unwrap_or_else(|| {
+            panic!(
+                "suffix length {} is more than the RcSubstring length {}",
+                n,
+                self.len()
+            )
+        });
+        self.check_split(mid);
+        let suffix = self.reslice(mid..self.len());
+        self.set_range(self.range.start..self.range.start + mid);
+        suffix
+    }
+
+    /// The first char and the rest of the substring after it, or `None` if it is empty
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let word = RcSubstring::from(String::from("état"));
+    /// let (first, rest) = word.split_first_char().unwrap();
+    /// assert_eq!(first, 'é');
+    /// assert_eq!(rest, "tat");
+    /// ```
+    pub fn split_first_char(&self) -> Option<(char, RcSubstring)> {
+        let c = self.chars().next()?;
+        Some((c, self.reslice(c.len_utf8()..self.len())))
+    }
+
+    /// The last char and the rest of the substring before it, or `None` if it is empty
+    pub fn split_last_char(&self) -> Option<(char, RcSubstring)> {
+        let c = self.chars().next_back()?;
+        Some((c, self.reslice(0..self.len() - c.len_utf8())))
+    }
+
+    // Panic if this can't be split at mid
+    #[track_caller]
+    fn check_split(&self, mid: usize) {
+        if !self.is_char_boundary(mid) {
+            panic!(
+                "can't split RcSubstring of length {} at {}",
+                self.len(),
+                mid
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_match_affixes() {
+        let source = Rc::new(String::from("(a == b);"));
+        let expression = RcSubstring::new(Rc::clone(&source), 3..5);
+        let op = expression.match_prefix("==").unwrap();
+        assert!(Rc::ptr_eq(op.shared().unwrap(), &source));
+        assert_eq!(op.range, 3..5);
+        let end = RcSubstring::from(Rc::clone(&source))
+            .match_suffix(");")
+            .unwrap();
+        assert_eq!(end.range, 7..9);
+        assert!(expression.match_suffix("=").is_some());
+        assert!(expression.match_suffix("a ==").is_none());
+        assert_eq!(expression.match_prefix("").unwrap(), "");
+        assert_eq!(expression.match_suffix(['=', '!']).unwrap(), "=");
+    }
+
+    #[test]
+    fn test_take_affixes() {
+        let source = Rc::new(String::from("[é:1]"));
+        let mut cursor = RcSubstring::from(Rc::clone(&source));
+        assert_eq!(cursor.take_prefix(1), "[");
+        assert_eq!(cursor.take_suffix(1), "]");
+        assert_eq!(cursor.range, 1..5);
+        let e = cursor.take_prefix(2);
+        assert_eq!(e, "é");
+        assert!(Rc::ptr_eq(e.shared().unwrap(), &source));
+        assert_eq!(cursor.take_suffix(2), ":1");
+        assert_eq!(cursor, "");
+        assert_eq!(cursor.take_prefix(0), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "can't split")]
+    fn test_take_prefix_not_char_boundary() {
+        RcSubstring::from(String::from("é")).take_prefix(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than")]
+    fn test_take_suffix_too_long() {
+        RcSubstring::from(String::from("ab")).take_suffix(3);
+    }
+
+    #[test]
+    fn test_split_chars() {
+        let source = Rc::new(String::from("a€b"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        let (last, rest) = text.split_last_char().unwrap();
+        assert_eq!(last, 'b');
+        let (euro, rest) = rest.reslice(1..4).split_first_char().unwrap();
+        assert_eq!(euro, '€');
+        assert_eq!(rest.range, 4..4);
+        assert!(Rc::ptr_eq(rest.shared().unwrap(), &source));
+        assert!(rest.split_first_char().is_none());
+        assert!(rest.split_last_char().is_none());
+    }
+}
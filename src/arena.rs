@@ -0,0 +1,338 @@
+//! An [Arena] that packs many small strings into a few large pages
+use crate::{Backing, RcSubstring};
+use std::fmt::{self, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The default size of a page, unless a string needs a bigger one
+const PAGE_SIZE: usize = 16 * 1024;
+
+// A fixed size buffer that text is only ever appended to, so substrings of the text written
+// so far can be read while more is written after it
+pub(crate) struct Page {
+    // From a boxed slice of capacity bytes
+    buf: *mut u8,
+    capacity: usize,
+    // The bytes before this are UTF-8 and never change again. Atomic rather than a Cell
+    // so substrings stay UnwindSafe; a Page is only used by one thread, so Relaxed is enough.
+    len: AtomicUsize,
+}
+
+impl Page {
+    fn with_capacity(capacity: usize) -> Rc<Page> {
+        let buf = Box::into_raw(vec![0u8; capacity].into_boxed_slice()).cast::<u8>();
+        Rc::new(Page {
+            buf,
+            capacity,
+            len: AtomicUsize::new(0),
+        })
+    }
+
+    // Append text, returning where it starts, or None if there isn't room
+    fn push(&self, text: &str) -> Option<usize> {
+        let start = self.len();
+        if self.capacity - start < text.len() {
+            return None;
+        }
+        // SAFETY: the bytes written are in bounds and after any that have been read
+        unsafe {
+            std::ptr::copy_nonoverlapping(text.as_ptr(), self.buf.add(start), text.len());
+        }
+        self.len.store(start + text.len(), Ordering::Relaxed);
+        Some(start)
+    }
+
+    // How many bytes have been written
+    fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: the bytes before len were copied from strs and are never written again
+        unsafe {
+            let bytes = std::slice::from_raw_parts(self.buf, self.len());
+            std::str::from_utf8_unchecked(bytes)
+        }
+    }
+}
+
+impl Drop for Page {
+    fn drop(&mut self) {
+        // SAFETY: buf and capacity came from the boxed slice, which hasn't been freed
+        unsafe {
+            drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+                self.buf,
+                self.capacity,
+            )));
+        }
+    }
+}
+
+/**
+Copies strings into large shared pages, returning an [RcSubstring] of each
+
+Programs that make many small strings, such as code generators, can push them into an arena
+so that they share a few big allocations rather than each getting its own `Rc<String>`.
+Each substring keeps only its own page alive, and a page is freed when the arena has moved
+on from it and all of its substrings are dropped.
+[crate::compact_all] leaves them as they are.
+
+```rust
+# use rcsubstring::Arena;
+let mut arena = Arena::new();
+let names: Vec<_> = (0..100).map(|i| arena.push_str(&format!("var{i}"))).collect();
+assert_eq!(names[42], "var42");
+assert_eq!(arena.page_count(), 1);
+```
+
+Text pushed one piece after another is adjacent in the page unless a new page had to be
+started, so for generated code the spans of single tokens can be captured as they are
+written and still be joined into longer spans without copying.
+
+```rust
+# use rcsubstring::Arena;
+let mut arena = Arena::with_page_size(256);
+let keyword = arena.push_str("fn ");
+let name = arena.push_fmt(format_args!("handler_{}", 7));
+let signature = arena.push_str("()");
+assert_eq!(name, "handler_7");
+assert_eq!(keyword + &name + &signature, "fn handler_7()");
+```
+
+A clone has the same page size but starts pages of its own, as text written into one page
+by two arenas could interleave.
+*/
+pub struct Arena {
+    page: Option<Rc<Page>>,
+    page_count: usize,
+    page_size: usize,
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena::with_page_size(PAGE_SIZE)
+    }
+}
+
+impl Clone for Arena {
+    fn clone(&self) -> Self {
+        Arena::with_page_size(self.page_size)
+    }
+}
+
+impl Arena {
+    /// Create an empty arena with pages of 16 KiB, which allocates nothing until a string is
+    /// pushed
+    pub fn new() -> Self {
+        Arena::default()
+    }
+
+    /// Create an empty arena with pages of `page_size` bytes
+    ///
+    /// Bigger pages mean fewer allocations, but a page stays alive while any of its
+    /// substrings do.
+    pub fn with_page_size(page_size: usize) -> Self {
+        Arena {
+            page: None,
+            page_count: 0,
+            page_size,
+        }
+    }
+
+    /// The size of the pages in bytes
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Copy `text` into the arena, returning a substring of the copy
+    ///
+    /// The text goes in the current page if there is room, otherwise in a new page. Text
+    /// longer than a page gets a page of its own, and later text still goes in the current
+    /// page.
+    pub fn push_str(&mut self, text: &str) -> RcSubstring {
+        if let Some(page) = &self.page
+            && let Some(start) = page.push(text)
+        {
+            return RcSubstring::from_parts(
+                Backing::Page(Rc::clone(page)),
+                start..start + text.len(),
+            );
+        }
+        let page = self.new_page(text.len());
+        page.push(text);
+        RcSubstring::from_parts(Backing::Page(page), 0..text.len())
+    }
+
+    /// Write `args` into the arena, returning a substring of what was written
+    ///
+    /// The text is formatted straight into the current page. If it runs out of room the text
+    /// so far is moved to a new page, leaving unused space behind.
+    ///
+    /// ```rust
+    /// # use rcsubstring::Arena;
+    /// let mut arena = Arena::new();
+    /// let label = arena.push_fmt(format_args!("L{}:", 12));
+    /// assert_eq!(label, "L12:");
+    /// ```
+    pub fn push_fmt(&mut self, args: fmt::Arguments<'_>) -> RcSubstring {
+        if let Some(text) = args.as_str() {
+            return self.push_str(text);
+        }
+        let page = match &self.page {
+            Some(page) => Rc::clone(page),
+            None => self.new_page(0),
+        };
+        let start = page.len();
+        let mut writer = PageWriter {
+            arena: self,
+            page,
+            start,
+        };
+        writer
+            .write_fmt(args)
+            .expect("a formatting trait implementation returned an error");
+        let end = writer.page.len();
+        RcSubstring::from_parts(Backing::Page(writer.page), writer.start..end)
+    }
+
+    // Start a page for text of len bytes
+    fn new_page(&mut self, len: usize) -> Rc<Page> {
+        let page = Page::with_capacity(self.page_size.max(len));
+        self.page_count += 1;
+        // A page of its own doesn't replace the page that smaller text is going in
+        if len <= self.page_size {
+            self.page = Some(Rc::clone(&page));
+        }
+        page
+    }
+
+    /// The number of pages allocated so far, including any that have been freed
+    pub fn page_count(&self) -> usize {
+        self.page_count
+    }
+}
+
+// Formats text into the pages of an arena, keeping it all in one page
+struct PageWriter<'a> {
+    arena: &'a mut Arena,
+    page: Rc<Page>,
+    // Where the text being written starts in page
+    start: usize,
+}
+
+impl Write for PageWriter<'_> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        if self.page.push(text).is_some() {
+            return Ok(());
+        }
+        let written = &self.page.as_str()[self.start..];
+        let needed = written.len() + text.len();
+        // Text too big for a page gets its own, doubled so long output isn't copied often
+        let len = if needed <= self.arena.page_size {
+            needed
+        } else {
+            needed * 2
+        };
+        let page = self.arena.new_page(len);
+        page.push(written);
+        page.push(text);
+        self.page = page;
+        self.start = 0;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Arena {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Arena")
+            .field("page_size", &self.page_size)
+            .field("page_count", &self.page_count)
+            .field("page_len", &self.page.as_ref().map(|page| page.len()))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pages() {
+        let mut arena = Arena::new();
+        assert_eq!(arena.page_count(), 0);
+        let first = arena.push_str("é");
+        let filler = arena.push_str(&"x".repeat(PAGE_SIZE - 3));
+        let fits = arena.push_str("y");
+        assert_eq!(arena.page_count(), 1);
+        let next = arena.push_str("zz");
+        assert_eq!(arena.page_count(), 2);
+        assert_eq!(
+            (first.range.clone(), fits.range.clone()),
+            (0..2, PAGE_SIZE - 1..PAGE_SIZE)
+        );
+        assert_eq!(next.range, 0..2);
+        assert_eq!(filler.len(), PAGE_SIZE - 3);
+        assert_eq!([&*first, &*fits, &*next], ["é", "y", "zz"]);
+        let big = arena.push_str(&"b".repeat(PAGE_SIZE + 1));
+        assert_eq!(big.len(), PAGE_SIZE + 1);
+        assert_eq!(arena.page_count(), 3);
+        assert_eq!(arena.push_str("").range, 2..2);
+        assert_eq!(arena.push_str("after").range, 2..7);
+        assert_eq!(arena.page_count(), 3);
+    }
+
+    #[test]
+    fn test_push_fmt() {
+        let mut arena = Arena::with_page_size(8);
+        assert_eq!(arena.page_size(), 8);
+        let (a, b) = ("ab", 1);
+        let first = arena.push_fmt(format_args!("{a}{b}"));
+        assert_eq!((&*first, first.range.clone()), ("ab1", 0..3));
+        assert_eq!(arena.push_fmt(format_args!("plain")).range, 3..8);
+        // Moved to a new page when it doesn't fit
+        let (x, y) = ("xy", "z");
+        let moved = arena.push_fmt(format_args!("{x}-{y}"));
+        assert_eq!((&*moved, moved.range.clone()), ("xy-z", 0..4));
+        assert_eq!(arena.page_count(), 2);
+        // Too big for a page, so it gets its own and the current page is kept
+        let (a, b, c) = (1234, 56789, "abcdefghij");
+        let big = arena.push_fmt(format_args!("{a}{b}{c}"));
+        assert_eq!(big, "123456789abcdefghij");
+        assert_eq!(arena.page_count(), 4);
+        // Writing 1234 filled the page before moving it
+        assert_eq!(arena.push_str("next").range, 0..4);
+        assert_eq!(arena.page_count(), 5);
+        let mut empty = Arena::new();
+        assert_eq!(empty.push_fmt(format_args!("{}", "")), "");
+        assert_eq!(empty.page_count(), 1);
+    }
+
+    #[test]
+    #[cfg(not(any(feature = "cached-hash", feature = "cached-char-count")))]
+    fn test_unwind_safe() {
+        fn assert_unwind_safe<T: std::panic::UnwindSafe + std::panic::RefUnwindSafe>() {}
+        assert_unwind_safe::<RcSubstring>();
+        assert_unwind_safe::<Arena>();
+    }
+
+    #[test]
+    fn test_substrings_outlive_arena() {
+        let mut arena = Arena::new();
+        let a = arena.push_str("ab");
+        let b = arena.push_str("cd");
+        let mut clone = arena.clone();
+        assert_eq!(clone.push_str("ef").range, 0..2);
+        assert_eq!((clone.page_count(), clone.page_size()), (1, PAGE_SIZE));
+        drop(arena);
+        // Adjacent pushes join without copying
+        let joined = a.clone() + &b;
+        assert_eq!(joined, "abcd");
+        assert_eq!(joined.range, 0..4);
+        assert_eq!(a.source_id(), b.source_id());
+        assert_eq!(b.subslice(1..2), "d");
+        assert_eq!(
+            format!("{:?}", b.debug_full()),
+            r#"RcSubstring { rcstring: "abcd", range: 2..4 }"#
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! ASCII fast paths, and [AsciiSubstring] for text known to be ASCII
+use crate::RcSubstring;
+use std::fmt::{Debug, Display};
+use std::ops::{Deref, Range};
+
+impl RcSubstring {
+    /// True if all of the text is ASCII
+    ///
+    /// With the `cached-char-count` feature this is free once [RcSubstring::char_count] has
+    /// been called, as the text is ASCII exactly when it has as many chars as bytes.
+    pub fn is_ascii(&self) -> bool {
+        #[cfg(feature = "cached-char-count")]
+        if let Some(count) = self.char_count.get() {
+            return count == self.len();
+        }
+        self.as_str().is_ascii()
+    }
+
+    /// The text with ASCII letters lowercased, sharing the text if none are uppercase
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let header = RcSubstring::from(String::from("Content-Type"));
+    /// assert_eq!(header.to_ascii_lowercase_owned(), "content-type");
+    /// ```
+    pub fn to_ascii_lowercase_owned(&self) -> RcSubstring {
+        if !self.bytes().any(|b| b.is_ascii_uppercase()) {
+            return self.clone();
+        }
+        RcSubstring::from(self.to_ascii_lowercase())
+    }
+
+    /// The text with ASCII letters uppercased, sharing the text if none are lowercase
+    pub fn to_ascii_uppercase_owned(&self) -> RcSubstring {
+        if !self.bytes().any(|b| b.is_ascii_lowercase()) {
+            return self.clone();
+        }
+        RcSubstring::from(self.to_ascii_uppercase())
+    }
+
+    /// True if the text equals `other` ignoring ASCII case, as [str::eq_ignore_ascii_case]
+    ///
+    /// Substrings of the same range of the same text are equal without comparing them.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let method = RcSubstring::from(String::from("get"));
+    /// assert!(method.eq_ignore_ascii_case("GET"));
+    /// assert!(method.eq_ignore_ascii_case(&RcSubstring::from_static("Get")));
+    /// ```
+    pub fn eq_ignore_ascii_case(&self, other: impl AsRef<str>) -> bool {
+        let other = other.as_ref();
+        std::ptr::eq(self.as_str(), other) || self.as_str().eq_ignore_ascii_case(other)
+    }
+
+    /// This substring as an [AsciiSubstring], or `None` if it isn't all ASCII
+    pub fn as_ascii(&self) -> Option<AsciiSubstring> {
+        AsciiSubstring::new(self.clone())
+    }
+}
+
+/**
+An [RcSubstring] that is all ASCII, so each char is one byte
+
+Chars can be found by index in constant time, and any range of chars can be sliced
+without checking char boundaries. It derefs to the RcSubstring so all of its methods can be
+used too.
+
+```rust
+# use rcsubstring::RcSubstring;
+let line = RcSubstring::from(String::from("HTTP/1.1 200 OK"));
+let line = line.as_ascii().unwrap();
+assert_eq!(line.char_at(9), Some('2'));
+assert_eq!(line.slice(9..12), "200");
+assert!(RcSubstring::from_static("naïve").as_ascii().is_none());
+```
+*/
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AsciiSubstring(RcSubstring);
+
+impl AsciiSubstring {
+    /// Wrap `substring` if it is all ASCII, otherwise return `None`
+    pub fn new(substring: RcSubstring) -> Option<Self> {
+        substring.is_ascii().then_some(AsciiSubstring(substring))
+    }
+
+    /// The char at `index`, or `None` if it is past the end
+    pub fn char_at(&self, index: usize) -> Option<char> {
+        self.0.as_bytes().get(index).map(|&b| char::from(b))
+    }
+
+    /// The chars in `range`, sharing the text
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` starts after it ends or is past the end
+    #[track_caller]
+    pub fn slice(&self, range: Range<usize>) -> AsciiSubstring {
+        assert!(
+            range.start <= range.end && range.end <= self.0.len(),
+            "range {:?} is out of bounds for an AsciiSubstring of length {}",
+            range,
+            self.0.len()
+        );
+        AsciiSubstring(self.0.reslice(range))
+    }
+
+    /// Consume this returning the RcSubstring
+    pub fn into_substring(self) -> RcSubstring {
+        self.0
+    }
+}
+
+impl Deref for AsciiSubstring {
+    type Target = RcSubstring;
+
+    fn deref(&self) -> &RcSubstring {
+        &self.0
+    }
+}
+
+impl From<AsciiSubstring> for RcSubstring {
+    fn from(ascii: AsciiSubstring) -> Self {
+        ascii.0
+    }
+}
+
+impl TryFrom<RcSubstring> for AsciiSubstring {
+    type Error = RcSubstring;
+
+    /// Wrap the substring if it is all ASCII, otherwise give it back
+    fn try_from(substring: RcSubstring) -> Result<Self, RcSubstring> {
+        if substring.is_ascii() {
+            Ok(AsciiSubstring(substring))
+        } else {
+            Err(substring)
+        }
+    }
+}
+
+impl PartialEq<&str> for AsciiSubstring {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl Debug for AsciiSubstring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.0.as_str(), f)
+    }
+}
+
+impl Display for AsciiSubstring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.0.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_case_owned() {
+        let source = Rc::new(String::from("lower ünïcode"));
+        let lower = RcSubstring::from(Rc::clone(&source));
+        assert!(Rc::ptr_eq(
+            lower.to_ascii_lowercase_owned().shared().unwrap(),
+            &source
+        ));
+        assert_eq!(lower.to_ascii_uppercase_owned(), "LOWER üNïCODE");
+        assert!(!lower.is_ascii());
+        assert!(lower.reslice(0..5).is_ascii());
+        assert!(lower.eq_ignore_ascii_case(&lower));
+        assert!(lower.eq_ignore_ascii_case("LOWER üNïCODE"));
+        assert!(!lower.eq_ignore_ascii_case("LOWER ÜNÏCODE"));
+    }
+
+    #[test]
+    fn test_ascii_substring() {
+        let text = AsciiSubstring::try_from(RcSubstring::from(String::from("MAIL FROM"))).unwrap();
+        assert_eq!(text.char_at(0), Some('M'));
+        assert_eq!(text.char_at(9), None);
+        let from = text.slice(5..9);
+        assert_eq!(from, "FROM");
+        assert_eq!(from.range, 5..9);
+        assert_eq!(format!("{from:?} {from}"), "\"FROM\" FROM");
+        assert_eq!(RcSubstring::from(from.slice(4..4)), "");
+        let error = AsciiSubstring::try_from(RcSubstring::from_static("é")).unwrap_err();
+        assert_eq!(error, "é");
+    }
+
+    #[test]
+    #[should_panic(expected = "AsciiSubstring")]
+    fn test_slice_out_of_bounds() {
+        RcSubstring::from_static("abc")
+            .as_ascii()
+            .unwrap()
+            .slice(2..4);
+    }
+}
@@ -0,0 +1,348 @@
+//! [RcSubBytes], a reference counted slice of bytes, and decoding base64 and hex text into it
+use crate::RcSubstring;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, Range};
+use std::rc::Rc;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const HEX: &[u8; 16] = b"0123456789abcdef";
+
+/**
+A reference counted slice of bytes, the counterpart of [RcSubstring] for binary data
+
+Stores an `Rc<[u8]>` and a range, and derefs to the `[u8]` in that range.
+Slicing shares the bytes rather than copying them.
+
+```rust
+# use rcsubstring::RcSubBytes;
+let bytes = RcSubBytes::from(vec![1, 2, 3, 4]);
+let middle = bytes.slice(1..3);
+assert_eq!(middle, [2, 3]);
+assert_eq!(middle.len(), 2);
+```
+*/
+#[derive(Clone)]
+pub struct RcSubBytes {
+    bytes: Rc<[u8]>,
+    range: Range<usize>,
+}
+
+impl RcSubBytes {
+    /// Construct a new RcSubBytes of `range` in `bytes`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` starts after it ends or is past the end of `bytes`
+    #[track_caller]
+    pub fn new(bytes: Rc<[u8]>, range: Range<usize>) -> Self {
+        assert!(
+            range.start <= range.end && range.end <= bytes.len(),
+            "range {:?} is out of bounds for RcSubBytes of length {}",
+            range,
+            bytes.len()
+        );
+        RcSubBytes { bytes, range }
+    }
+
+    /// The bytes in `range` of this, sharing them
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` starts after it ends or is past the end
+    #[track_caller]
+    pub fn slice(&self, range: Range<usize>) -> RcSubBytes {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {:?} is out of bounds for RcSubBytes of length {}",
+            range,
+            self.len()
+        );
+        let start = self.range.start;
+        RcSubBytes {
+            bytes: Rc::clone(&self.bytes),
+            range: start + range.start..start + range.end,
+        }
+    }
+
+    /// The whole of the shared bytes that this is a slice of
+    pub fn shared(&self) -> &Rc<[u8]> {
+        &self.bytes
+    }
+
+    /// The bytes encoded as base64 with the standard alphabet and padding
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubBytes;
+    /// assert_eq!(RcSubBytes::from(b"hi!?".to_vec()).encode_base64(), "aGkhPw==");
+    /// ```
+    pub fn encode_base64(&self) -> RcSubstring {
+        let mut text = String::with_capacity(self.len().div_ceil(3) * 4);
+        for chunk in self.chunks(3) {
+            let n = chunk
+                .iter()
+                .enumerate()
+                .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    text.push(char::from(BASE64[(n >> (18 - 6 * i)) as usize & 63]));
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+        RcSubstring::from(text)
+    }
+
+    /// The bytes encoded as lowercase hex
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubBytes;
+    /// assert_eq!(RcSubBytes::from(vec![0, 0xab, 0x10]).encode_hex(), "00ab10");
+    /// ```
+    pub fn encode_hex(&self) -> RcSubstring {
+        let mut text = String::with_capacity(self.len() * 2);
+        for &b in self.iter() {
+            text.push(char::from(HEX[usize::from(b >> 4)]));
+            text.push(char::from(HEX[usize::from(b & 15)]));
+        }
+        RcSubstring::from(text)
+    }
+}
+
+impl RcSubstring {
+    /// Decode this text as base64 with the standard alphabet
+    ///
+    /// The padding at the end is optional, but nothing else such as whitespace is allowed.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{DecodeError, RcSubstring};
+    /// let token = RcSubstring::from(String::from("aGVsbG8"));
+    /// assert_eq!(token.decode_base64().unwrap(), b"hello");
+    /// let bad = RcSubstring::from(String::from("aG k"));
+    /// assert_eq!(bad.decode_base64(), Err(DecodeError::InvalidChar { index: 2 }));
+    /// ```
+    pub fn decode_base64(&self) -> Result<RcSubBytes, DecodeError> {
+        let text = self.as_bytes();
+        let padded = text.strip_suffix(b"==").or_else(|| text.strip_suffix(b"="));
+        let data = padded.unwrap_or(text);
+        if (padded.is_some() && !text.len().is_multiple_of(4)) || data.len() % 4 == 1 {
+            return Err(invalid_length(text.len()));
+        }
+        let mut bytes = Vec::with_capacity(data.len() * 3 / 4);
+        for (i, chunk) in data.chunks(4).enumerate() {
+            let mut n = 0u32;
+            for (j, &c) in chunk.iter().enumerate() {
+                let index = i * 4 + j;
+                let value = BASE64
+                    .iter()
+                    .position(|&b| b == c)
+                    .ok_or_else(|| invalid_char(self, index))?;
+                n |= (value as u32) << (18 - 6 * j);
+            }
+            let len = chunk.len() - 1;
+            bytes.extend_from_slice(&n.to_be_bytes()[1..1 + len]);
+        }
+        Ok(RcSubBytes::from(bytes))
+    }
+
+    /// Decode this text as hex, in either case
+    ///
+    /// ```rust
+    /// # use rcsubstring::{DecodeError, RcSubstring};
+    /// let digest = RcSubstring::from(String::from("00fF7a"));
+    /// assert_eq!(digest.decode_hex().unwrap(), [0, 255, 0x7a]);
+    /// let odd = RcSubstring::from(String::from("abc"));
+    /// assert_eq!(odd.decode_hex(), Err(DecodeError::InvalidLength { len: 3 }));
+    /// ```
+    pub fn decode_hex(&self) -> Result<RcSubBytes, DecodeError> {
+        let text = self.as_bytes();
+        if !text.len().is_multiple_of(2) {
+            return Err(invalid_length(text.len()));
+        }
+        let digit = |index: usize| {
+            char::from(text[index])
+                .to_digit(16)
+                .ok_or_else(|| invalid_char(self, index))
+        };
+        let bytes = (0..text.len())
+            .step_by(2)
+            .map(|i| Ok((digit(i)? << 4 | digit(i + 1)?) as u8))
+            .collect::<Result<Vec<u8>, DecodeError>>()?;
+        Ok(RcSubBytes::from(bytes))
+    }
+}
+
+// The error for the byte at index of text, reported at the start of its char
+fn invalid_char(text: &str, index: usize) -> DecodeError {
+    DecodeError::InvalidChar {
+        index: text.floor_char_boundary(index),
+    }
+}
+
+fn invalid_length(len: usize) -> DecodeError {
+    DecodeError::InvalidLength { len }
+}
+
+/// Why text couldn't be decoded by [RcSubstring::decode_base64] or [RcSubstring::decode_hex]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The char at `index` is not part of the encoding
+    InvalidChar {
+        /// The byte offset of the char
+        index: usize,
+    },
+    /// The text is a length that no encoded data can be
+    InvalidLength {
+        /// The length of the text in bytes
+        len: usize,
+    },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::InvalidChar { index } => write!(f, "invalid char at {}", index),
+            DecodeError::InvalidLength { len } => write!(f, "invalid encoded length {}", len),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl Deref for RcSubBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.bytes[self.range.clone()]
+    }
+}
+
+impl AsRef<[u8]> for RcSubBytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for RcSubBytes {
+    /// Wrap all of the bytes
+    fn from(bytes: Vec<u8>) -> Self {
+        RcSubBytes::from(Rc::<[u8]>::from(bytes))
+    }
+}
+
+impl From<Rc<[u8]>> for RcSubBytes {
+    /// Wrap all of the shared bytes
+    fn from(bytes: Rc<[u8]>) -> Self {
+        let len = bytes.len();
+        RcSubBytes {
+            bytes,
+            range: 0..len,
+        }
+    }
+}
+
+impl PartialEq for RcSubBytes {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for RcSubBytes {}
+
+impl PartialEq<[u8]> for RcSubBytes {
+    fn eq(&self, other: &[u8]) -> bool {
+        **self == *other
+    }
+}
+
+impl<const N: usize> PartialEq<[u8; N]> for RcSubBytes {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        **self == *other
+    }
+}
+
+impl<const N: usize> PartialEq<&[u8; N]> for RcSubBytes {
+    fn eq(&self, other: &&[u8; N]) -> bool {
+        **self == **other
+    }
+}
+
+impl Hash for RcSubBytes {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl Debug for RcSubBytes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str) -> RcSubstring {
+        RcSubstring::from(String::from(text))
+    }
+
+    #[test]
+    fn test_sub_bytes() {
+        let bytes = RcSubBytes::from(vec![1, 2, 3, 4, 5]);
+        let tail = bytes.slice(2..5);
+        let middle = tail.slice(0..2);
+        assert_eq!(middle, [3, 4]);
+        assert!(Rc::ptr_eq(middle.shared(), bytes.shared()));
+        assert_eq!(middle, RcSubBytes::from(vec![3, 4]));
+        assert_eq!(format!("{:?}", middle), "[3, 4]");
+        assert!(tail.slice(3..3).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds for RcSubBytes")]
+    fn test_slice_out_of_bounds() {
+        RcSubBytes::from(vec![1, 2]).slice(1..3);
+    }
+
+    #[test]
+    fn test_base64() {
+        for (decoded, encoded) in [
+            (&b""[..], ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (&[0xfb, 0xff, 0xbf], "+/+/"),
+        ] {
+            let bytes = text(encoded).decode_base64().unwrap();
+            assert_eq!(bytes, *decoded);
+            assert_eq!(bytes.encode_base64(), encoded);
+        }
+        assert_eq!(text("Zm8").decode_base64().unwrap(), b"fo");
+        let error = |encoded| text(encoded).decode_base64().unwrap_err();
+        assert_eq!(error("Zm9vY"), DecodeError::InvalidLength { len: 5 });
+        assert_eq!(error("Zm8=="), DecodeError::InvalidLength { len: 5 });
+        assert_eq!(error("Zg=a"), DecodeError::InvalidChar { index: 2 });
+        assert_eq!(error("Zgé"), DecodeError::InvalidChar { index: 2 });
+        assert_eq!(error("Zgé").to_string(), "invalid char at 2");
+    }
+
+    #[test]
+    fn test_hex() {
+        let bytes = text("DEADbeef00").decode_hex().unwrap();
+        assert_eq!(bytes, [0xde, 0xad, 0xbe, 0xef, 0]);
+        assert_eq!(bytes.encode_hex(), "deadbeef00");
+        assert_eq!(
+            text("0g").decode_hex(),
+            Err(DecodeError::InvalidChar { index: 1 })
+        );
+        assert_eq!(
+            text("é").decode_hex(),
+            Err(DecodeError::InvalidChar { index: 0 })
+        );
+        assert!(text("").decode_hex().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Turning `&str`s borrowed from a source back into [RcSubstring]s
+use crate::RcSubstring;
+use std::ops::Range;
+use std::rc::Rc;
+
+// The range of inner within outer, if inner is a slice of it
+fn subslice_range(outer: &str, inner: &str) -> Option<Range<usize>> {
+    let start = (inner.as_ptr() as usize).checked_sub(outer.as_ptr() as usize)?;
+    let end = start + inner.len();
+    (end <= outer.len()).then_some(start..end)
+}
+
+impl RcSubstring {
+    /// Construct an RcSubstring from a `slice` borrowed from `rc`
+    ///
+    /// The range is worked out from where `slice` is in memory, so this returns `None` if
+    /// `slice` is not part of the text of `rc`, even if its text appears there.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let line = Rc::new(String::from("name=value"));
+    /// let (_, value) = line.split_once('=').unwrap();
+    /// let value = RcSubstring::from_borrowed(&line, value).unwrap();
+    /// assert_eq!(value, "value");
+    /// assert!(RcSubstring::from_borrowed(&line, "value").is_none());
+    /// ```
+    #[track_caller]
+    pub fn from_borrowed(rc: &Rc<String>, slice: &str) -> Option<RcSubstring> {
+        let range = subslice_range(rc, slice)?;
+        Some(RcSubstring::new(Rc::clone(rc), range))
+    }
+
+    /// The range of memory holding the text of this substring
+    ///
+    /// As for [slice::as_ptr_range] the end is one past the last byte.
+    pub fn as_ptr_range(&self) -> Range<*const u8> {
+        self.as_bytes().as_ptr_range()
+    }
+
+    /// True if `slice` is part of the text of this substring in memory, not just equal text
+    ///
+    /// If so [RcSubstring::map_str] can re-wrap it.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("key=value"));
+    /// let (key, _) = text.split_once('=').unwrap();
+    /// assert!(text.contains_slice(key));
+    /// assert!(!text.contains_slice("key"));
+    /// ```
+    pub fn contains_slice(&self, slice: &str) -> bool {
+        subslice_range(self, slice).is_some()
+    }
+
+    /// Apply a `str` method that returns part of the text, keeping the result shared
+    ///
+    /// This makes any method returning a borrowed subslice, such as [str::trim] or
+    /// [str::strip_prefix], usable with an RcSubstring.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a `&str` that is not part of the text it was given.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("  [item]  "));
+    /// let item = text.map_str(|s| s.trim().trim_matches(['[', ']']));
+    /// assert_eq!(item, "item");
+    /// ```
+    #[track_caller]
+    pub fn map_str(&self, f: impl FnOnce(&str) -> &str) -> RcSubstring {
+        let text = self.as_str();
+        let range = subslice_range(text, f(text))
+            .expect("RcSubstring::map_str function returned a str that isn't part of its input");
+        self.reslice(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subslice_range() {
+        let text = "hello world";
+        assert_eq!(subslice_range(text, &text[6..]), Some(6..11));
+        assert_eq!(subslice_range(text, &text[11..]), Some(11..11));
+        assert_eq!(subslice_range(&text[6..], &text[..5]), None);
+        assert_eq!(subslice_range(&text[..5], &text[3..8]), None);
+    }
+
+    #[test]
+    fn test_pointers() {
+        let source = Rc::new(String::from("abcdef"));
+        let middle = RcSubstring::new(Rc::clone(&source), 2..4);
+        let range = middle.as_ptr_range();
+        assert_eq!(range.start, source[2..].as_ptr());
+        assert_eq!(range.end as usize - range.start as usize, 2);
+        assert!(middle.contains_slice(&source[3..4]));
+        assert!(middle.contains_slice(&source[4..4]));
+        assert!(!middle.contains_slice(&source[1..3]));
+        assert!(!middle.contains_slice(&source[3..5]));
+    }
+
+    #[test]
+    fn test_from_borrowed() {
+        let source = Rc::new(String::from("a,b"));
+        let b = source.split(',').nth(1).unwrap();
+        let b = RcSubstring::from_borrowed(&source, b).unwrap();
+        assert!(Rc::ptr_eq(b.shared().unwrap(), &source));
+        assert_eq!(b.range, 2..3);
+    }
+
+    #[test]
+    fn test_map_str() {
+        let source = Rc::new(String::from("xx-key-xx"));
+        let middle = RcSubstring::new(Rc::clone(&source), 2..7);
+        let key = middle.map_str(|s| s.strip_prefix('-').unwrap_or(s));
+        assert_eq!(key.range, 3..7);
+        let key = key.map_str(|s| s.trim_end_matches('-'));
+        assert_eq!(key, "key");
+        assert!(Rc::ptr_eq(key.shared().unwrap(), &source));
+    }
+
+    #[test]
+    #[should_panic(expected = "map_str")]
+    fn test_map_str_not_subslice() {
+        RcSubstring::from(String::from("text")).map_str(|_| "other");
+    }
+}
@@ -0,0 +1,200 @@
+//! Building a string while recording spans of it
+use crate::RcSubstring;
+use std::fmt::{self, Write};
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+A span of an [RcStringBuilder]'s text that becomes an [RcSubstring] once the builder is finished
+
+The text has no `Rc<String>` to share until [RcStringBuilder::finish] is called, so this
+just records where the span is.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingSpan {
+    index: usize,
+    range: Range<usize>,
+}
+
+impl PendingSpan {
+    /// The position of this span in the list returned by [RcStringBuilder::finish]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The byte range of this span in the built text
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The [RcSubstring] for this span given the text returned by [RcStringBuilder::finish]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span doesn't fit `text`, which means it is the text of another builder
+    #[track_caller]
+    pub fn resolve(&self, text: &RcSubstring) -> RcSubstring {
+        text.subslice(self.range())
+    }
+}
+
+/**
+Builds up a string, returning a [PendingSpan] for each piece you want to refer to later
+
+Code generators can keep the spans of what they emit (eg. for diagnostics) and get them
+all back as [RcSubstring]s of the one finished `Rc<String>`.
+Text written through [fmt::Write] is appended without recording a span.
+
+```rust
+# use rcsubstring::RcStringBuilder;
+use std::fmt::Write;
+let mut builder = RcStringBuilder::new();
+builder.push_str("fn ");
+let name = builder.write_span(format_args!("{}_{}", "get", 2));
+write!(builder, "() {{}}").unwrap();
+let (text, spans) = builder.finish();
+assert_eq!(text, "fn get_2() {}");
+assert_eq!(spans[name.index()], "get_2");
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RcStringBuilder {
+    text: String,
+    spans: Vec<Range<usize>>,
+}
+
+impl RcStringBuilder {
+    /// Create an empty builder
+    pub fn new() -> Self {
+        RcStringBuilder::default()
+    }
+
+    /// Create an empty builder with space for `capacity` bytes of text
+    pub fn with_capacity(capacity: usize) -> Self {
+        RcStringBuilder {
+            text: String::with_capacity(capacity),
+            spans: Vec::new(),
+        }
+    }
+
+    /// The text built so far
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// The length of the text built so far, eg. to pass to [RcStringBuilder::span_from] later
+    pub fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    /// True if nothing has been written
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Append `text` and return its span
+    pub fn push_str(&mut self, text: &str) -> PendingSpan {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.span_from(start)
+    }
+
+    /// Append formatted text and return its span
+    ///
+    /// Use with [format_args!], eg. `builder.write_span(format_args!("{name}: {value}"))`
+    pub fn write_span(&mut self, args: fmt::Arguments) -> PendingSpan {
+        let start = self.text.len();
+        self.text
+            .write_fmt(args)
+            .expect("writing to a String doesn't fail");
+        self.span_from(start)
+    }
+
+    /// Record a span from the offset `start` to the end of the text built so far
+    ///
+    /// This gives a span covering several writes, taking `start` from [RcStringBuilder::len]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is past the end of the text or not at a char boundary
+    pub fn span_from(&mut self, start: usize) -> PendingSpan {
+        assert!(
+            self.text.is_char_boundary(start),
+            "start {} is not a char boundary of the RcStringBuilder text",
+            start
+        );
+        let range = start..self.text.len();
+        self.spans.push(range.clone());
+        PendingSpan {
+            index: self.spans.len() - 1,
+            range,
+        }
+    }
+
+    /// Freeze the text into one `Rc<String>`
+    ///
+    /// Returns the whole text and an [RcSubstring] for every span recorded, in the order
+    /// they were recorded (see [PendingSpan::index])
+    pub fn finish(self) -> (RcSubstring, Vec<RcSubstring>) {
+        let text = Rc::new(self.text);
+        let spans = self
+            .spans
+            .into_iter()
+            .map(|range| RcSubstring::new(Rc::clone(&text), range))
+            .collect();
+        (RcSubstring::from(text), spans)
+    }
+}
+
+impl Write for RcStringBuilder {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.text.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spans() {
+        let mut builder = RcStringBuilder::with_capacity(64);
+        assert!(builder.is_empty());
+        let start = builder.len();
+        let key = builder.push_str("key");
+        builder.push_str(" = ");
+        let value = builder.write_span(format_args!("{:?}", "é"));
+        let line = builder.span_from(start);
+        writeln!(builder).unwrap();
+        assert_eq!(builder.as_str(), "key = \"é\"\n");
+        assert_eq!(line.range(), 0..10);
+
+        let (text, spans) = builder.finish();
+        assert_eq!(spans.len(), 4);
+        assert_eq!(spans[key.index()], "key");
+        assert_eq!(value.resolve(&text), "\"é\"");
+        assert_eq!(line.resolve(&text), "key = \"é\"");
+        assert!(Rc::ptr_eq(
+            spans[0].shared().unwrap(),
+            text.shared().unwrap()
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "RcStringBuilder")]
+    fn test_span_from_past_end() {
+        let mut builder = RcStringBuilder::new();
+        builder.push_str("ab");
+        builder.span_from(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of bounds for length 2")]
+    fn test_resolve_other_text() {
+        let mut builder = RcStringBuilder::new();
+        let span = builder.push_str("long");
+        let _ = builder.finish();
+        span.resolve(&RcSubstring::from(String::from("ab")));
+    }
+}
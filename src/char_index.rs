@@ -0,0 +1,155 @@
+//! Converting between char and byte offsets in an [RcSubstring]
+use crate::RcSubstring;
+
+impl RcSubstring {
+    /// The byte offset of the char at `char_idx`, or of the end if it is the number of chars
+    ///
+    /// Returns `None` if there are fewer chars. This scans from the start each time, so use
+    /// a [CharIndex] for repeated queries on long text.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("née"));
+    /// assert_eq!(text.char_to_byte(2), Some(3));
+    /// assert_eq!(text.char_to_byte(3), Some(4));
+    /// assert_eq!(text.char_to_byte(4), None);
+    /// ```
+    pub fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        char_to_byte(self, char_idx)
+    }
+
+    /// The number of chars before `byte_idx`
+    ///
+    /// Returns `None` if `byte_idx` is out of bounds or not on a char boundary.
+    /// This scans from the start each time, so use a [CharIndex] for repeated queries on long text.
+    pub fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        self.is_char_boundary(byte_idx)
+            .then(|| self[..byte_idx].chars().count())
+    }
+
+    /// Build a [CharIndex] for fast conversions between char and byte offsets
+    pub fn char_index(&self) -> CharIndex {
+        CharIndex::new(self.clone())
+    }
+}
+
+// The byte offset of the char at char_idx in text, or of the end
+fn char_to_byte(text: &str, char_idx: usize) -> Option<usize> {
+    match text.char_indices().nth(char_idx) {
+        Some((i, _)) => Some(i),
+        None => (text.chars().count() == char_idx).then_some(text.len()),
+    }
+}
+
+// How many chars apart each remembered offset is
+const STRIDE: usize = 64;
+
+/**
+A mapping between the char and byte offsets of an [RcSubstring]
+
+Remembers the byte offset of every 64th char, so each conversion only has to scan
+at most 64 chars rather than from the start. Offsets are relative to the substring.
+
+```rust
+# use rcsubstring::RcSubstring;
+let text = RcSubstring::from("é".repeat(1000));
+let index = text.char_index();
+assert_eq!(index.char_to_byte(500), Some(1000));
+assert_eq!(index.byte_to_char(1000), Some(500));
+assert_eq!(index.byte_to_char(1001), None);
+assert_eq!(index.char_count(), 1000);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct CharIndex {
+    text: RcSubstring,
+    // Byte offset of every STRIDE'th char
+    checkpoints: Vec<usize>,
+    chars: usize,
+}
+
+impl CharIndex {
+    /// Build the index for `text`
+    pub fn new(text: RcSubstring) -> Self {
+        let mut checkpoints = Vec::with_capacity(text.len() / STRIDE + 1);
+        let mut chars = 0;
+        for (i, _) in text.char_indices() {
+            if chars % STRIDE == 0 {
+                checkpoints.push(i);
+            }
+            chars += 1;
+        }
+        CharIndex {
+            text,
+            checkpoints,
+            chars,
+        }
+    }
+
+    /// The indexed substring
+    pub fn text(&self) -> &RcSubstring {
+        &self.text
+    }
+
+    /// The number of chars in the substring
+    pub fn char_count(&self) -> usize {
+        self.chars
+    }
+
+    /// The byte offset of the char at `char_idx`, or of the end if it is the number of chars
+    pub fn char_to_byte(&self, char_idx: usize) -> Option<usize> {
+        if char_idx == self.chars {
+            return Some(self.text.len());
+        }
+        let start = *self.checkpoints.get(char_idx / STRIDE)?;
+        char_to_byte(&self.text[start..], char_idx % STRIDE).map(|i| start + i)
+    }
+
+    /// The number of chars before `byte_idx`, if it is on a char boundary
+    pub fn byte_to_char(&self, byte_idx: usize) -> Option<usize> {
+        if !self.text.is_char_boundary(byte_idx) {
+            return None;
+        }
+        let k = self
+            .checkpoints
+            .partition_point(|&start| start <= byte_idx)
+            .saturating_sub(1);
+        let start = self.checkpoints.get(k).copied().unwrap_or(0);
+        Some(k * STRIDE + self.text[start..byte_idx].chars().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scanning() {
+        let text = RcSubstring::from(String::from("aé€😀"));
+        let bytes = [0, 1, 3, 6, 10];
+        for (chars, &byte) in bytes.iter().enumerate() {
+            assert_eq!(text.char_to_byte(chars), Some(byte));
+            assert_eq!(text.byte_to_char(byte), Some(chars));
+        }
+        assert_eq!(text.byte_to_char(2), None);
+        assert_eq!(text.byte_to_char(11), None);
+        assert_eq!(text.char_to_byte(5), None);
+    }
+
+    #[test]
+    fn test_index_matches_scanning() {
+        let text = RcSubstring::from("aé€😀".repeat(50));
+        let index = text.char_index();
+        assert_eq!(index.char_count(), 200);
+        for chars in 0..=201 {
+            assert_eq!(index.char_to_byte(chars), text.char_to_byte(chars));
+        }
+        for byte in 0..=text.len() + 1 {
+            assert_eq!(index.byte_to_char(byte), text.byte_to_char(byte));
+        }
+        let empty = RcSubstring::from(String::new()).char_index();
+        assert_eq!(empty.char_to_byte(0), Some(0));
+        assert_eq!(empty.byte_to_char(0), Some(0));
+        assert_eq!(empty.char_to_byte(1), None);
+    }
+}
@@ -0,0 +1,203 @@
+//! Splitting an [RcSubstring] on any of a set of chars
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+use std::rc::Rc;
+
+/**
+A set of chars to split on
+
+ASCII chars are kept in a bitmap so testing them is a couple of instructions;
+any others are kept sorted and binary searched. Cloning is cheap.
+
+```rust
+# use rcsubstring::{CharSet, RcSubstring};
+let separators = CharSet::new(&[',', ';', '|']);
+assert!(separators.contains(';'));
+let text = RcSubstring::from(String::from("a,b;c|d"));
+let fields: Vec<_> = separators.split(&text).collect();
+assert_eq!(fields, ["a", "b", "c", "d"]);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CharSet {
+    ascii: u128,
+    other: Rc<[char]>,
+}
+
+impl CharSet {
+    /// Create a set of the given chars
+    pub fn new(chars: &[char]) -> Self {
+        let mut ascii = 0;
+        let mut other = Vec::new();
+        for &c in chars {
+            if c.is_ascii() {
+                ascii |= 1 << c as u32;
+            } else {
+                other.push(c);
+            }
+        }
+        other.sort_unstable();
+        other.dedup();
+        CharSet {
+            ascii,
+            other: other.into(),
+        }
+    }
+
+    /// True if `c` is in the set
+    pub fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            self.ascii & (1 << c as u32) != 0
+        } else {
+            self.other.binary_search(&c).is_ok()
+        }
+    }
+
+    /// Iterate over the parts of `haystack` separated by any char in the set
+    ///
+    /// As with [str::split], adjacent separators give empty parts.
+    pub fn split(&self, haystack: &RcSubstring) -> SplitAnyChar {
+        SplitAnyChar {
+            set: self.clone(),
+            haystack: haystack.clone(),
+            start: 0,
+            end: haystack.len(),
+            finished: false,
+        }
+    }
+}
+
+impl From<&[char]> for CharSet {
+    fn from(chars: &[char]) -> Self {
+        CharSet::new(chars)
+    }
+}
+
+impl RcSubstring {
+    /// Split this substring on any of `chars`
+    ///
+    /// This builds a new [CharSet] on each call, so use one directly when splitting
+    /// repeatedly on the same chars.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("x=1;y=2,z"));
+    /// let parts: Vec<_> = text.split_any_char_owned(&[';', ',']).collect();
+    /// assert_eq!(parts, ["x=1", "y=2", "z"]);
+    /// ```
+    pub fn split_any_char_owned(&self, chars: &[char]) -> SplitAnyChar {
+        CharSet::new(chars).split(self)
+    }
+}
+
+/// Iterator over the parts of a substring between chars of a [CharSet]
+///
+/// Created by [CharSet::split] or [RcSubstring::split_any_char_owned]
+#[derive(Debug, Clone)]
+pub struct SplitAnyChar {
+    set: CharSet,
+    haystack: RcSubstring,
+    start: usize,
+    end: usize,
+    finished: bool,
+}
+
+impl Iterator for SplitAnyChar {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let rest = &self.haystack[self.start..self.end];
+        match rest.char_indices().find(|&(_, c)| self.set.contains(c)) {
+            Some((i, c)) => {
+                let piece = self.haystack.reslice(self.start..self.start + i);
+                self.start += i + c.len_utf8();
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(self.haystack.reslice(self.start..self.end))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            (0, Some(0))
+        } else {
+            (1, Some(self.end - self.start + 1))
+        }
+    }
+}
+
+impl DoubleEndedIterator for SplitAnyChar {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let rest = &self.haystack[self.start..self.end];
+        match rest.char_indices().rfind(|&(_, c)| self.set.contains(c)) {
+            Some((i, c)) => {
+                let piece = self
+                    .haystack
+                    .reslice(self.start + i + c.len_utf8()..self.end);
+                self.end = self.start + i;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(self.haystack.reslice(self.start..self.end))
+            }
+        }
+    }
+}
+
+impl FusedIterator for SplitAnyChar {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_set() {
+        let set = CharSet::new(&['é', ',', 'é', '\u{7f}', '→']);
+        assert!(set.contains(','));
+        assert!(set.contains('\u{7f}'));
+        assert!(set.contains('é'));
+        assert!(set.contains('→'));
+        assert!(!set.contains('e'));
+        assert!(!set.contains('\0'));
+        assert_eq!(set.other.len(), 2);
+        assert!(!CharSet::default().contains(' '));
+    }
+
+    #[test]
+    fn test_split_matches_str_split() {
+        let chars = [',', 'é', ';'];
+        for text in ["", ",", "a,b", ",aéb;;c,", "no separators"] {
+            let substring = RcSubstring::from(String::from(text));
+            let parts: Vec<_> = substring.split_any_char_owned(&chars).collect();
+            let expected: Vec<_> = text.split(&chars[..]).collect();
+            assert_eq!(parts, expected);
+            let parts: Vec<_> = substring.split_any_char_owned(&chars).rev().collect();
+            let expected: Vec<_> = text.rsplit(&chars[..]).collect();
+            assert_eq!(parts, expected);
+        }
+    }
+
+    #[test]
+    fn test_split_both_ends() {
+        let text = RcSubstring::from(String::from("a,b,c,d"));
+        let mut split = text.split_any_char_owned(&[',']);
+        assert_eq!(split.size_hint(), (1, Some(8)));
+        assert_eq!(split.next().unwrap(), "a");
+        assert_eq!(split.next_back().unwrap(), "d");
+        assert_eq!(split.next_back().unwrap(), "c");
+        assert_eq!(split.next().unwrap(), "b");
+        assert!(split.next().is_none());
+        assert!(split.next_back().is_none());
+        assert_eq!(split.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,162 @@
+//! A `chumsky` span type that carries its source so it can render its own text
+use crate::RcSubstring;
+use ::chumsky::span::Span;
+use std::fmt::{Debug, Display};
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+A span for `chumsky` parsers holding the `Rc<String>` being parsed and a byte range
+
+Use it as the span type of a parse by giving the shared source as the input context.
+Each span can then show the text it covers, so errors can quote the offending input,
+and converts cheaply into an [RcSubstring].
+
+```rust
+# use rcsubstring::{RcSpan, RcSubstring};
+# use std::rc::Rc;
+use chumsky::prelude::*;
+
+let source = Rc::new(String::from("let answer"));
+let parser = text::ascii::ident::<_, extra::Err<Rich<char, RcSpan>>>()
+    .map_with(|_, e| e.span())
+    .padded()
+    .repeated()
+    .collect::<Vec<RcSpan>>();
+let spans = parser
+    .parse(source.as_str().with_context(Rc::clone(&source)))
+    .into_result()
+    .unwrap();
+drop(source);
+assert_eq!(spans[1].to_string(), "answer");
+let word = RcSubstring::from(spans[1].clone());
+assert_eq!(word, "answer");
+```
+*/
+#[derive(Clone, PartialEq, Eq)]
+pub struct RcSpan {
+    source: Rc<String>,
+    range: Range<usize>,
+}
+
+impl RcSpan {
+    /// The shared source this span is in
+    pub fn source(&self) -> &Rc<String> {
+        &self.source
+    }
+
+    /// The byte range of this span in its source
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The text covered by this span
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is not valid for the source
+    pub fn text(&self) -> &str {
+        &self.source[self.range.clone()]
+    }
+}
+
+impl Span for RcSpan {
+    type Context = Rc<String>;
+    type Offset = usize;
+
+    fn new(context: Self::Context, range: Range<Self::Offset>) -> Self {
+        RcSpan {
+            source: context,
+            range,
+        }
+    }
+
+    fn context(&self) -> Self::Context {
+        Rc::clone(&self.source)
+    }
+
+    fn start(&self) -> Self::Offset {
+        self.range.start
+    }
+
+    fn end(&self) -> Self::Offset {
+        self.range.end
+    }
+}
+
+impl Display for RcSpan {
+    // Shows the range instead if it can't slice the source, as this is used in error messages
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.source.get(self.range.clone()) {
+            Some(text) => f.write_str(text),
+            None => write!(f, "<invalid range {:?}>", self.range),
+        }
+    }
+}
+
+impl Debug for RcSpan {
+    // Show the text of the span rather than the whole source
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.source.get(self.range.clone()) {
+            Some(text) => write!(f, "{:?}@{:?}", text, self.range),
+            None => write!(f, "<invalid range>@{:?}", self.range),
+        }
+    }
+}
+
+impl From<RcSpan> for RcSubstring {
+    fn from(span: RcSpan) -> Self {
+        RcSubstring::new(span.source, span.range)
+    }
+}
+
+impl From<RcSubstring> for RcSpan {
+    fn from(substring: RcSubstring) -> Self {
+        let (source, range) = substring.into_shared();
+        RcSpan { source, range }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::chumsky::prelude::*;
+
+    #[test]
+    fn test_error_span() {
+        let source = Rc::new(String::from("1 + x"));
+        let parser = text::int::<_, extra::Err<Rich<char, RcSpan>>>(10)
+            .padded()
+            .separated_by(just('+'))
+            .collect::<Vec<_>>()
+            .then_ignore(end());
+        let errors = parser
+            .parse(source.as_str().with_context(Rc::clone(&source)))
+            .into_errors();
+        assert_eq!(errors.len(), 1);
+        let span = errors[0].span();
+        assert_eq!(span.to_string(), "x");
+        assert_eq!(span.range(), 4..5);
+        assert_eq!(format!("{:?}", span), "\"x\"@4..5");
+    }
+
+    #[test]
+    fn test_invalid_range_is_shown() {
+        let span = <RcSpan as Span>::new(Rc::new(String::from("née")), 0..2);
+        assert_eq!(span.to_string(), "<invalid range 0..2>");
+        assert_eq!(format!("{:?}", span), "<invalid range>@0..2");
+        let past_end = <RcSpan as Span>::new(Rc::new(String::from("x")), 0..5);
+        assert_eq!(format!("{:?}", past_end), "<invalid range>@0..5");
+    }
+
+    #[test]
+    fn test_conversion() {
+        let substring = RcSubstring::new(Rc::new(String::from("some text")), 5..9);
+        let span = RcSpan::from(substring);
+        assert_eq!(span.text(), "text");
+        assert_eq!(span.start(), 5);
+        let substring = RcSubstring::from(span.to_end());
+        assert_eq!(substring, "");
+        assert_eq!(substring.range, 9..9);
+    }
+}
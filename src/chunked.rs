@@ -0,0 +1,284 @@
+//! Reading a stream as a series of shared `Rc<String>` chunks
+use crate::RcSubstring;
+use std::io::{self, ErrorKind, Read};
+use std::iter::FusedIterator;
+use std::mem;
+use std::rc::Rc;
+
+const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/**
+Reads from an [io::Read] a chunk at a time, as `Rc<String>`s
+
+Each chunk is about the chunk size, but ends on a char boundary: the bytes of a char split
+by the chunk size start the next chunk. Use [RcChunkedReader::split_lines] to read lines
+without keeping the whole stream in memory.
+
+```rust
+# use rcsubstring::RcChunkedReader;
+let mut reader = RcChunkedReader::with_chunk_size(&b"one two"[..], 4);
+assert_eq!(reader.next_chunk().unwrap().unwrap().as_str(), "one ");
+assert_eq!(reader.next_chunk().unwrap().unwrap().as_str(), "two");
+assert!(reader.next_chunk().unwrap().is_none());
+```
+*/
+#[derive(Debug)]
+pub struct RcChunkedReader<R> {
+    reader: R,
+    chunk_size: usize,
+    // The start of a char that didn't fit in the last chunk
+    pending: Vec<u8>,
+    done: bool,
+}
+
+impl<R: Read> RcChunkedReader<R> {
+    /// Read `reader` in chunks of 64 KiB
+    pub fn new(reader: R) -> Self {
+        RcChunkedReader::with_chunk_size(reader, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Read `reader` in chunks of `chunk_size` bytes, or just over if a char needs it
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        RcChunkedReader {
+            reader,
+            chunk_size: chunk_size.max(1),
+            pending: Vec::new(),
+            done: false,
+        }
+    }
+
+    /// Read the next chunk, or `None` at the end of the stream
+    ///
+    /// Errors if the reader fails or the data is not valid UTF-8
+    pub fn next_chunk(&mut self) -> io::Result<Option<Rc<String>>> {
+        let mut bytes = mem::take(&mut self.pending);
+        let mut target = self.chunk_size;
+        loop {
+            while !self.done && bytes.len() < target {
+                let start = bytes.len();
+                bytes.resize(target, 0);
+                match self.reader.read(&mut bytes[start..]) {
+                    Ok(n) => {
+                        bytes.truncate(start + n);
+                        self.done = n == 0;
+                    }
+                    Err(error) => {
+                        bytes.truncate(start);
+                        if error.kind() != ErrorKind::Interrupted {
+                            self.pending = bytes;
+                            return Err(error);
+                        }
+                    }
+                }
+            }
+            let tail = incomplete_tail(&bytes);
+            if self.done || tail == 0 {
+                break;
+            }
+            if tail == bytes.len() {
+                // Too short for even one char, so read more of it
+                target = bytes.len() + 1;
+                continue;
+            }
+            self.pending = bytes.split_off(bytes.len() - tail);
+            break;
+        }
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        String::from_utf8(bytes)
+            .map(|text| Some(Rc::new(text)))
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))
+    }
+
+    /// The lines of the stream
+    ///
+    /// Lines are split as for [str::lines]. A line within one chunk is a substring of it,
+    /// while one that spans chunks is copied into a new `Rc<String>`.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcChunkedReader;
+    /// let reader = RcChunkedReader::with_chunk_size(&b"first\nsecond\r\nthird"[..], 8);
+    /// let lines: Vec<_> = reader.split_lines().collect::<Result<_, _>>().unwrap();
+    /// assert_eq!(lines, ["first", "second", "third"]);
+    /// ```
+    pub fn split_lines(self) -> ChunkedLines<R> {
+        ChunkedLines {
+            reader: self,
+            chunk: RcSubstring::from_static(""),
+            partial: None,
+        }
+    }
+
+    /// The underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Consume this returning the underlying reader
+    ///
+    /// Any bytes of a split char that have been read are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for RcChunkedReader<R> {
+    type Item = io::Result<Rc<String>>;
+
+    fn next(&mut self) -> Option<io::Result<Rc<String>>> {
+        self.next_chunk().transpose()
+    }
+}
+
+/**
+An iterator over the lines of a stream read in chunks
+
+Created by [RcChunkedReader::split_lines]
+*/
+#[derive(Debug)]
+pub struct ChunkedLines<R> {
+    reader: RcChunkedReader<R>,
+    // What is left of the current chunk
+    chunk: RcSubstring,
+    // The start of a line that continues into the next chunk
+    partial: Option<String>,
+}
+
+impl<R: Read> Iterator for ChunkedLines<R> {
+    type Item = io::Result<RcSubstring>;
+
+    fn next(&mut self) -> Option<io::Result<RcSubstring>> {
+        loop {
+            if self.chunk.is_empty() {
+                match self.reader.next_chunk() {
+                    Ok(Some(chunk)) => self.chunk = RcSubstring::from(chunk),
+                    Ok(None) => return self.partial.take().map(|line| Ok(RcSubstring::from(line))),
+                    Err(error) => return Some(Err(error)),
+                }
+            }
+            let Some(end) = self.chunk.find('\n') else {
+                self.partial
+                    .get_or_insert_with(String::new)
+                    .push_str(&self.chunk);
+                self.chunk = RcSubstring::from_static("");
+                continue;
+            };
+            let line = self.chunk.reslice(0..end);
+            self.chunk = self.chunk.reslice(end + 1..self.chunk.len());
+            return Some(Ok(match self.partial.take() {
+                Some(partial) => finish_line(partial + &line),
+                None => match line.strip_suffix('\r') {
+                    Some(stripped) => line.reslice(0..stripped.len()),
+                    None => line,
+                },
+            }));
+        }
+    }
+}
+
+impl<R: Read> FusedIterator for ChunkedLines<R> {}
+
+// The number of bytes at the end of bytes that start a char without finishing it
+fn incomplete_tail(bytes: &[u8]) -> usize {
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xc0 == 0x80 {
+            continue;
+        }
+        let len = match byte {
+            0xf0.. => 4,
+            0xe0.. => 3,
+            0xc0.. => 2,
+            _ => 1,
+        };
+        return if len > back { back } else { 0 };
+    }
+    0
+}
+
+// A line copied from across chunks, without the carriage return before its newline
+fn finish_line(mut line: String) -> RcSubstring {
+    if line.ends_with('\r') {
+        line.pop();
+    }
+    RcSubstring::from(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reads one byte at a time, failing once at the given offset
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        fail_at: Option<usize>,
+        pos: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.fail_at == Some(self.pos) {
+                self.fail_at = None;
+                return Err(io::Error::other("fail"));
+            }
+            let Some(&byte) = self.bytes.get(self.pos) else {
+                return Ok(0);
+            };
+            buf[0] = byte;
+            self.pos += 1;
+            Ok(1)
+        }
+    }
+
+    fn chunks(bytes: &[u8], chunk_size: usize) -> Vec<String> {
+        RcChunkedReader::with_chunk_size(bytes, chunk_size)
+            .map(|chunk| chunk.unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_chunks_end_on_char_boundaries() {
+        assert_eq!(chunks("aéb€c".as_bytes(), 2), ["a", "é", "b", "€", "c"]);
+        assert_eq!(chunks("é€😀".as_bytes(), 1), ["é", "€", "😀"]);
+        assert!(chunks(b"", 4).is_empty());
+        let mut reader = RcChunkedReader::with_chunk_size(&b"ab\xffcd"[..], 8);
+        assert_eq!(
+            reader.next().unwrap().unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+        let mut reader = RcChunkedReader::with_chunk_size(&b"ab\xc3"[..], 2);
+        assert_eq!(reader.next().unwrap().unwrap().as_str(), "ab");
+        assert!(reader.next().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_short_reads_and_errors() {
+        let trickle = Trickle {
+            bytes: "abcdé".as_bytes(),
+            fail_at: Some(3),
+            pos: 0,
+        };
+        let mut reader = RcChunkedReader::with_chunk_size(trickle, 4);
+        assert!(reader.next_chunk().is_err());
+        assert_eq!(reader.next_chunk().unwrap().unwrap().as_str(), "abcd");
+        assert_eq!(reader.next_chunk().unwrap().unwrap().as_str(), "é");
+        assert!(reader.next_chunk().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_split_lines() {
+        let text = "short\nspans chunks\r\n\nlast\r";
+        for chunk_size in [1, 3, 6, 7, 100] {
+            let reader = RcChunkedReader::with_chunk_size(text.as_bytes(), chunk_size);
+            let lines: Vec<_> = reader.split_lines().map(Result::unwrap).collect();
+            assert_eq!(lines, text.lines().collect::<Vec<_>>(), "{chunk_size}");
+        }
+        let reader = RcChunkedReader::with_chunk_size(&b"one\ntwo\n"[..], 100);
+        let lines: Vec<_> = reader.split_lines().map(Result::unwrap).collect();
+        assert!(Rc::ptr_eq(
+            lines[0].shared().unwrap(),
+            lines[1].shared().unwrap()
+        ));
+    }
+}
@@ -0,0 +1,69 @@
+//! Colouring the escapes and symbols [RcSubstring](crate::RcSubstring) adapters display
+use anstyle::{AnsiColor, Style};
+use std::fmt::Display;
+use std::io::IsTerminal;
+
+// How escapes and symbols standing in for chars are shown
+const MARKER: Style = Style::new().fg_color(Some(anstyle::Color::Ansi(AnsiColor::Cyan)));
+
+/**
+Whether an adapter such as [ShowInvisibles](crate::ShowInvisibles) colours its output
+
+The choice is passed to each adapter, so different outputs can be coloured differently.
+
+```rust
+# use rcsubstring::{ColorChoice, RcSubstring};
+let text = RcSubstring::from(String::from("a\tb"));
+let shown = text.show_invisibles().color(ColorChoice::Always).to_string();
+assert_eq!(shown, "a\u{1b}[36m→\u{1b}[0mb");
+let plain = text.show_invisibles().color(ColorChoice::Never).to_string();
+assert_eq!(plain, "a→b");
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorChoice {
+    /// Colour if stderr is a terminal and the `NO_COLOR` environment variable isn't set
+    #[default]
+    Auto,
+    /// Always colour, eg. when the output is known to go to a terminal
+    Always,
+    /// Never colour
+    Never,
+}
+
+impl ColorChoice {
+    /// True if output should be coloured
+    ///
+    /// [ColorChoice::Auto] is decided when this is called, so adapters check it once when
+    /// they are created rather than each time they are displayed.
+    pub fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && std::io::stderr().is_terminal()
+            }
+        }
+    }
+}
+
+// Write marker in the marker style
+pub(crate) fn write_marker(
+    f: &mut std::fmt::Formatter<'_>,
+    marker: impl Display,
+) -> std::fmt::Result {
+    write!(f, "{}{}{}", MARKER.render(), marker, MARKER.render_reset())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choices() {
+        assert!(ColorChoice::Always.enabled());
+        assert!(!ColorChoice::Never.enabled());
+        assert_eq!(ColorChoice::default(), ColorChoice::Auto);
+    }
+}
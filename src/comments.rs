@@ -0,0 +1,190 @@
+//! Skipping the comments in code held in an [RcSubstring]
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// Iterate over the parts of this code between comments
+    ///
+    /// `line` starts a comment that runs to the end of the line, and `block` is the start and
+    /// end of comments that can span lines; an empty `line` means there are no line comments.
+    /// If both start at the same place, as `--` and `--[[` do in Lua, the longer marker wins.
+    /// The newline after a line comment is part of the next segment, and empty segments are
+    /// skipped. Comment markers inside string literals are not told apart, and block comments
+    /// don't nest.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let code = RcSubstring::from(String::from("let x = 1; // one\nlet /* why */ y = 2;"));
+    /// let code: Vec<_> = code.without_comments("//", Some(("/*", "*/"))).collect();
+    /// assert_eq!(code, ["let x = 1; ", "\nlet ", " y = 2;"]);
+    /// ```
+    pub fn without_comments<'a>(
+        &self,
+        line: &'a str,
+        block: Option<(&'a str, &'a str)>,
+    ) -> WithoutComments<'a> {
+        WithoutComments {
+            text: self.clone(),
+            line,
+            block,
+            pos: 0,
+        }
+    }
+}
+
+/**
+An iterator over the parts of code between comments
+
+Created by [RcSubstring::without_comments]
+*/
+#[derive(Debug, Clone)]
+pub struct WithoutComments<'a> {
+    text: RcSubstring,
+    line: &'a str,
+    block: Option<(&'a str, &'a str)>,
+    pos: usize,
+}
+
+impl WithoutComments<'_> {
+    // Where the next comment starts and ends, after pos
+    fn next_comment(&self) -> Option<(usize, usize)> {
+        let rest = &self.text[self.pos..];
+        let line = (!self.line.is_empty())
+            .then(|| rest.find(self.line))
+            .flatten();
+        let (block, open_len) = self
+            .block
+            .filter(|(open, _)| !open.is_empty())
+            .map_or((None, 0), |(open, _)| (rest.find(open), open.len()));
+        let (start, end) = match (line, block) {
+            (Some(line), Some(block))
+                if block < line || (block == line && open_len >= self.line.len()) =>
+            {
+                self.block_end(block)
+            }
+            (Some(line), _) => {
+                let after = line + self.line.len();
+                (
+                    line,
+                    rest[after..].find('\n').map_or(rest.len(), |i| after + i),
+                )
+            }
+            (None, Some(block)) => self.block_end(block),
+            (None, None) => return None,
+        };
+        Some((self.pos + start, self.pos + end))
+    }
+
+    // The start and end of a block comment starting at start after pos
+    fn block_end(&self, start: usize) -> (usize, usize) {
+        let rest = &self.text[self.pos..];
+        let (open, close) = self.block.expect("only called for block comments");
+        let after = start + open.len();
+        let end = rest[after..]
+            .find(close)
+            .map_or(rest.len(), |i| after + i + close.len());
+        (start, end)
+    }
+}
+
+impl Iterator for WithoutComments<'_> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        while self.pos < self.text.len() {
+            let start = self.pos;
+            let (end, after) = self
+                .next_comment()
+                .unwrap_or((self.text.len(), self.text.len()));
+            self.pos = after;
+            if end > start {
+                return Some(self.text.reslice(start..end));
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for WithoutComments<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn code(text: &str, line: &str, block: Option<(&str, &str)>) -> Vec<String> {
+        RcSubstring::from(String::from(text))
+            .without_comments(line, block)
+            .map(|part| part.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_line_comments() {
+        assert_eq!(code("a # b\nc", "#", None), ["a ", "\nc"]);
+        assert_eq!(code("# only", "#", None), Vec::<String>::new());
+        assert_eq!(code("a #\n#\nb", "#", None), ["a ", "\n", "\nb"]);
+        assert_eq!(code("no comments", "", None), ["no comments"]);
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let block = Some(("{-", "-}"));
+        assert_eq!(code("a{- x -}b{-y", "--", block), ["a", "b"]);
+        assert_eq!(code("a -- {- x\n-}", "--", block), ["a ", "\n-}"]);
+        assert_eq!(code("{- -- -}b", "--", block), ["b"]);
+    }
+
+    #[test]
+    fn test_overlapping_markers() {
+        let lua = Some(("--[[", "]]"));
+        assert_eq!(
+            code(
+                "a --[[ x
+y ]] b -- c
+d",
+                "--",
+                lua
+            ),
+            [
+                "a ", " b ", "
+d"
+            ]
+        );
+        assert_eq!(
+            code(
+                "a --[ x
+b",
+                "--",
+                lua
+            ),
+            [
+                "a ", "
+b"
+            ]
+        );
+        // A longer line marker wins over a shorter block marker
+        assert_eq!(
+            code(
+                "a ### x
+b #: c :#",
+                "###",
+                Some(("#", "#"))
+            ),
+            [
+                "a ", "
+b "
+            ]
+        );
+    }
+
+    #[test]
+    fn test_segments_share_source() {
+        let source = Rc::new(String::from("x; // c\ny;"));
+        let parts: Vec<_> = RcSubstring::from(Rc::clone(&source))
+            .without_comments("//", None)
+            .collect();
+        assert!(Rc::ptr_eq(parts[1].shared().unwrap(), &source));
+        assert_eq!(parts[1].range, 7..10);
+    }
+}
@@ -0,0 +1,92 @@
+//! Copying substrings out of the large sources they pin
+use crate::RcSubstring;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Copy the text of all of `spans` into one new `Rc<String>` and point them at it
+///
+/// Once every span of a large source has been compacted the source itself can be freed.
+/// Spans of the same source that overlap are copied once, so they still overlap afterwards.
+/// Static substrings don't pin anything and are left as they are.
+///
+/// ```rust
+/// # use rcsubstring::{RcSubstring, compact_all};
+/// # use std::rc::Rc;
+/// let big = Rc::new("x".repeat(10_000) + "keep" + &"y".repeat(10_000));
+/// let mut spans = vec![RcSubstring::new(Rc::clone(&big), 10_000..10_004)];
+/// compact_all(&mut spans);
+/// assert_eq!(spans[0], "keep");
+/// assert_eq!(Rc::strong_count(&big), 1);
+/// ```
+pub fn compact_all(spans: &mut [RcSubstring]) {
+    // The ranges of each source that are needed, by source pointer
+    let mut needed: HashMap<*const String, (Rc<String>, Vec<Range<usize>>)> = HashMap::new();
+    for span in spans.iter() {
+        if let Some(rcstring) = span.shared() {
+            needed
+                .entry(Rc::as_ptr(rcstring))
+                .or_insert_with(|| (Rc::clone(rcstring), Vec::new()))
+                .1
+                .push(span.range.clone());
+        }
+    }
+    // Merge overlapping ranges, copying each merged range into the new text once,
+    // keeping where each ended up
+    let mut text = String::new();
+    let mut copied: HashMap<*const String, Vec<(Range<usize>, usize)>> = HashMap::new();
+    for (ptr, (rcstring, mut ranges)) in needed {
+        ranges.sort_unstable_by_key(|range| range.start);
+        let mut merged: Vec<(Range<usize>, usize)> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some((last, _)) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push((range, 0)),
+            }
+        }
+        for (range, offset) in &mut merged {
+            *offset = text.len();
+            text.push_str(&rcstring[range.clone()]);
+        }
+        copied.insert(ptr, merged);
+    }
+    let text = Rc::new(text);
+    for span in spans.iter_mut() {
+        let Some(rcstring) = span.shared() else {
+            continue;
+        };
+        let merged = &copied[&Rc::as_ptr(rcstring)];
+        let i = merged.partition_point(|(range, _)| range.end < span.range.start);
+        let (range, offset) = &merged[i];
+        let start = offset + span.range.start - range.start;
+        *span = RcSubstring::new(Rc::clone(&text), start..start + span.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_all() {
+        let a = Rc::new(String::from("0123456789"));
+        let b = Rc::new(String::from("abcdef"));
+        let mut spans = vec![
+            RcSubstring::new(Rc::clone(&a), 6..9),
+            RcSubstring::new(Rc::clone(&b), 1..3),
+            RcSubstring::new(Rc::clone(&a), 2..4),
+            RcSubstring::new(Rc::clone(&a), 3..7),
+            RcSubstring::new(Rc::clone(&a), 9..9),
+            RcSubstring::from_static("static"),
+        ];
+        compact_all(&mut spans);
+        assert_eq!(Rc::strong_count(&a), 1);
+        assert_eq!(Rc::strong_count(&b), 1);
+        let text: Vec<_> = spans.iter().map(|span| span.to_string()).collect();
+        assert_eq!(text, ["678", "bc", "23", "3456", "", "static"]);
+        let new = spans[0].shared().unwrap();
+        assert_eq!(new.len(), "2345678".len() + "bc".len());
+        assert!(Rc::ptr_eq(new, spans[1].shared().unwrap()));
+        assert!(spans[5].shared().is_none());
+    }
+}
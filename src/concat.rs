@@ -0,0 +1,321 @@
+//! Joining [RcSubstring]s together with `+`, `sum` and `collect`, or into runs
+use crate::RcSubstring;
+use std::borrow::Borrow;
+use std::iter::{FusedIterator, Sum};
+use std::ops::Add;
+use std::rc::Rc;
+
+impl RcSubstring {
+    /// Merge runs of substrings that each directly follow the one before in the same text
+    ///
+    /// Each run is yielded as one substring covering it, without copying. A gap, or a piece
+    /// of another text, ends the run. Empty pieces are skipped. This gives the contiguous
+    /// chunks that are left after filtering out some tokens.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("a = b; /* c */ d;"));
+    /// let tokens = [0..1, 1..2, 2..3, 3..4, 4..5, 5..6, 6..7, 7..14, 14..15, 15..16, 16..17];
+    /// let kept = tokens
+    ///     .into_iter()
+    ///     .map(|range| text.subslice(range))
+    ///     .filter(|token| !token.starts_with("/*"));
+    /// let chunks: Vec<_> = RcSubstring::concat_adjacent_iter(kept).collect();
+    /// assert_eq!(chunks, ["a = b; ", " d;"]);
+    /// ```
+    pub fn concat_adjacent_iter<I: IntoIterator<Item = RcSubstring>>(
+        pieces: I,
+    ) -> ConcatAdjacent<I::IntoIter> {
+        ConcatAdjacent {
+            pieces: pieces.into_iter(),
+            run: None,
+        }
+    }
+
+    // True if other directly follows this in the same text
+    fn is_followed_by(&self, other: &RcSubstring) -> bool {
+        self.rcstring.same_text(&other.rcstring) && self.range.end == other.range.start
+    }
+
+    // True if other follows this in the same text with just separator between them
+    fn is_separated_from(&self, separator: &str, other: &RcSubstring) -> bool {
+        self.rcstring.same_text(&other.rcstring)
+            && self.range.end + separator.len() == other.range.start
+            && self.rcstring.as_str()[self.range.end..other.range.start] == *separator
+    }
+
+    // This followed by other, without copying if other directly follows this in the same text
+    fn concat(&self, other: &RcSubstring) -> RcSubstring {
+        if self.is_followed_by(other) {
+            let mut joined = self.clone();
+            joined.set_range(self.range.start..other.range.end);
+            return joined;
+        }
+        self.concat_str(other)
+    }
+
+    // This followed by other in a new Rc<String>, unless one of them is empty
+    fn concat_str(&self, other: &str) -> RcSubstring {
+        if other.is_empty() {
+            return self.clone();
+        }
+        let mut text = String::with_capacity(self.len() + other.len());
+        text.push_str(self);
+        text.push_str(other);
+        RcSubstring::from(Rc::new(text))
+    }
+}
+
+impl Add<&RcSubstring> for RcSubstring {
+    type Output = RcSubstring;
+
+    /// Join two substrings
+    ///
+    /// If `rhs` directly follows `self` in the same text the result just covers both,
+    /// otherwise their text is copied into a new `Rc<String>`.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let source = Rc::new(String::from("key=value"));
+    /// let key = RcSubstring::new(Rc::clone(&source), 0..3);
+    /// let rest = RcSubstring::new(Rc::clone(&source), 3..9);
+    /// let both = key.clone() + &rest;
+    /// assert_eq!(both, "key=value");
+    /// assert_eq!(Rc::strong_count(&source), 4);
+    /// assert_eq!(rest + &key, "=valuekey");
+    /// ```
+    fn add(self, rhs: &RcSubstring) -> RcSubstring {
+        if self.is_empty() {
+            return rhs.clone();
+        }
+        self.concat(rhs)
+    }
+}
+
+impl Add for RcSubstring {
+    type Output = RcSubstring;
+
+    /// Join two substrings, as for `RcSubstring + &RcSubstring`
+    fn add(self, rhs: RcSubstring) -> RcSubstring {
+        self + &rhs
+    }
+}
+
+impl Add<&str> for RcSubstring {
+    type Output = RcSubstring;
+
+    /// Append a `&str`, copying both into a new `Rc<String>` unless `rhs` is empty
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let name = RcSubstring::from(String::from("main"));
+    /// assert_eq!(name + ".rs", "main.rs");
+    /// ```
+    fn add(self, rhs: &str) -> RcSubstring {
+        self.concat_str(rhs)
+    }
+}
+
+impl<'a> Sum<&'a RcSubstring> for RcSubstring {
+    /// Join all the substrings
+    ///
+    /// While each directly follows the one before in the same text the result just covers
+    /// them, otherwise all the text is copied once into a new `Rc<String>`.
+    /// The sum of no substrings is empty.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let source = Rc::new(String::from("a b c"));
+    /// let words: Vec<_> = RcSubstring::from(Rc::clone(&source)).split_owned(" ").collect();
+    /// let joined: RcSubstring = words.iter().sum();
+    /// assert_eq!(joined, "abc");
+    /// ```
+    fn sum<I: Iterator<Item = &'a RcSubstring>>(iter: I) -> Self {
+        join(iter, "")
+    }
+}
+
+impl Sum for RcSubstring {
+    /// Join all the substrings, as for summing `&RcSubstring`s
+    fn sum<I: Iterator<Item = RcSubstring>>(iter: I) -> Self {
+        join(iter, "")
+    }
+}
+
+// Join the pieces with separator between each, copying them only once one doesn't follow
+// the one before with just the separator between them in the same text
+pub(crate) fn join(
+    pieces: impl Iterator<Item = impl Borrow<RcSubstring>>,
+    separator: &str,
+) -> RcSubstring {
+    let mut joined: Option<RcSubstring> = None;
+    let mut copied: Option<String> = None;
+    for piece in pieces {
+        let piece = piece.borrow();
+        if let Some(text) = &mut copied {
+            text.push_str(separator);
+            text.push_str(piece);
+            continue;
+        }
+        joined = Some(match joined {
+            None => piece.clone(),
+            Some(mut so_far) if so_far.is_separated_from(separator, piece) => {
+                so_far.set_range(so_far.range.start..piece.range.end);
+                so_far
+            }
+            Some(so_far) => {
+                copied = Some(String::from(&*so_far) + separator + piece);
+                so_far
+            }
+        });
+    }
+    match (copied, joined) {
+        (Some(text), _) => RcSubstring::from(text),
+        (None, Some(joined)) => joined,
+        (None, None) => RcSubstring::from_static(""),
+    }
+}
+
+/**
+Iterator over the runs of adjacent substrings from another iterator, each merged into one
+
+Created by [RcSubstring::concat_adjacent_iter]
+*/
+#[derive(Debug, Clone)]
+pub struct ConcatAdjacent<I> {
+    pieces: I,
+    // The run so far
+    run: Option<RcSubstring>,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for ConcatAdjacent<I> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for piece in self.pieces.by_ref() {
+            if piece.is_empty() {
+                continue;
+            }
+            match &mut self.run {
+                Some(run) if run.is_followed_by(&piece) => {
+                    run.set_range(run.range.start..piece.range.end);
+                }
+                Some(run) => return Some(std::mem::replace(run, piece)),
+                None => self.run = Some(piece),
+            }
+        }
+        self.run.take()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, most) = self.pieces.size_hint();
+        let run = self.run.is_some() as usize;
+        (run, most.and_then(|most| most.checked_add(run)))
+    }
+}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for ConcatAdjacent<I> {}
+
+impl FromIterator<char> for RcSubstring {
+    /// Collect the chars into a new `Rc<String>`
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        RcSubstring::from(String::from_iter(iter))
+    }
+}
+
+impl<'a> FromIterator<&'a str> for RcSubstring {
+    /// Collect the strs into a new `Rc<String>`
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text: RcSubstring = ["a", "b", "c"].into_iter().collect();
+    /// assert_eq!(text, "abc");
+    /// ```
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        RcSubstring::from(String::from_iter(iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adjacent_shares_source() {
+        let source = Rc::new(String::from("abcdef"));
+        let ab = RcSubstring::new(Rc::clone(&source), 0..2);
+        let cd = RcSubstring::new(Rc::clone(&source), 2..4);
+        let abcd = ab.clone() + cd.clone();
+        assert!(Rc::ptr_eq(abcd.shared().unwrap(), &source));
+        assert_eq!(abcd.range, 0..4);
+        let text = RcSubstring::from_static("static");
+        let joined = text.reslice(0..3) + text.reslice(3..6);
+        assert!(joined.shared().is_none());
+        assert_eq!(joined, "static");
+    }
+
+    #[test]
+    fn test_copies_otherwise() {
+        let source = Rc::new(String::from("abcdef"));
+        let ab = RcSubstring::new(Rc::clone(&source), 0..2);
+        let ef = RcSubstring::new(Rc::clone(&source), 4..6);
+        let abef = ab.clone() + &ef;
+        assert_eq!(abef, "abef");
+        assert!(!Rc::ptr_eq(abef.shared().unwrap(), &source));
+        let other = RcSubstring::from(String::from("cd"));
+        assert_eq!(ab.clone() + other, "abcd");
+        assert_eq!(ab.clone() + "", "ab");
+        assert!(Rc::ptr_eq((ab.clone() + "").shared().unwrap(), &source));
+        let empty = RcSubstring::new(Rc::new(String::new()), 0..0);
+        assert!(Rc::ptr_eq((empty + &ef).shared().unwrap(), &source));
+    }
+
+    #[test]
+    fn test_sum() {
+        let source = Rc::new(String::from("abcdef"));
+        let piece = |range| RcSubstring::new(Rc::clone(&source), range);
+        let adjacent: RcSubstring = [piece(1..2), piece(2..2), piece(2..5)].into_iter().sum();
+        assert!(Rc::ptr_eq(adjacent.shared().unwrap(), &source));
+        assert_eq!(adjacent.range, 1..5);
+        let gaps: RcSubstring = [piece(0..1), piece(1..2), piece(3..4), piece(4..6)]
+            .iter()
+            .sum();
+        assert_eq!(gaps, "abdef");
+        assert!(!gaps.rcstring.same_text(&adjacent.rcstring));
+        assert_eq!(std::iter::empty::<RcSubstring>().sum::<RcSubstring>(), "");
+    }
+
+    #[test]
+    fn test_collect() {
+        let text: RcSubstring = "héllo".chars().rev().collect();
+        assert_eq!(text, "olléh");
+        let text: RcSubstring = "a-b-c".split('-').collect();
+        assert_eq!(text, "abc");
+    }
+
+    #[test]
+    fn test_concat_adjacent_iter() {
+        let source = Rc::new(String::from("abcdef"));
+        let piece = |range| RcSubstring::new(Rc::clone(&source), range);
+        let other = RcSubstring::from(String::from("xy"));
+        let runs: Vec<_> = RcSubstring::concat_adjacent_iter([
+            piece(0..1),
+            piece(1..2),
+            piece(4..4),
+            piece(2..3),
+            piece(4..5),
+            other.reslice(0..1),
+            other.reslice(1..2),
+            piece(5..6),
+        ])
+        .collect();
+        assert_eq!(runs, ["abc", "e", "xy", "f"]);
+        assert_eq!(runs[0].range, 0..3);
+        assert!(Rc::ptr_eq(runs[0].shared().unwrap(), &source));
+        assert!(runs[2].rcstring.same_text(&other.rcstring));
+        assert_eq!(runs[2].range, 0..2);
+        assert_eq!(RcSubstring::concat_adjacent_iter([piece(2..2)]).count(), 0);
+    }
+}
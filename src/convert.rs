@@ -0,0 +1,152 @@
+//! Conversion of any kind of text into an [RcSubstring], and of one into a [Cow]
+use crate::{Backing, RcSubstring, SharedCow};
+use std::borrow::Cow;
+use std::rc::Rc;
+
+/**
+Conversion into an [RcSubstring], copying the text only if it is borrowed
+
+Functions can take `impl IntoRcSubstring` so that callers can pass whatever text they have.
+Unlike [SharedCow] the conversion happens straight away, so use that instead when the text
+may not need to be kept.
+
+```rust
+# use rcsubstring::{IntoRcSubstring, RcSubstring};
+# use std::rc::Rc;
+fn label(text: impl IntoRcSubstring) -> RcSubstring {
+    text.into_rc_substring()
+}
+assert_eq!(label("borrowed"), "borrowed");
+assert_eq!(label(String::from("owned")), "owned");
+assert_eq!(label(Rc::new(String::from("shared"))), "shared");
+```
+*/
+pub trait IntoRcSubstring {
+    /// Convert into an [RcSubstring]
+    fn into_rc_substring(self) -> RcSubstring;
+}
+
+impl IntoRcSubstring for RcSubstring {
+    fn into_rc_substring(self) -> RcSubstring {
+        self
+    }
+}
+
+impl IntoRcSubstring for &RcSubstring {
+    fn into_rc_substring(self) -> RcSubstring {
+        self.clone()
+    }
+}
+
+impl IntoRcSubstring for Rc<String> {
+    fn into_rc_substring(self) -> RcSubstring {
+        RcSubstring::from(self)
+    }
+}
+
+impl IntoRcSubstring for String {
+    fn into_rc_substring(self) -> RcSubstring {
+        RcSubstring::from(self)
+    }
+}
+
+/// Copies the text into a new `Rc<String>`; use [RcSubstring::from_static] for constants
+///
+/// With the `inline` feature short text is copied into the RcSubstring instead, without
+/// allocating.
+impl IntoRcSubstring for &str {
+    fn into_rc_substring(self) -> RcSubstring {
+        #[cfg(feature = "inline")]
+        if let Some(text) = crate::inline::InlineText::new(self) {
+            return RcSubstring::from_parts(Backing::Inline(text), 0..self.len());
+        }
+        RcSubstring::from(String::from(self))
+    }
+}
+
+impl IntoRcSubstring for Cow<'_, str> {
+    fn into_rc_substring(self) -> RcSubstring {
+        RcSubstring::from(self.into_owned())
+    }
+}
+
+impl IntoRcSubstring for SharedCow<'_> {
+    fn into_rc_substring(self) -> RcSubstring {
+        self.into_shared()
+    }
+}
+
+impl RcSubstring {
+    /// The text borrowed as a [Cow], for APIs that take one
+    pub fn to_cow(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+
+    /// Convert into a [Cow] that doesn't borrow from anything, copying only when needed
+    ///
+    /// Static substrings are borrowed, and a substring of the whole of an `Rc<String>` that
+    /// nothing else shares takes the `String` out of it. Otherwise the text is copied.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::borrow::Cow;
+    /// let constant = RcSubstring::from_static("constant");
+    /// assert!(matches!(constant.into_cow_static(), Cow::Borrowed("constant")));
+    /// let owned: Cow<'static, str> = RcSubstring::from(String::from("owned")).into();
+    /// assert_eq!(owned, "owned");
+    /// ```
+    pub fn into_cow_static(self) -> Cow<'static, str> {
+        match self.rcstring {
+            Backing::Static(text) => Cow::Borrowed(&text[self.range]),
+            Backing::Shared(rcstring) if self.range == (0..rcstring.len()) => {
+                Cow::Owned(Rc::try_unwrap(rcstring).unwrap_or_else(|rcstring| (*rcstring).clone()))
+            }
+            backing => Cow::Owned(String::from(&backing.as_str()[self.range])),
+        }
+    }
+}
+
+impl From<RcSubstring> for Cow<'static, str> {
+    /// Convert as by [RcSubstring::into_cow_static]
+    fn from(substring: RcSubstring) -> Self {
+        substring.into_cow_static()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_share_when_possible() {
+        let source = Rc::new(String::from("text"));
+        let substring = Rc::clone(&source).into_rc_substring();
+        assert!(Rc::ptr_eq(substring.shared().unwrap(), &source));
+        assert!(Rc::ptr_eq(
+            (&substring).into_rc_substring().shared().unwrap(),
+            &source
+        ));
+        let shared = SharedCow::from(substring).into_rc_substring();
+        assert!(Rc::ptr_eq(shared.shared().unwrap(), &source));
+        assert_eq!(Cow::Borrowed("cow").into_rc_substring(), "cow");
+        assert_eq!("str".into_rc_substring(), "str");
+    }
+
+    #[test]
+    fn test_into_cow() {
+        let source = Rc::new(String::from("text"));
+        let whole = RcSubstring::from(Rc::clone(&source));
+        assert!(matches!(whole.to_cow(), Cow::Borrowed("text")));
+        let part = whole.reslice(1..3);
+        assert_eq!(part.into_cow_static(), "ex");
+        drop(source);
+        let address = whole.as_ptr();
+        match whole.into_cow_static() {
+            Cow::Owned(string) => assert_eq!(string.as_ptr(), address),
+            Cow::Borrowed(_) => panic!("should own the string"),
+        }
+        let shared = RcSubstring::from(String::from("shared"));
+        let _keep = shared.clone();
+        assert_eq!(Cow::from(shared), "shared");
+    }
+}
@@ -0,0 +1,175 @@
+//! Splitting CSV-like text into records, where quoted fields can span lines
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// Split this substring into records of fields separated by `delimiter`, as in CSV
+    ///
+    /// Records end at `\n` or `\r\n`, except inside a field in double quotes, so a record can
+    /// span several lines. The quotes are not part of the field, and a field with a doubled
+    /// `""` inside has it copied to unescape it; other fields are substrings of the source.
+    /// A field is only quoted if it starts with `"`. Anything after the closing quote up to
+    /// the next delimiter is ignored, and an unclosed quote runs to the end. Blank lines are
+    /// skipped.
+    ///
+    /// For records that are known to be one per line, [RcSubstring::lines_owned] with
+    /// [RcSubstring::fields_sep] is simpler.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let csv = RcSubstring::from(String::from(
+    ///     "id,comment\n1,\"two\nlines\"\n2,\"say \"\"hi\"\"\"\n",
+    /// ));
+    /// let records: Vec<_> = csv.split_quoted_csvish(',').collect();
+    /// assert_eq!(records.len(), 3);
+    /// assert_eq!(records[1], ["1", "two\nlines"]);
+    /// assert_eq!(records[2], ["2", "say \"hi\""]);
+    /// ```
+    pub fn split_quoted_csvish(&self, delimiter: char) -> QuotedRecords {
+        QuotedRecords {
+            text: self.clone(),
+            delimiter,
+            pos: 0,
+        }
+    }
+}
+
+/**
+Iterator over the records of CSV-like text, each a `Vec` of fields
+
+Created by [RcSubstring::split_quoted_csvish]
+*/
+#[derive(Debug, Clone)]
+pub struct QuotedRecords {
+    text: RcSubstring,
+    delimiter: char,
+    // Where the next record starts
+    pos: usize,
+}
+
+impl QuotedRecords {
+    // Read the field at self.pos, leaving self.pos at the delimiter or line end after it
+    fn field(&mut self) -> RcSubstring {
+        let rest = &self.text[self.pos..];
+        if !rest.starts_with('"') {
+            let len = self.field_end(rest);
+            let mut end = self.pos + len;
+            if rest[len..].starts_with('\n') && rest[..len].ends_with('\r') {
+                end -= 1;
+            }
+            let field = self.text.reslice(self.pos..end);
+            self.pos += len;
+            return field;
+        }
+
+        let content = self.pos + 1;
+        let mut escaped = false;
+        let mut search = content;
+        let close = loop {
+            match self.text[search..].find('"') {
+                Some(i) if self.text[search + i + 1..].starts_with('"') => {
+                    escaped = true;
+                    search += i + 2;
+                }
+                Some(i) => break search + i,
+                None => break self.text.len(),
+            }
+        };
+        let field = if escaped {
+            RcSubstring::from(self.text[content..close].replace("\"\"", "\""))
+        } else {
+            self.text.reslice(content..close)
+        };
+        let after = (close + 1).min(self.text.len());
+        self.pos = after + self.field_end(&self.text[after..]);
+        field
+    }
+
+    // The length of the start of rest before the next delimiter or \n
+    fn field_end(&self, rest: &str) -> usize {
+        rest.find([self.delimiter, '\n']).unwrap_or(rest.len())
+    }
+}
+
+impl Iterator for QuotedRecords {
+    type Item = Vec<RcSubstring>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = &self.text[self.pos..];
+            if rest.is_empty() {
+                return None;
+            }
+            match rest.strip_prefix('\r').unwrap_or(rest).strip_prefix('\n') {
+                Some(after) => self.pos = self.text.len() - after.len(),
+                None => break,
+            }
+        }
+        let mut record = Vec::new();
+        loop {
+            record.push(self.field());
+            let Some(c) = self.text[self.pos..].chars().next() else {
+                return Some(record);
+            };
+            self.pos += c.len_utf8();
+            if c == '\n' {
+                return Some(record);
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // What is left could be only blank lines, which give no records
+        (0, Some(self.text.len() - self.pos))
+    }
+}
+
+impl FusedIterator for QuotedRecords {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn records(text: &str, delimiter: char) -> Vec<Vec<RcSubstring>> {
+        RcSubstring::from(String::from(text))
+            .split_quoted_csvish(delimiter)
+            .collect()
+    }
+
+    #[test]
+    fn test_records() {
+        assert_eq!(
+            records("a,b\r\n\r\n\nc,,\n,\"\"\n", ','),
+            [vec!["a", "b"], vec!["c", "", ""], vec!["", ""]]
+        );
+        assert_eq!(records("", ','), Vec::<Vec<RcSubstring>>::new());
+        assert_eq!(records("\n\n", ','), Vec::<Vec<RcSubstring>>::new());
+        assert_eq!(records("é;\"a;b\";c", ';'), [vec!["é", "a;b", "c"]]);
+        assert_eq!(records("a,", ','), [vec!["a", ""]]);
+    }
+
+    #[test]
+    fn test_blank_lines_only() {
+        let mut blank = RcSubstring::from(String::from("\n\r\n")).split_quoted_csvish(',');
+        assert_eq!(blank.size_hint(), (0, Some(3)));
+        assert_eq!(blank.next(), None);
+        assert_eq!(blank.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        assert_eq!(
+            records("\"x\r\ny\"\r\n\"a\"\"\"stray,\"unclosed\n", ','),
+            [vec!["x\r\ny"], vec!["a\"", "unclosed\n"]]
+        );
+        let source = Rc::new(String::from("\"shared\",copied\"\""));
+        let record = RcSubstring::from(Rc::clone(&source))
+            .split_quoted_csvish(',')
+            .next()
+            .unwrap();
+        assert!(Rc::ptr_eq(record[0].shared().unwrap(), &source));
+        assert_eq!(record[0].range, 1..7);
+        assert_eq!(record[1], "copied\"\"");
+    }
+}
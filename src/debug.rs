@@ -0,0 +1,196 @@
+//! `Debug` output for [RcSubstring] that stays readable for long sources
+use crate::storage::sealed::Text;
+use crate::{RcSubstring, StringStorage, Substring};
+use std::fmt::{self, Debug};
+
+// How many bytes of the source to show before cutting it short
+const SOURCE_PREVIEW: usize = 64;
+
+impl<S: StringStorage> Debug for Substring<S> {
+    /// Shows the text, its range and the length of the source, with the start of the source
+    ///
+    /// Sources longer than 64 bytes are cut short with `…`. Use [RcSubstring::debug_full]
+    /// to show all of the source.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let text = RcSubstring::new(Rc::new("x".repeat(100)), 0..2);
+    /// let debug = format!("{:?}", text);
+    /// assert!(debug.starts_with(r#"RcSubstring { text: "xx", range: 0..2, source_len: 100, "#));
+    /// assert!(debug.ends_with(r#"xxx"… }"#));
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = self.rcstring.as_str();
+        let mut debug = f.debug_struct(S::TYPE_NAME);
+        debug
+            .field("text", &SubstringText(source.get(self.range.clone())))
+            .field("range", &self.range)
+            .field("source_len", &source.len())
+            .field("source", &SourcePreview(source));
+        #[cfg(feature = "provenance")]
+        if let Some(label) = self.label {
+            debug
+                .field("label", &label)
+                .field("created_at", &format_args!("{}", self.created_at));
+        }
+        debug.finish()
+    }
+}
+
+// The text of a substring for Debug output, which mustn't panic if the range is invalid
+struct SubstringText<'a>(Option<&'a str>);
+
+impl Debug for SubstringText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(text) => Debug::fmt(text, f),
+            None => f.write_str("<invalid range>"),
+        }
+    }
+}
+
+// The start of a source for Debug output
+struct SourcePreview<'a>(&'a str);
+
+impl Debug for SourcePreview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.len() <= SOURCE_PREVIEW {
+            return Debug::fmt(self.0, f);
+        }
+        Debug::fmt(&self.0[..self.0.floor_char_boundary(SOURCE_PREVIEW)], f)?;
+        f.write_str("…")
+    }
+}
+
+impl RcSubstring {
+    /// Debug output with all of the source, as before it was cut short
+    ///
+    /// Useful for tests that compare the older output.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("abc"));
+    /// assert_eq!(
+    ///     format!("{:?}", text.debug_full()),
+    ///     r#"RcSubstring { rcstring: "abc", range: 0..3 }"#
+    /// );
+    /// ```
+    pub fn debug_full(&self) -> DebugFull<'_> {
+        DebugFull { substring: self }
+    }
+
+    /// Compact Debug output of the text and range, such as `"main"[3..7 of 12]`
+    ///
+    /// The source isn't shown and nothing depends on where the substring was created, so
+    /// this stays the same between runs, for snapshot tests of ASTs full of spans.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let name = RcSubstring::new(Rc::new(String::from("fn main() {}")), 3..7);
+    /// assert_eq!(format!("{:?}", name.debug_span()), r#""main"[3..7 of 12]"#);
+    /// ```
+    pub fn debug_span(&self) -> DebugSpan<'_> {
+        DebugSpan { substring: self }
+    }
+}
+
+/**
+Debug output of an [RcSubstring] with all of its source
+
+Created by [RcSubstring::debug_full]
+*/
+#[derive(Clone, Copy)]
+pub struct DebugFull<'a> {
+    substring: &'a RcSubstring,
+}
+
+impl Debug for DebugFull<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RcSubstring")
+            .field("rcstring", &self.substring.rcstring)
+            .field("range", &self.substring.range)
+            .finish()
+    }
+}
+
+/**
+Compact Debug output of an [RcSubstring]'s text and range, without its source
+
+Created by [RcSubstring::debug_span]
+*/
+#[derive(Clone, Copy)]
+pub struct DebugSpan<'a> {
+    substring: &'a RcSubstring,
+}
+
+impl Debug for DebugSpan<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = &self.substring.range;
+        let source = self.substring.rcstring.as_str();
+        Debug::fmt(&SubstringText(source.get(range.clone())), f)?;
+        write!(f, "[{}..{} of {}]", range.start, range.end, source.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_long_source_is_cut_short() {
+        let source = Rc::new("é".repeat(40));
+        let text = RcSubstring::new(Rc::clone(&source), 78..80);
+        let preview = "é".repeat(32);
+        assert_eq!(
+            format!("{:?}", text),
+            format!(
+                r#"RcSubstring {{ text: "é", range: 78..80, source_len: 80, source: "{preview}"… }}"#
+            )
+        );
+        assert_eq!(
+            format!("{:#?}", text),
+            format!(
+                "RcSubstring {{\n    text: \"é\",\n    range: 78..80,\n    source_len: 80,\n    source: \"{preview}\"…,\n}}"
+            )
+        );
+        let full = format!("{:?}", text.debug_full());
+        assert!(full.contains(source.as_str()));
+    }
+
+    #[test]
+    fn test_debug_span() {
+        let source = RcSubstring::from(String::from("say \"hé\"\n"));
+        let quoted = source.reslice(4..9);
+        assert_eq!(
+            format!("{:?}", quoted.debug_span()),
+            r#""\"hé\""[4..9 of 10]"#
+        );
+        assert_eq!(
+            format!("{:#?}", vec![source.reslice(0..0).debug_span()]),
+            "[\n    \"\"[0..0 of 10],\n]"
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "strict-validation"))]
+    fn test_invalid_range_is_shown() {
+        let text = RcSubstring::new(Rc::new("née".into()), 0..2);
+        let debug = format!("{:?}", text);
+        assert!(debug.starts_with("RcSubstring { text: <invalid range>, range: 0..2, "));
+        assert_eq!(
+            format!("{:?}", text.debug_span()),
+            "<invalid range>[0..2 of 4]"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "provenance")]
+    fn test_label_is_shown() {
+        let text = RcSubstring::labeled("lexer", Rc::new(String::from("ab")), 0..1);
+        let debug = format!("{:?}", text);
+        assert!(debug.contains(r#"source: "ab", label: "lexer", created_at: src/debug.rs:"#));
+    }
+}
@@ -0,0 +1,154 @@
+//! Picking balanced delimited groups out of an [RcSubstring]
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+impl RcSubstring {
+    /// The text inside the first balanced `open` ... `close` pair, and the text after it
+    ///
+    /// Nested pairs are part of the content. Returns `None` if there is no `open`, or no
+    /// `close` to balance it. If `open` and `close` are the same char pairs can't nest,
+    /// so this finds the text between the first two.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let expr = RcSubstring::from(String::from("(add (mul 2 3) 4) rest"));
+    /// let (inside, rest) = expr.take_delimited('(', ')').unwrap();
+    /// assert_eq!(inside, "add (mul 2 3) 4");
+    /// assert_eq!(rest, " rest");
+    /// ```
+    pub fn take_delimited(&self, open: char, close: char) -> Option<(RcSubstring, RcSubstring)> {
+        let (inside, end) = find_delimited(self, 0, open, close)?;
+        Some((self.reslice(inside), self.reslice(end..self.len())))
+    }
+
+    /// Iterate over the text inside each top-level balanced `open` ... `close` pair
+    ///
+    /// Text outside the pairs is skipped. Stops at an `open` that is never closed.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let template = RcSubstring::from(String::from("Hi {name}, you have {count {n}}"));
+    /// let groups: Vec<_> = template.delimited_groups('{', '}').collect();
+    /// assert_eq!(groups, ["name", "count {n}"]);
+    /// ```
+    pub fn delimited_groups(&self, open: char, close: char) -> DelimitedGroups {
+        DelimitedGroups {
+            text: self.clone(),
+            open,
+            close,
+            pos: 0,
+        }
+    }
+}
+
+// The range inside the first balanced pair in text at or after start, and where the pair ends
+fn find_delimited(
+    text: &str,
+    start: usize,
+    open: char,
+    close: char,
+) -> Option<(Range<usize>, usize)> {
+    let from = start + text[start..].find(open)?;
+    let content = from + open.len_utf8();
+    let mut depth = 1;
+    for (i, c) in text[content..].char_indices() {
+        // Check for close first so that pairs of the same char don't nest
+        if c == close {
+            depth -= 1;
+            if depth == 0 {
+                let end = content + i;
+                return Some((content..end, end + close.len_utf8()));
+            }
+        } else if c == open {
+            depth += 1;
+        }
+    }
+    None
+}
+
+/**
+An iterator over the text inside each top-level balanced pair of delimiters
+
+Created by [RcSubstring::delimited_groups]
+*/
+#[derive(Debug, Clone)]
+pub struct DelimitedGroups {
+    text: RcSubstring,
+    open: char,
+    close: char,
+    pos: usize,
+}
+
+impl Iterator for DelimitedGroups {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        match find_delimited(&self.text, self.pos, self.open, self.close) {
+            Some((inside, end)) => {
+                self.pos = end;
+                Some(self.text.reslice(inside))
+            }
+            None => {
+                self.pos = self.text.len();
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.open.len_utf8() + self.close.len_utf8();
+        (0, Some((self.text.len() - self.pos) / len))
+    }
+}
+
+impl FusedIterator for DelimitedGroups {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_take_delimited() {
+        let source = Rc::new(String::from("x «a «b» c» y"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        let (inside, rest) = text.take_delimited('«', '»').unwrap();
+        assert_eq!(inside, "a «b» c");
+        assert!(Rc::ptr_eq(inside.shared().unwrap(), &source));
+        assert_eq!(rest, " y");
+        let (quoted, rest) = RcSubstring::from(String::from(r#"say "hi" "there""#))
+            .take_delimited('"', '"')
+            .unwrap();
+        assert_eq!(quoted, "hi");
+        assert_eq!(rest, r#" "there""#);
+        assert!(
+            RcSubstring::from(String::from("(open"))
+                .take_delimited('(', ')')
+                .is_none()
+        );
+        assert!(
+            RcSubstring::from(String::from("none)"))
+                .take_delimited('(', ')')
+                .is_none()
+        );
+        assert_eq!(
+            RcSubstring::from(String::from("()"))
+                .take_delimited('(', ')')
+                .unwrap()
+                .0,
+            ""
+        );
+    }
+
+    #[test]
+    fn test_delimited_groups() {
+        let text = RcSubstring::from(String::from("[a] [[b]] [c"));
+        let mut groups = text.delimited_groups('[', ']');
+        assert_eq!(groups.size_hint(), (0, Some(6)));
+        assert_eq!(groups.next().unwrap(), "a");
+        assert_eq!(groups.next().unwrap(), "[b]");
+        assert_eq!(groups.next(), None);
+        assert_eq!(groups.size_hint(), (0, Some(0)));
+    }
+}
@@ -0,0 +1,182 @@
+//! Displaying the text of an [RcSubstring] quoted or shortened, for messages
+use crate::RcSubstring;
+use std::fmt::{Display, Write};
+
+impl RcSubstring {
+    /// Display the text in double quotes, escaped as by [str::escape_debug]
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let token = RcSubstring::from(String::from("say \"hi\""));
+    /// assert_eq!(format!("unexpected {}", token.quoted()), r#"unexpected "say \"hi\"""#);
+    /// ```
+    pub fn quoted(&self) -> Quoted<'_> {
+        Quoted {
+            text: self.as_str(),
+        }
+    }
+
+    /// Display at most `max_chars` chars of the text, ending with `…` if it is cut short
+    ///
+    /// The `…` counts as one of the chars.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let value = RcSubstring::from(String::from("a very long value"));
+    /// assert_eq!(value.truncated(7).to_string(), "a very…");
+    /// assert_eq!(value.truncated(100).to_string(), "a very long value");
+    /// ```
+    pub fn truncated(&self, max_chars: usize) -> Truncated<'_> {
+        Truncated {
+            text: self.as_str(),
+            max_chars,
+        }
+    }
+
+    /// Display at most `max_chars` chars of the text, with `…` in place of the middle if it
+    /// is too long
+    ///
+    /// This keeps both the start and the end, eg. for paths and identifiers. The `…` counts
+    /// as one of the chars, and if the rest can't be split evenly the start gets one more.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let path = RcSubstring::from(String::from("src/very/deep/module.rs"));
+    /// assert_eq!(path.ellipsized_middle(11).to_string(), "src/v…le.rs");
+    /// ```
+    pub fn ellipsized_middle(&self, max_chars: usize) -> EllipsizedMiddle<'_> {
+        EllipsizedMiddle {
+            text: self.as_str(),
+            max_chars,
+        }
+    }
+}
+
+// The byte length of the first n chars of text, or None if it has no more than n chars
+fn first_chars_len(text: &str, n: usize) -> Option<usize> {
+    text.char_indices().nth(n).map(|(i, _)| i)
+}
+
+/**
+Displays text in double quotes with escapes
+
+Created by [RcSubstring::quoted]
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Quoted<'a> {
+    text: &'a str,
+}
+
+impl Display for Quoted<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        write!(f, "{}", self.text.escape_debug())?;
+        f.write_char('"')
+    }
+}
+
+/**
+Displays the start of text, with `…` if it is cut short
+
+Created by [RcSubstring::truncated]
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct Truncated<'a> {
+    text: &'a str,
+    max_chars: usize,
+}
+
+impl Display for Truncated<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if first_chars_len(self.text, self.max_chars).is_none() {
+            return f.write_str(self.text);
+        }
+        let Some(keep) = self.max_chars.checked_sub(1) else {
+            return Ok(());
+        };
+        let end = first_chars_len(self.text, keep).unwrap_or(self.text.len());
+        f.write_str(&self.text[..end])?;
+        f.write_char('…')
+    }
+}
+
+/**
+Displays the start and end of text, with `…` in place of the middle if it is too long
+
+Created by [RcSubstring::ellipsized_middle]
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct EllipsizedMiddle<'a> {
+    text: &'a str,
+    max_chars: usize,
+}
+
+impl Display for EllipsizedMiddle<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if first_chars_len(self.text, self.max_chars).is_none() {
+            return f.write_str(self.text);
+        }
+        let Some(keep) = self.max_chars.checked_sub(1) else {
+            return Ok(());
+        };
+        let head = first_chars_len(self.text, keep.div_ceil(2)).unwrap_or(self.text.len());
+        let tail = match keep / 2 {
+            0 => self.text.len(),
+            n => self
+                .text
+                .char_indices()
+                .rev()
+                .nth(n - 1)
+                .map_or(0, |(i, _)| i),
+        };
+        f.write_str(&self.text[..head])?;
+        f.write_char('…')?;
+        f.write_str(&self.text[tail..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quoted() {
+        assert_eq!(
+            RcSubstring::from(String::from("é\n\\"))
+                .quoted()
+                .to_string(),
+            r#""é\n\\""#
+        );
+        assert_eq!(
+            RcSubstring::from(String::new()).quoted().to_string(),
+            r#""""#
+        );
+    }
+
+    #[test]
+    fn test_truncated() {
+        let text = RcSubstring::from(String::from("héllo"));
+        let truncated = |n| text.truncated(n).to_string();
+        assert_eq!(truncated(5), "héllo");
+        assert_eq!(truncated(4), "hél…");
+        assert_eq!(truncated(2), "h…");
+        assert_eq!(truncated(1), "…");
+        assert_eq!(truncated(0), "");
+        assert_eq!(
+            RcSubstring::from(String::new()).truncated(0).to_string(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_ellipsized_middle() {
+        let text = RcSubstring::from(String::from("abcdéfgh"));
+        let middle = |n| text.ellipsized_middle(n).to_string();
+        assert_eq!(middle(8), "abcdéfgh");
+        assert_eq!(middle(7), "abc…fgh");
+        assert_eq!(middle(6), "abc…gh");
+        assert_eq!(middle(2), "a…");
+        assert_eq!(middle(1), "…");
+        assert_eq!(middle(0), "");
+    }
+}
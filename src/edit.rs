@@ -0,0 +1,449 @@
+//! Moving substrings onto the new text after an edit
+use crate::{EditError, RcSubstring, RcSubstringError};
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+A change to a text: the bytes in `range` were replaced by `new_len` bytes
+
+Given an edit and the edited text, [apply_edit] and [AnchoredSubstring::apply_edit] move
+substrings of the old text onto the new one.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edit {
+    /// The bytes of the old text that were replaced
+    pub range: Range<usize>,
+    /// The length of the text that replaced them
+    pub new_len: usize,
+}
+
+impl Edit {
+    /// Replace `range` of `source` with `replacement`, returning the new text and the edit
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or not on char boundaries, as [String::replace_range] does
+    ///
+    /// ```rust
+    /// # use rcsubstring::Edit;
+    /// let (text, edit) = Edit::replace("let x = 1;", 4..5, "total");
+    /// assert_eq!(*text, "let total = 1;");
+    /// assert_eq!(edit, Edit { range: 4..5, new_len: 5 });
+    /// ```
+    #[track_caller]
+    pub fn replace(source: &str, range: Range<usize>, replacement: &str) -> (Rc<String>, Edit) {
+        let mut text = String::with_capacity(source.len() - range.len() + replacement.len());
+        text.push_str(source);
+        text.replace_range(range.clone(), replacement);
+        let edit = Edit {
+            range,
+            new_len: replacement.len(),
+        };
+        (Rc::new(text), edit)
+    }
+
+    // Where `offset` in the old text ends up in the new text
+    fn map_offset(&self, offset: usize, bias: Bias) -> usize {
+        let Range { start, end } = self.range;
+        if offset < start || (offset == start && bias == Bias::Left) {
+            offset
+        } else if offset > end || (offset == end && bias == Bias::Right) {
+            offset - self.range.len() + self.new_len
+        } else {
+            match bias {
+                Bias::Left => start,
+                Bias::Right => start + self.new_len,
+            }
+        }
+    }
+
+    // The range of a span after the edit, or None if the edit replaced only part of it
+    fn rebase(&self, range: &Range<usize>) -> Option<Range<usize>> {
+        let shift = |offset: usize| offset - self.range.len() + self.new_len;
+        if range.end <= self.range.start {
+            Some(range.clone())
+        } else if range.start >= self.range.end {
+            Some(shift(range.start)..shift(range.end))
+        } else if range.start <= self.range.start && range.end >= self.range.end {
+            Some(range.start..shift(range.end))
+        } else {
+            None
+        }
+    }
+}
+
+/**
+Move `spans` of the old text onto `new_source`, the text after `edit`
+
+Spans before the edit keep their range and spans after it are shifted by the change in
+length. A span that contains all of the edit grows or shrinks to cover the new text.
+Spans that the edit replaced some or all of are removed, as they may no longer make sense.
+An insertion at the very start or end of a span is left outside it; use [AnchoredSubstring]
+to choose otherwise.
+
+All of `spans` should be of the old text.
+
+# Panics
+
+Panics if a rebased span doesn't fit `new_source`, which means it is not the result of `edit`,
+even in release builds
+
+```rust
+# use rcsubstring::{Edit, RcSubstring, apply_edit};
+# use std::rc::Rc;
+let source = Rc::new(String::from("fn main() { run(); }"));
+let mut spans = vec![
+    RcSubstring::new(Rc::clone(&source), 3..7),
+    RcSubstring::new(Rc::clone(&source), 14..18),
+    RcSubstring::new(Rc::clone(&source), 10..20),
+];
+let (text, edit) = Edit::replace(&source, 12..15, "start");
+apply_edit(&mut spans, &edit, &text);
+assert_eq!(spans, ["main", "{ start(); }"]);
+```
+*/
+#[track_caller]
+pub fn apply_edit(spans: &mut Vec<RcSubstring>, edit: &Edit, new_source: &Rc<String>) {
+    spans.retain_mut(|span| match edit.rebase(&span.range) {
+        Some(range) => {
+            if let Err(error) = RcSubstringError::check(new_source, &range) {
+                panic!("{}", error);
+            }
+            *span = RcSubstring::new(Rc::clone(new_source), range);
+            true
+        }
+        None => false,
+    });
+}
+
+/// Which way a position goes when text is inserted at it, or the text around it is replaced
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Bias {
+    /// Stay before the new text
+    Left,
+    /// Move after the new text
+    Right,
+}
+
+/**
+A substring whose ends are anchored to the text around them, so it survives any edit
+
+Each end has a [Bias] that decides where it goes if text is inserted exactly at it, or if
+the text around it is replaced. Unlike [apply_edit] the substring is never dropped, though
+it can end up empty.
+
+```rust
+# use rcsubstring::{AnchoredSubstring, Bias, Edit, RcSubstring};
+# use std::rc::Rc;
+let source = Rc::new(String::from("let x = 1;"));
+let mut value = AnchoredSubstring::new(RcSubstring::new(Rc::clone(&source), 8..9));
+let (text, edit) = Edit::replace(&source, 9..9, "0");
+value.apply_edit(&edit, &text);
+assert_eq!(*value.substring(), "10");
+
+let mut name = AnchoredSubstring::with_bias(
+    RcSubstring::new(Rc::clone(&text), 4..5),
+    Bias::Right,
+    Bias::Left,
+);
+let (text, edit) = Edit::replace(&text, 4..4, "mut ");
+name.apply_edit(&edit, &text);
+assert_eq!(*name.substring(), "x");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct AnchoredSubstring {
+    substring: RcSubstring,
+    start_bias: Bias,
+    end_bias: Bias,
+}
+
+impl AnchoredSubstring {
+    /// Anchor `substring` so that it grows to take in text inserted at either end
+    pub fn new(substring: RcSubstring) -> Self {
+        AnchoredSubstring::with_bias(substring, Bias::Left, Bias::Right)
+    }
+
+    /// Anchor `substring` with the given bias for its start and end
+    ///
+    /// `Bias::Right` for the start and `Bias::Left` for the end keep text inserted at the
+    /// ends outside it.
+    pub fn with_bias(substring: RcSubstring, start_bias: Bias, end_bias: Bias) -> Self {
+        AnchoredSubstring {
+            substring,
+            start_bias,
+            end_bias,
+        }
+    }
+
+    /// The substring of the latest text
+    pub fn substring(&self) -> &RcSubstring {
+        &self.substring
+    }
+
+    /// The bias of the start and end
+    pub fn bias(&self) -> (Bias, Bias) {
+        (self.start_bias, self.end_bias)
+    }
+
+    /// Move the substring onto `new_source`, the text after `edit`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the moved range doesn't fit `new_source`, which means it is not the result
+    /// of `edit`, even in release builds
+    #[track_caller]
+    pub fn apply_edit(&mut self, edit: &Edit, new_source: &Rc<String>) {
+        let start = edit.map_offset(self.substring.range.start, self.start_bias);
+        let end = edit
+            .map_offset(self.substring.range.end, self.end_bias)
+            .max(start);
+        if let Err(error) = RcSubstringError::check(new_source, &(start..end)) {
+            panic!("{}", error);
+        }
+        self.substring = RcSubstring::new(Rc::clone(new_source), start..end);
+    }
+
+    /// Consume the anchor returning the substring
+    pub fn into_substring(self) -> RcSubstring {
+        self.substring
+    }
+}
+
+/**
+Makes several replacements in a text at once, giving substrings of what replaced them
+
+Each replacement is the [RcSubstring] to replace and its new text. The replacements can be
+given in any order, but mustn't overlap. This suits code fixes and refactorings, where each
+fix knows the span it applies to and wants to point at its result in the edited text.
+
+```rust
+# use rcsubstring::{Edited, RcSubstring};
+# use std::rc::Rc;
+let source = Rc::new(String::from("let x = y + x;"));
+let (edited, spans) = Edited::new(Rc::clone(&source))
+    .replace(&RcSubstring::new(Rc::clone(&source), 12..13), "total")
+    .replace(&RcSubstring::new(Rc::clone(&source), 4..5), "total")
+    .finish()
+    .unwrap();
+assert_eq!(*edited, "let total = y + total;");
+assert_eq!(spans, ["total", "total"]);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Edited {
+    source: Rc<String>,
+    replacements: Vec<(RcSubstring, String)>,
+}
+
+impl Edited {
+    /// Start editing `source`
+    pub fn new(source: Rc<String>) -> Self {
+        Edited {
+            source,
+            replacements: Vec::new(),
+        }
+    }
+
+    /// Replace `span`, which must be of the source, with `text`
+    ///
+    /// An empty span inserts `text`. Insertions at the same place are made in the order given.
+    pub fn replace(mut self, span: &RcSubstring, text: impl Into<String>) -> Self {
+        self.replacements.push((span.clone(), text.into()));
+        self
+    }
+
+    /// Make the replacements, returning the edited text and a substring of it for each
+    /// replacement, in the order they were given
+    ///
+    /// Errors if a replaced span is not of the source, or two of them overlap
+    pub fn finish(self) -> Result<(Rc<String>, Vec<RcSubstring>), EditError> {
+        for (index, (span, _)) in self.replacements.iter().enumerate() {
+            if !span.shared().is_some_and(|rc| Rc::ptr_eq(rc, &self.source)) {
+                return Err(EditError::OtherSource { index });
+            }
+        }
+        let mut order: Vec<usize> = (0..self.replacements.len()).collect();
+        order.sort_by_key(|&i| {
+            let range = &self.replacements[i].0.range;
+            (range.start, range.end)
+        });
+        for pair in order.windows(2) {
+            if self.replacements[pair[0]].0.range.end > self.replacements[pair[1]].0.range.start {
+                return Err(EditError::Overlapping {
+                    index: pair[0],
+                    other: pair[1],
+                });
+            }
+        }
+
+        let new_len = self
+            .replacements
+            .iter()
+            .fold(self.source.len(), |len, (span, text)| {
+                len - span.len() + text.len()
+            });
+        let mut text = String::with_capacity(new_len);
+        let mut ranges = vec![0..0; self.replacements.len()];
+        let mut copied = 0;
+        for i in order {
+            let (span, replacement) = &self.replacements[i];
+            text.push_str(&self.source[copied..span.range.start]);
+            ranges[i] = text.len()..text.len() + replacement.len();
+            text.push_str(replacement);
+            copied = span.range.end;
+        }
+        text.push_str(&self.source[copied..]);
+
+        let text = Rc::new(text);
+        let spans = ranges
+            .into_iter()
+            .map(|range| RcSubstring::new(Rc::clone(&text), range))
+            .collect();
+        Ok((text, spans))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spans(source: &Rc<String>, ranges: &[Range<usize>]) -> Vec<RcSubstring> {
+        ranges
+            .iter()
+            .map(|range| RcSubstring::new(Rc::clone(source), range.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_edit() {
+        let source = Rc::new(String::from("abcdefgh"));
+        let mut rebased = spans(&source, &[0..2, 2..4, 3..5, 4..6, 6..8, 1..7, 2..6]);
+        let (text, edit) = Edit::replace(&source, 2..6, "é");
+        apply_edit(&mut rebased, &edit, &text);
+        assert_eq!(rebased, ["ab", "gh", "bég", "é"]);
+        assert_eq!(rebased[1].range, 4..6);
+        assert!(Rc::ptr_eq(rebased[0].shared().unwrap(), &text));
+    }
+
+    #[test]
+    fn test_apply_insertion() {
+        let source = Rc::new(String::from("abcd"));
+        let mut rebased = spans(&source, &[0..2, 2..4, 1..3, 2..2]);
+        let (text, edit) = Edit::replace(&source, 2..2, "XY");
+        apply_edit(&mut rebased, &edit, &text);
+        assert_eq!(rebased, ["ab", "cd", "bXYc", ""]);
+        assert_eq!(rebased[3].range, 2..2);
+    }
+
+    #[test]
+    fn test_anchored_replacement() {
+        let source = Rc::new(String::from("abcdefgh"));
+        let anchored = |range, start, end| {
+            AnchoredSubstring::with_bias(RcSubstring::new(Rc::clone(&source), range), start, end)
+        };
+        let mut anchors = [
+            anchored(1..3, Bias::Left, Bias::Left),
+            anchored(1..3, Bias::Left, Bias::Right),
+            anchored(3..5, Bias::Left, Bias::Right),
+            anchored(3..5, Bias::Right, Bias::Left),
+            anchored(5..7, Bias::Right, Bias::Right),
+        ];
+        let (text, edit) = Edit::replace(&source, 2..6, "XY");
+        for anchor in &mut anchors {
+            anchor.apply_edit(&edit, &text);
+        }
+        let anchors = anchors.map(AnchoredSubstring::into_substring);
+        assert_eq!(anchors, ["b", "bXY", "XY", "", "g"]);
+        assert_eq!(anchors[3].range, 4..4);
+    }
+
+    #[test]
+    fn test_anchored_insertion() {
+        let source = Rc::new(String::from("ab"));
+        let mut empty = AnchoredSubstring::with_bias(
+            RcSubstring::new(Rc::clone(&source), 1..1),
+            Bias::Right,
+            Bias::Left,
+        );
+        let mut growing = AnchoredSubstring::new(RcSubstring::new(Rc::clone(&source), 1..1));
+        let (text, edit) = Edit::replace(&source, 1..1, "-");
+        empty.apply_edit(&edit, &text);
+        growing.apply_edit(&edit, &text);
+        assert_eq!(empty.substring().range, 2..2);
+        assert_eq!(*growing.substring(), "-");
+        assert_eq!(growing.bias(), (Bias::Left, Bias::Right));
+    }
+
+    #[test]
+    #[should_panic(expected = "index 7 out of bounds for length 4")]
+    fn test_wrong_new_source() {
+        let source = Rc::new(String::from("abcd"));
+        let mut rebased = vec![RcSubstring::new(Rc::clone(&source), 2..4)];
+        let edit = Edit {
+            range: 0..0,
+            new_len: 3,
+        };
+        apply_edit(&mut rebased, &edit, &source);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 7 out of bounds for length 4")]
+    fn test_anchored_wrong_new_source() {
+        let source = Rc::new(String::from("abcd"));
+        let mut anchored = AnchoredSubstring::new(RcSubstring::new(Rc::clone(&source), 2..4));
+        let edit = Edit {
+            range: 0..0,
+            new_len: 3,
+        };
+        anchored.apply_edit(&edit, &source);
+    }
+
+    #[test]
+    fn test_edited() {
+        let source = Rc::new(String::from("a+b*c"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        let (edited, spans) = Edited::new(Rc::clone(&source))
+            .replace(&text.reslice(4..5), "γ")
+            .replace(&text.reslice(0..0), "(")
+            .replace(&text.reslice(3..4), "")
+            .replace(&text.reslice(0..0), "(")
+            .replace(&text.reslice(2..2), ")")
+            .finish()
+            .unwrap();
+        assert_eq!(*edited, "((a+)bγ");
+        assert_eq!(spans, ["γ", "(", "", "(", ")"]);
+        assert_eq!(spans[1].range, 0..1);
+        assert_eq!(spans[3].range, 1..2);
+        assert!(Rc::ptr_eq(spans[0].shared().unwrap(), &edited));
+        let (unchanged, spans) = Edited::new(Rc::clone(&source)).finish().unwrap();
+        assert_eq!(unchanged, source);
+        assert!(spans.is_empty());
+    }
+
+    #[test]
+    fn test_edited_errors() {
+        let source = Rc::new(String::from("abcdef"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        let overlapping = Edited::new(Rc::clone(&source))
+            .replace(&text.reslice(4..6), "x")
+            .replace(&text.reslice(0..2), "x")
+            .replace(&text.reslice(1..3), "x")
+            .finish();
+        assert_eq!(
+            overlapping.unwrap_err(),
+            EditError::Overlapping { index: 1, other: 2 }
+        );
+        let copy = RcSubstring::from(String::from("abcdef"));
+        let error = Edited::new(source)
+            .replace(&text.reslice(0..1), "x")
+            .replace(&copy.reslice(1..2), "x")
+            .finish()
+            .unwrap_err();
+        assert_eq!(error, EditError::OtherSource { index: 1 });
+        assert_eq!(
+            error.to_string(),
+            "replacement 1 is not of the text being edited"
+        );
+    }
+}
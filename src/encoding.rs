@@ -0,0 +1,158 @@
+//! Decoding legacy-encoded bytes with `encoding_rs` into a shared `Rc<String>`
+use crate::RcSubstring;
+use ::encoding_rs::Encoding;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// Decode `bytes` into a new `Rc<String>`
+///
+/// A BOM at the start of `bytes` takes precedence over `encoding` and is removed.
+/// Malformed sequences are replaced with U+FFFD and the returned flag is true if
+/// that happened.
+pub fn decode_to_rc(bytes: &[u8], encoding: &'static Encoding) -> (Rc<String>, bool) {
+    let (text, _, had_errors) = encoding.decode(bytes);
+    (Rc::new(text.into_owned()), had_errors)
+}
+
+/**
+Text decoded to UTF-8 along with a map back to byte offsets in the original input
+
+Tools that report positions in legacy-encoded files (eg. from an index built over the raw
+bytes) can use [DecodedText::map_range] to get the matching [RcSubstring] of the decoded text.
+
+```rust
+# use rcsubstring::DecodedText;
+let input = b"caf\xE9 cr\xE8me";
+let decoded = DecodedText::decode(input, encoding_rs::WINDOWS_1252);
+assert_eq!(decoded.text().as_str(), "café crème");
+let word = decoded.map_range(5..10).unwrap();
+assert_eq!(word, "crème");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct DecodedText {
+    text: Rc<String>,
+    encoding: &'static Encoding,
+    had_errors: bool,
+    // Offsets (input, output) where both the input and output are at a char boundary,
+    // each followed by the number of further bytes that map one-to-one
+    boundaries: Vec<(usize, usize, usize)>,
+}
+
+impl DecodedText {
+    /// Decode `bytes`, recording how offsets in the input map to the decoded text
+    ///
+    /// As for [decode_to_rc] a BOM takes precedence over `encoding` and is removed.
+    /// This decodes a byte at a time to find the boundaries, so is slower than [decode_to_rc].
+    pub fn decode(bytes: &[u8], encoding: &'static Encoding) -> Self {
+        let mut decoder = encoding.new_decoder();
+        let mut text = String::new();
+        let mut had_errors = false;
+        let mut boundaries = vec![(0, 0, 0)];
+        if let Some((_, bom_len)) = Encoding::for_bom(bytes) {
+            boundaries.push((bom_len, 0, 0));
+        }
+        for i in 0..bytes.len() {
+            let written = text.len();
+            text.reserve(decoder.max_utf8_buffer_length(1).unwrap_or(4));
+            let (_, _, errors) =
+                decoder.decode_to_string(&bytes[i..i + 1], &mut text, i + 1 == bytes.len());
+            had_errors |= errors;
+            if text.len() == written {
+                continue;
+            }
+            let last = boundaries.last_mut().unwrap();
+            if text.len() == written + 1 && last.0 + last.2 == i && last.1 + last.2 == written {
+                last.2 += 1;
+            } else {
+                boundaries.push((i + 1, text.len(), 0));
+            }
+        }
+        DecodedText {
+            text: Rc::new(text),
+            encoding: decoder.encoding(),
+            had_errors,
+            boundaries,
+        }
+    }
+
+    /// The decoded text
+    pub fn text(&self) -> &Rc<String> {
+        &self.text
+    }
+
+    /// The encoding that was used, which may differ from the one requested if there was a BOM
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding
+    }
+
+    /// True if any malformed sequences were replaced with U+FFFD
+    pub fn had_errors(&self) -> bool {
+        self.had_errors
+    }
+
+    /// The offset in the decoded text matching `offset` in the input bytes
+    ///
+    /// Returns `None` if `offset` is not at the boundary of a decoded char
+    pub fn map_offset(&self, offset: usize) -> Option<usize> {
+        let i = self
+            .boundaries
+            .partition_point(|&(input, _, _)| input <= offset)
+            .checked_sub(1)?;
+        let (input, output, run) = self.boundaries[i];
+        (offset - input <= run).then_some(output + offset - input)
+    }
+
+    /// The decoded text for a byte `range` of the input
+    ///
+    /// Returns `None` if either end of `range` is not at the boundary of a decoded char
+    pub fn map_range(&self, range: Range<usize>) -> Option<RcSubstring> {
+        let start = self.map_offset(range.start)?;
+        let end = self.map_offset(range.end)?;
+        (start <= end).then(|| RcSubstring::new(Rc::clone(&self.text), start..end))
+    }
+
+    /// The whole of the decoded text
+    pub fn to_substring(&self) -> RcSubstring {
+        RcSubstring::from(Rc::clone(&self.text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::encoding_rs::{SHIFT_JIS, UTF_8, WINDOWS_1252};
+
+    #[test]
+    fn test_decode_to_rc() {
+        let (text, had_errors) = decode_to_rc(b"\xA3100", WINDOWS_1252);
+        assert_eq!(text.as_str(), "£100");
+        assert!(!had_errors);
+        let (text, had_errors) = decode_to_rc(b"\xEF\xBB\xBFok\xFF", WINDOWS_1252);
+        assert_eq!(text.as_str(), "ok\u{FFFD}");
+        assert!(had_errors);
+    }
+
+    #[test]
+    fn test_multibyte_input() {
+        // "日本 text" in Shift_JIS
+        let input = b"\x93\xFA\x96\x7B text";
+        let decoded = DecodedText::decode(input, SHIFT_JIS);
+        assert_eq!(decoded.text().as_str(), "日本 text");
+        assert_eq!(decoded.map_range(2..4).unwrap(), "本");
+        assert_eq!(decoded.map_range(5..9).unwrap(), "text");
+        assert_eq!(decoded.map_offset(9), Some(11));
+        assert!(decoded.map_offset(1).is_none());
+        assert!(decoded.map_offset(10).is_none());
+    }
+
+    #[test]
+    fn test_bom() {
+        let input = b"\xEF\xBB\xBFhi";
+        let decoded = DecodedText::decode(input, WINDOWS_1252);
+        assert_eq!(decoded.encoding(), UTF_8);
+        assert_eq!(decoded.text().as_str(), "hi");
+        assert_eq!(decoded.map_range(3..5).unwrap(), "hi");
+        assert_eq!(decoded.map_offset(0), Some(0));
+    }
+}
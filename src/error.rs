@@ -0,0 +1,305 @@
+//! Errors for invalid substring ranges
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::Range;
+use std::panic::Location;
+
+/// Why a range can't be used for an [RcSubstring](crate::RcSubstring)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RcSubstringErrorKind {
+    /// The range starts after it ends
+    StartAfterEnd {
+        /// Start of the range
+        start: usize,
+        /// End of the range
+        end: usize,
+    },
+    /// An end of the range is past the end of the string
+    OutOfBounds {
+        /// The offending end of the range
+        index: usize,
+        /// Length of the string
+        len: usize,
+    },
+    /// An end of the range is inside a multi-byte char
+    NotCharBoundary {
+        /// The offending end of the range
+        index: usize,
+    },
+}
+
+impl Display for RcSubstringErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RcSubstringErrorKind::StartAfterEnd { start, end } => {
+                write!(f, "start {} is after end {}", start, end)
+            }
+            RcSubstringErrorKind::OutOfBounds { index, len } => {
+                write!(f, "index {} out of bounds for length {}", index, len)
+            }
+            RcSubstringErrorKind::NotCharBoundary { index } => {
+                write!(f, "index {} is not a char boundary", index)
+            }
+        }
+    }
+}
+
+/// An invalid range for an [RcSubstring](crate::RcSubstring), with where it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RcSubstringError {
+    kind: RcSubstringErrorKind,
+    location: Option<&'static Location<'static>>,
+    label: Option<&'static str>,
+    excerpt: String,
+}
+
+// How much text either side of the range to show in an excerpt
+const EXCERPT_CONTEXT: usize = 16;
+
+impl RcSubstringError {
+    // Check range is in bounds of text, but not that it is on char boundaries
+    pub(crate) fn check_bounds(
+        text: &str,
+        range: &Range<usize>,
+    ) -> Result<(), RcSubstringErrorKind> {
+        if range.start > range.end {
+            return Err(RcSubstringErrorKind::StartAfterEnd {
+                start: range.start,
+                end: range.end,
+            });
+        }
+        if range.end > text.len() {
+            return Err(RcSubstringErrorKind::OutOfBounds {
+                index: range.end,
+                len: text.len(),
+            });
+        }
+        Ok(())
+    }
+
+    // Check range can be used to slice text
+    pub(crate) fn check_kind(text: &str, range: &Range<usize>) -> Result<(), RcSubstringErrorKind> {
+        RcSubstringError::check_bounds(text, range)?;
+        for index in [range.start, range.end] {
+            if !text.is_char_boundary(index) {
+                return Err(RcSubstringErrorKind::NotCharBoundary { index });
+            }
+        }
+        Ok(())
+    }
+
+    // Check range can be used to slice text, blaming the caller if not
+    #[track_caller]
+    pub(crate) fn check(text: &str, range: &Range<usize>) -> Result<(), RcSubstringError> {
+        let location = Location::caller();
+        RcSubstringError::check_kind(text, range)
+            .map_err(|kind| RcSubstringError::new(kind, Some(location), text, range))
+    }
+
+    pub(crate) fn new(
+        kind: RcSubstringErrorKind,
+        location: Option<&'static Location<'static>>,
+        text: &str,
+        range: &Range<usize>,
+    ) -> Self {
+        RcSubstringError {
+            kind,
+            location,
+            label: None,
+            excerpt: excerpt(text, range),
+        }
+    }
+
+    pub(crate) fn with_label(mut self, label: Option<&'static str>) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// What is wrong with the range
+    pub fn kind(&self) -> &RcSubstringErrorKind {
+        &self.kind
+    }
+
+    /// Where the substring was created, if known
+    ///
+    /// For [RcSubstring::try_new](crate::RcSubstring::try_new) this is always where it was called.
+    /// Existing substrings only record where they were created in debug builds, or with the
+    /// `provenance` feature.
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        self.location
+    }
+
+    /// The label of the substring, if it was made with [RcSubstring::labeled](crate::RcSubstring::labeled)
+    pub fn label(&self) -> Option<&'static str> {
+        self.label
+    }
+
+    /// The text around the start of the range, or the end of the text if that is out of bounds
+    pub fn excerpt(&self) -> &str {
+        &self.excerpt
+    }
+}
+
+// Up to EXCERPT_CONTEXT bytes of text either side of the start of range, on char boundaries
+fn excerpt(text: &str, range: &Range<usize>) -> String {
+    let middle = range.start.min(text.len());
+    let mut start = middle.saturating_sub(EXCERPT_CONTEXT);
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = (middle + EXCERPT_CONTEXT).min(text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    String::from(&text[start..end])
+}
+
+impl Display for RcSubstringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} for RcSubstring", self.kind)?;
+        if let Some(label) = self.label {
+            write!(f, " {:?}", label)?;
+        }
+        if let Some(location) = self.location {
+            write!(f, " created at {}", location)?;
+        }
+        write!(f, " of text {:?}", self.excerpt)
+    }
+}
+
+impl Error for RcSubstringError {}
+
+/// Why a range was rejected by [Spans](crate::Spans)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpansErrorKind {
+    /// The range can't be used for a substring
+    Invalid(RcSubstringError),
+    /// The range starts before the previous one
+    OutOfOrder {
+        /// The previous range
+        previous: Range<usize>,
+    },
+    /// The range overlaps the previous one
+    Overlapping {
+        /// The previous range
+        previous: Range<usize>,
+    },
+}
+
+/// The first range rejected by [Spans](crate::Spans), and why
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpansError {
+    pub(crate) index: usize,
+    pub(crate) range: Range<usize>,
+    pub(crate) kind: SpansErrorKind,
+}
+
+impl SpansError {
+    /// The position of the rejected range in the ranges given
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The rejected range
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Why the range was rejected
+    pub fn kind(&self) -> &SpansErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for SpansError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "range {} ({:?}) ", self.index, self.range)?;
+        match &self.kind {
+            SpansErrorKind::Invalid(error) => write!(f, "is invalid: {}", error),
+            SpansErrorKind::OutOfOrder { previous } => {
+                write!(f, "starts before the previous range {:?}", previous)
+            }
+            SpansErrorKind::Overlapping { previous } => {
+                write!(f, "overlaps the previous range {:?}", previous)
+            }
+        }
+    }
+}
+
+impl Error for SpansError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            SpansErrorKind::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Why the replacements given to [Edited](crate::Edited) couldn't be made
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditError {
+    /// The replaced substring is not of the text being edited
+    OtherSource {
+        /// The position of the replacement in the order they were given
+        index: usize,
+    },
+    /// Two replaced substrings overlap
+    Overlapping {
+        /// The position of the first replacement in the order they were given
+        index: usize,
+        /// The position of the replacement it overlaps
+        other: usize,
+    },
+}
+
+impl Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::OtherSource { index } => {
+                write!(f, "replacement {} is not of the text being edited", index)
+            }
+            EditError::Overlapping { index, other } => {
+                write!(f, "replacement {} overlaps replacement {}", index, other)
+            }
+        }
+    }
+}
+
+impl Error for EditError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let text = "añb";
+        assert!(RcSubstringError::check(text, &(0..4)).is_ok());
+        assert_eq!(
+            RcSubstringError::check_kind(text, &(2..4)),
+            Err(RcSubstringErrorKind::NotCharBoundary { index: 2 })
+        );
+        assert_eq!(
+            RcSubstringError::check_bounds(text, &(1..5)),
+            Err(RcSubstringErrorKind::OutOfBounds { index: 5, len: 4 })
+        );
+        let line = line!() + 1;
+        let error = RcSubstringError::check(text, &(1..5)).unwrap_err();
+        assert_eq!(error.location().unwrap().line(), line);
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "index 5 out of bounds for length 4 for RcSubstring created at src/error.rs:{}:21 of text \"añb\"",
+                line
+            )
+        );
+    }
+
+    #[test]
+    fn test_excerpt() {
+        let text = "0123456789abcdefghijklmnopqrstuvwxyzéé";
+        assert_eq!(excerpt(text, &(20..22)), "456789abcdefghijklmnopqrstuvwxyz");
+        assert_eq!(excerpt(text, &(21..22)), "56789abcdefghijklmnopqrstuvwxyzé");
+        assert_eq!(excerpt(text, &(100..101)), "opqrstuvwxyzéé");
+    }
+}
@@ -0,0 +1,287 @@
+//! Escaping the text of an [RcSubstring] and making invisible chars visible
+use crate::RcSubstring;
+use std::fmt::{Display, Write};
+
+// Write marker, the escape or symbol for a char, in colour if color is set
+fn write_marker(
+    f: &mut std::fmt::Formatter<'_>,
+    color: bool,
+    marker: impl Display,
+) -> std::fmt::Result {
+    #[cfg(feature = "color")]
+    if color {
+        return crate::color::write_marker(f, marker);
+    }
+    #[cfg(not(feature = "color"))]
+    let _ = color;
+    write!(f, "{marker}")
+}
+
+// Chars that show as nothing, or as an ordinary space, but aren't
+fn is_invisible(c: char) -> bool {
+    match c {
+        '\u{a0}' | '\u{ad}' | '\u{feff}' => true,
+        '\u{2000}'..='\u{200f}' | '\u{2028}'..='\u{202f}' | '\u{2060}'..='\u{2064}' => true,
+        c => c.is_control(),
+    }
+}
+
+impl RcSubstring {
+    /// Display the text with backslash escapes for control and invisible chars
+    ///
+    /// Tabs, newlines and carriage returns are shown as `\t`, `\n` and `\r`, other invisible
+    /// chars such as no-break spaces as `\u{a0}`, and backslashes are doubled. Nothing is
+    /// allocated, so this is cheap to use in error messages.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let input = RcSubstring::from(String::from("a\tb\u{a0}c\\"));
+    /// assert_eq!(input.display_escaped().to_string(), r"a\tb\u{a0}c\\");
+    /// ```
+    pub fn display_escaped(&self) -> DisplayEscaped<'_> {
+        DisplayEscaped {
+            text: self.as_str(),
+            color: false,
+        }
+    }
+
+    /// Display the text with whitespace and invisible chars replaced by visible symbols
+    ///
+    /// Tabs are shown as `→`, spaces at the end of a line as `·`, no-break spaces as `⍽`,
+    /// newlines as `↵` and other control chars as their Unicode control pictures such as
+    /// `␍`. Other invisible chars are shown as `<U+200B>`. Nothing is allocated.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let input = RcSubstring::from(String::from("key =\tvalue  \r\n"));
+    /// assert_eq!(input.show_invisibles().to_string(), "key =→value··␍↵");
+    /// ```
+    pub fn show_invisibles(&self) -> ShowInvisibles<'_> {
+        ShowInvisibles {
+            text: self.as_str(),
+            color: false,
+        }
+    }
+
+    /// The text escaped as by [str::escape_debug], sharing the text if nothing is escaped
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("tab\there"));
+    /// assert_eq!(text.escape_debug_owned(), r"tab\there");
+    /// ```
+    pub fn escape_debug_owned(&self) -> RcSubstring {
+        self.escaped(|text| text.escape_debug().to_string())
+    }
+
+    /// The text escaped as by [str::escape_default], sharing the text if nothing is escaped
+    pub fn escape_default_owned(&self) -> RcSubstring {
+        self.escaped(|text| text.escape_default().to_string())
+    }
+
+    /// The text escaped as by [str::escape_unicode]
+    ///
+    /// Every char is escaped, so only an empty substring is shared.
+    pub fn escape_unicode_owned(&self) -> RcSubstring {
+        if self.is_empty() {
+            return self.clone();
+        }
+        RcSubstring::from(self.escape_unicode().to_string())
+    }
+
+    // The text escaped by escape, or this if escaping wouldn't change it
+    fn escaped(&self, escape: impl FnOnce(&str) -> String) -> RcSubstring {
+        // Printable ASCII other than quotes and backslashes is never escaped
+        let plain = |b| matches!(b, b' '..=b'~') && !matches!(b, b'"' | b'\'' | b'\\');
+        if self.bytes().all(plain) {
+            return self.clone();
+        }
+        let escaped = escape(self);
+        if escaped == self.as_str() {
+            self.clone()
+        } else {
+            RcSubstring::from(escaped)
+        }
+    }
+}
+
+/**
+Displays text with backslash escapes for control and invisible chars
+
+Created by [RcSubstring::display_escaped]
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayEscaped<'a> {
+    text: &'a str,
+    // Only set with the color feature
+    color: bool,
+}
+
+impl DisplayEscaped<'_> {
+    /// Colour the escapes if `choice` says to
+    #[cfg(feature = "color")]
+    pub fn color(mut self, choice: crate::ColorChoice) -> Self {
+        self.color = choice.enabled();
+        self
+    }
+}
+
+impl Display for DisplayEscaped<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.text.chars() {
+            match c {
+                '\t' => write_marker(f, self.color, "\\t")?,
+                '\n' => write_marker(f, self.color, "\\n")?,
+                '\r' => write_marker(f, self.color, "\\r")?,
+                '\\' => write_marker(f, self.color, "\\\\")?,
+                c if is_invisible(c) => write_marker(f, self.color, c.escape_unicode())?,
+                c => f.write_char(c)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/**
+Displays text with whitespace and invisible chars replaced by visible symbols
+
+Created by [RcSubstring::show_invisibles]
+*/
+#[derive(Debug, Clone, Copy)]
+pub struct ShowInvisibles<'a> {
+    text: &'a str,
+    // Only set with the color feature
+    color: bool,
+}
+
+impl ShowInvisibles<'_> {
+    /// Colour the symbols if `choice` says to
+    #[cfg(feature = "color")]
+    pub fn color(mut self, choice: crate::ColorChoice) -> Self {
+        self.color = choice.enabled();
+        self
+    }
+}
+
+impl Display for ShowInvisibles<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rest = self.text;
+        while let Some(c) = rest.chars().next() {
+            if c == ' ' {
+                // Only a run of spaces that ends the line is shown
+                let spaces = rest.len() - rest.trim_start_matches(' ').len();
+                let after = rest[spaces..].chars().next();
+                let trailing = matches!(after, None | Some('\n' | '\r'));
+                for _ in 0..spaces {
+                    if trailing {
+                        write_marker(f, self.color, '·')?;
+                    } else {
+                        f.write_char(' ')?;
+                    }
+                }
+                rest = &rest[spaces..];
+                continue;
+            }
+            match c {
+                '\t' => write_marker(f, self.color, '→')?,
+                '\n' => write_marker(f, self.color, '↵')?,
+                '\u{a0}' => write_marker(f, self.color, '⍽')?,
+                '\u{7f}' => write_marker(f, self.color, '␡')?,
+                // The control pictures block follows the order of the C0 controls
+                '\0'..='\u{1f}' => {
+                    let picture = char::from_u32(0x2400 + c as u32).unwrap();
+                    write_marker(f, self.color, picture)?
+                }
+                c if is_invisible(c) => {
+                    write_marker(f, self.color, format_args!("<U+{:04X}>", c as u32))?
+                }
+                c => f.write_char(c)?,
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_display_escaped() {
+        let text = RcSubstring::from(String::from("\0x\u{200b}é\r\n\u{1b}"));
+        assert_eq!(
+            text.display_escaped().to_string(),
+            r"\u{0}x\u{200b}é\r\n\u{1b}"
+        );
+        assert_eq!(
+            format!("[{}]", RcSubstring::from(String::new()).display_escaped()),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn test_escape_owned() {
+        let source = Rc::new(String::from("plain text"));
+        let plain = RcSubstring::from(Rc::clone(&source));
+        assert!(Rc::ptr_eq(
+            plain.escape_debug_owned().shared().unwrap(),
+            &source
+        ));
+        assert!(Rc::ptr_eq(
+            plain.escape_default_owned().shared().unwrap(),
+            &source
+        ));
+        assert_eq!(plain.escape_unicode_owned().len(), 60);
+        let accented = RcSubstring::from(String::from("café"));
+        let unchanged = accented.escape_debug_owned();
+        assert!(unchanged.rcstring.same_text(&accented.rcstring));
+        assert_eq!(unchanged.range, accented.range);
+        assert_eq!(accented.escape_default_owned(), r"caf\u{e9}");
+        assert_eq!(
+            RcSubstring::from(String::from("'q'\n")).escape_debug_owned(),
+            r"\'q\'\n"
+        );
+        assert_eq!(RcSubstring::from(String::new()).escape_unicode_owned(), "");
+    }
+
+    #[test]
+    fn test_show_invisibles() {
+        let text = RcSubstring::from(String::from("a b  \n c\u{feff}\u{1}  "));
+        assert_eq!(text.show_invisibles().to_string(), "a b··↵ c<U+FEFF>␁··");
+        assert_eq!(
+            RcSubstring::from(String::from("   "))
+                .show_invisibles()
+                .to_string(),
+            "···"
+        );
+        assert_eq!(
+            RcSubstring::from(String::from("\u{7f}\u{a0}"))
+                .show_invisibles()
+                .to_string(),
+            "␡⍽"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn test_color() {
+        use crate::ColorChoice;
+        let text = RcSubstring::from(String::from("x \u{200b}\\"));
+        assert_eq!(
+            text.show_invisibles()
+                .color(ColorChoice::Always)
+                .to_string(),
+            "x \u{1b}[36m<U+200B>\u{1b}[0m\\"
+        );
+        assert_eq!(
+            text.display_escaped()
+                .color(ColorChoice::Always)
+                .to_string(),
+            "x \u{1b}[36m\\u{200b}\u{1b}[0m\u{1b}[36m\\\\\u{1b}[0m"
+        );
+        let plain = text.display_escaped().color(ColorChoice::Never);
+        assert_eq!(plain.to_string(), r"x \u{200b}\\");
+    }
+}
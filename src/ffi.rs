@@ -0,0 +1,155 @@
+//! A C interface to [RcSubstring]s as opaque handles
+//!
+//! A Rust library hands a substring to C with [RcSubstringHandle::into_raw]. C code reads it
+//! with [rcsubstring_data] and [rcsubstring_len], which give the UTF-8 text as a pointer and
+//! length (not nul terminated), and must release every handle with [rcsubstring_free].
+//! The reference count is not atomic so a handle and its clones must all be used from one thread.
+use crate::{IntoRcSubstring, RcSubstring};
+use std::ptr;
+
+/// An opaque handle to an [RcSubstring] for C code
+///
+/// C code only ever sees pointers to this.
+#[repr(C)]
+#[derive(Debug)]
+pub struct RcSubstringHandle {
+    substring: RcSubstring,
+}
+
+impl RcSubstringHandle {
+    /// Move `substring` into a new handle to pass to C
+    pub fn into_raw(substring: impl IntoRcSubstring) -> *mut RcSubstringHandle {
+        let substring = substring.into_rc_substring();
+        Box::into_raw(Box::new(RcSubstringHandle { substring }))
+    }
+
+    /// Take back the substring from a handle passed back from C, freeing the handle
+    ///
+    /// # Safety
+    ///
+    /// `handle` must have come from [RcSubstringHandle::into_raw] or [rcsubstring_clone],
+    /// and not been freed. It must not be used again afterwards.
+    pub unsafe fn from_raw(handle: *mut RcSubstringHandle) -> RcSubstring {
+        // SAFETY: the caller guarantees handle came from Box::into_raw and is still live
+        unsafe { Box::from_raw(handle) }.substring
+    }
+}
+
+/// Copy `len` bytes of UTF-8 text at `data` into a new handle
+///
+/// Returns null if the text is not valid UTF-8.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or may be null if `len` is 0.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcsubstring_new(data: *const u8, len: usize) -> *mut RcSubstringHandle {
+    let bytes = if len == 0 {
+        &[][..]
+    } else {
+        // SAFETY: the caller guarantees data points to len bytes
+        unsafe { std::slice::from_raw_parts(data, len) }
+    };
+    match std::str::from_utf8(bytes) {
+        Ok(text) => RcSubstringHandle::into_raw(text),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// A pointer to the UTF-8 text of the substring, which is not nul terminated
+///
+/// The pointer is valid until the handle is freed. Returns null for a null handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcsubstring_data(handle: *const RcSubstringHandle) -> *const u8 {
+    // SAFETY: the caller guarantees handle is null or live
+    match unsafe { handle.as_ref() } {
+        Some(handle) => handle.substring.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// The length in bytes of the text of the substring, or 0 for a null handle
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcsubstring_len(handle: *const RcSubstringHandle) -> usize {
+    // SAFETY: the caller guarantees handle is null or live
+    unsafe { handle.as_ref() }.map_or(0, |handle| handle.substring.len())
+}
+
+/// A new handle to the same text, sharing it rather than copying
+///
+/// Both handles must be freed. Returns null for a null handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcsubstring_clone(
+    handle: *const RcSubstringHandle,
+) -> *mut RcSubstringHandle {
+    // SAFETY: the caller guarantees handle is null or live
+    match unsafe { handle.as_ref() } {
+        Some(handle) => RcSubstringHandle::into_raw(handle.substring.clone()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Free a handle, releasing the text if no other handles or substrings share it
+///
+/// Does nothing for a null handle.
+///
+/// # Safety
+///
+/// `handle` must be null or a live handle, and must not be used again afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn rcsubstring_free(handle: *mut RcSubstringHandle) {
+    if !handle.is_null() {
+        // SAFETY: the caller guarantees handle is live and not used again
+        drop(unsafe { RcSubstringHandle::from_raw(handle) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_handles() {
+        let source = Rc::new(String::from("hello world"));
+        let handle = RcSubstringHandle::into_raw(RcSubstring::new(Rc::clone(&source), 6..11));
+        unsafe {
+            assert_eq!(rcsubstring_len(handle), 5);
+            let data = std::slice::from_raw_parts(rcsubstring_data(handle), 5);
+            assert_eq!(data, b"world");
+            let clone = rcsubstring_clone(handle);
+            rcsubstring_free(handle);
+            assert_eq!(Rc::strong_count(&source), 2);
+            assert_eq!(RcSubstringHandle::from_raw(clone), "world");
+        }
+        assert_eq!(Rc::strong_count(&source), 1);
+    }
+
+    #[test]
+    fn test_new_and_null() {
+        unsafe {
+            let handle = rcsubstring_new(b"abc".as_ptr(), 3);
+            assert_eq!(rcsubstring_len(handle), 3);
+            rcsubstring_free(handle);
+            assert!(rcsubstring_new(b"\xFF".as_ptr(), 1).is_null());
+            let empty = rcsubstring_new(ptr::null(), 0);
+            assert_eq!(rcsubstring_len(empty), 0);
+            rcsubstring_free(empty);
+            assert!(rcsubstring_data(ptr::null()).is_null());
+            assert!(rcsubstring_clone(ptr::null()).is_null());
+            rcsubstring_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! Matching an [RcSubstring] against a wildcard pattern
+use crate::RcSubstring;
+
+impl RcSubstring {
+    /// True if the whole of this substring matches the glob `pattern`
+    ///
+    /// In the pattern `*` matches any run of chars, `?` matches any one char and `[...]`
+    /// matches one char from a class such as `[abc]` or `[a-z]`, or not in it with `[!...]`
+    /// or `[^...]`. A backslash makes the next char literal. A `[` with no closing `]` is
+    /// literal too.
+    ///
+    /// Matching never backtracks more than to the last `*`, so it takes at most
+    /// O(pattern × text) time.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let path = RcSubstring::from(String::from("src/main.rs"));
+    /// assert!(path.matches_glob("src/*.rs"));
+    /// assert!(path.matches_glob("src/[lm]ain.?s"));
+    /// assert!(!path.matches_glob("*.toml"));
+    /// ```
+    pub fn matches_glob(&self, pattern: &str) -> bool {
+        glob_match(pattern, self)
+    }
+}
+
+enum Token<'a> {
+    Star,
+    Any,
+    Char(char),
+    Class { members: &'a str, negated: bool },
+}
+
+// The token at the start of pattern and its length in bytes
+fn token(pattern: &str) -> (Token<'_>, usize) {
+    let mut chars = pattern.chars();
+    let first = chars.next().expect("token of empty pattern");
+    match first {
+        '*' => (Token::Star, 1),
+        '?' => (Token::Any, 1),
+        '\\' => match chars.next() {
+            Some(c) => (Token::Char(c), 1 + c.len_utf8()),
+            None => (Token::Char('\\'), 1),
+        },
+        '[' => {
+            let body = &pattern[1..];
+            let negated = body.starts_with(['!', '^']);
+            let start = negated as usize;
+            // A ] straight after the [ is a member rather than the end
+            let search_from = start + body[start..].starts_with(']') as usize;
+            match body[search_from..].find(']') {
+                Some(i) => {
+                    let end = search_from + i;
+                    let members = &body[start..end];
+                    (Token::Class { members, negated }, 1 + end + 1)
+                }
+                None => (Token::Char('['), 1),
+            }
+        }
+        c => (Token::Char(c), c.len_utf8()),
+    }
+}
+
+// True if c is one of the chars or ranges in members
+fn class_contains(members: &str, c: char) -> bool {
+    let mut chars = members.chars();
+    while let Some(low) = chars.next() {
+        let mut ahead = chars.clone();
+        if ahead.next() == Some('-')
+            && let Some(high) = ahead.next()
+        {
+            if (low..=high).contains(&c) {
+                return true;
+            }
+            chars = ahead;
+        } else if low == c {
+            return true;
+        }
+    }
+    false
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Where to resume after the last star: the pattern after it and the text it has taken
+    let mut resume: Option<(usize, usize)> = None;
+    loop {
+        if p < pattern.len() {
+            let (token, len) = token(&pattern[p..]);
+            let next = text[t..].chars().next();
+            let matched = match (token, next) {
+                (Token::Star, _) => {
+                    p += len;
+                    resume = Some((p, t));
+                    continue;
+                }
+                (_, None) => false,
+                (Token::Any, Some(_)) => true,
+                (Token::Char(expected), Some(c)) => c == expected,
+                (Token::Class { members, negated }, Some(c)) => {
+                    class_contains(members, c) != negated
+                }
+            };
+            if let (true, Some(c)) = (matched, next) {
+                p += len;
+                t += c.len_utf8();
+                continue;
+            }
+        } else if t == text.len() {
+            return true;
+        }
+        // Let the last star take one more char and try again from after it
+        match resume {
+            Some((after_star, taken)) if taken < text.len() => {
+                let taken = taken + text[taken..].chars().next().map_or(0, char::len_utf8);
+                resume = Some((after_star, taken));
+                p = after_star;
+                t = taken;
+            }
+            _ => return false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wildcards() {
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "a"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("a*b*c", "aXbYbZc"));
+        assert!(!glob_match("a*b*c", "aXbYbZ"));
+        assert!(glob_match("??", "éa"));
+        assert!(!glob_match("?", ""));
+        assert!(glob_match("*.*", "a.b.c"));
+        assert!(glob_match("**a", "bba"));
+    }
+
+    #[test]
+    fn test_classes() {
+        assert!(glob_match("[a-c]x", "bx"));
+        assert!(!glob_match("[a-c]x", "dx"));
+        assert!(glob_match("[!a-c]", "d"));
+        assert!(glob_match("[^a]", "b"));
+        assert!(glob_match("[]]", "]"));
+        assert!(glob_match("[a-]", "-"));
+        assert!(glob_match("[é-ë]", "ê"));
+        assert!(glob_match("[ab", "[ab"));
+        assert!(glob_match(r"\*\?", "*?"));
+        assert!(!glob_match(r"\*", "a"));
+    }
+
+    #[test]
+    fn test_no_blowup() {
+        let text = "a".repeat(200);
+        let pattern = "a*".repeat(50) + "b";
+        assert!(!glob_match(&pattern, &text));
+    }
+}
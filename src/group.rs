@@ -0,0 +1,175 @@
+//! Telling the sources of [RcSubstring]s apart, and sorting substrings by their source
+use crate::{Backing, RcSubstring};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::rc::Rc;
+
+/**
+An opaque identity for the text a substring is of, for keying per-source maps and logging
+
+Every substring of the same `Rc<String>` has the same id, while different `Rc<String>`s have
+different ids even if their text is equal. Unlike the `Rc` itself an id doesn't keep the text
+alive. It comes from the address of the text, so it stays the same while any substring of
+the text is alive, but once the text has been freed its id may be reused by a new one.
+Text stored inside substrings with the `inline` feature has no lasting address, so it is
+numbered when it is stored instead, and substrings of it keep the number.
+
+```rust
+# use rcsubstring::{RcSubstring, SourceId};
+# use std::collections::HashMap;
+# use std::rc::Rc;
+let file = Rc::new(String::from("fn main() {}"));
+let name = RcSubstring::new(Rc::clone(&file), 3..7);
+let mut line_starts: HashMap<SourceId, Vec<usize>> = HashMap::new();
+line_starts.insert(SourceId::of_rc(&file), vec![0]);
+assert_eq!(line_starts[&name.source_id()], [0]);
+let copy = RcSubstring::from(String::from("fn main() {}"));
+assert_ne!(copy.source_id(), name.source_id());
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceId(SourceKind);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SourceKind {
+    // The address of the String in an Rc<String>
+    Shared(usize),
+    // The address of an arena page, whose text grows
+    Page(usize),
+    // The address and length of any other kind of text
+    Text(usize, usize),
+    // The id of text stored in the substring itself, which moves with it so has no address
+    #[cfg(feature = "inline")]
+    Inline(u32),
+}
+
+impl SourceId {
+    /// The id of `rcstring`, the same as the id of its substrings
+    pub fn of_rc(rcstring: &Rc<String>) -> Self {
+        SourceId(SourceKind::Shared(Rc::as_ptr(rcstring) as usize))
+    }
+}
+
+impl Display for SourceId {
+    /// Shows the id as a hex address, eg. for logging
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            SourceKind::Shared(address) | SourceKind::Page(address) => write!(f, "{:#x}", address),
+            SourceKind::Text(address, len) => write!(f, "{:#x}+{}", address, len),
+            #[cfg(feature = "inline")]
+            SourceKind::Inline(id) => write!(f, "inline#{}", id),
+        }
+    }
+}
+
+impl RcSubstring {
+    /// The identity of the text this is a substring of, see [SourceId]
+    pub fn source_id(&self) -> SourceId {
+        match &self.rcstring {
+            Backing::Shared(rcstring) => SourceId::of_rc(rcstring),
+            Backing::Page(page) => SourceId(SourceKind::Page(Rc::as_ptr(page) as usize)),
+            #[cfg(feature = "inline")]
+            Backing::Inline(text) => SourceId(SourceKind::Inline(text.id())),
+            backing => {
+                let text = backing.as_str();
+                SourceId(SourceKind::Text(text.as_ptr() as usize, text.len()))
+            }
+        }
+    }
+}
+
+/// Group `spans` by the `Rc<String>` they are substrings of
+///
+/// Groups are in the order their first span appears, and each keeps its spans in order.
+/// Sources are told apart by identity, so spans of different `Rc<String>`s with the same text
+/// are in different groups. Static substrings of the same text are also grouped, with the text
+/// copied into a new `Rc<String>` once that their spans are moved to.
+///
+/// ```rust
+/// # use rcsubstring::{RcSubstring, group_by_source};
+/// # use std::rc::Rc;
+/// let a = Rc::new(String::from("file a"));
+/// let b = Rc::new(String::from("file b"));
+/// let spans = [
+///     RcSubstring::new(Rc::clone(&a), 0..4),
+///     RcSubstring::new(Rc::clone(&b), 5..6),
+///     RcSubstring::new(Rc::clone(&a), 5..6),
+/// ];
+/// let groups = group_by_source(spans);
+/// assert_eq!(groups.len(), 2);
+/// assert!(Rc::ptr_eq(&groups[0].0, &a));
+/// assert_eq!(groups[0].1, ["file", "a"]);
+/// assert_eq!(groups[1].1, ["b"]);
+/// ```
+pub fn group_by_source(
+    spans: impl IntoIterator<Item = RcSubstring>,
+) -> Vec<(Rc<String>, Vec<RcSubstring>)> {
+    let mut groups: Vec<(Rc<String>, Vec<RcSubstring>)> = Vec::new();
+    let mut indexes: HashMap<SourceId, usize> = HashMap::new();
+    for span in spans {
+        let i = *indexes.entry(span.source_id()).or_insert_with(|| {
+            let (rcstring, _) = span.clone().into_shared();
+            groups.push((rcstring, Vec::new()));
+            groups.len() - 1
+        });
+        let (rcstring, group) = &mut groups[i];
+        if span.shared().is_some() {
+            group.push(span);
+        } else {
+            group.push(RcSubstring::new(Rc::clone(rcstring), span.range));
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups_by_identity() {
+        let a = Rc::new(String::from("same"));
+        let b = Rc::new(String::from("same"));
+        let spans = vec![
+            RcSubstring::new(Rc::clone(&b), 0..2),
+            RcSubstring::new(Rc::clone(&a), 1..3),
+            RcSubstring::new(Rc::clone(&b), 2..4),
+            RcSubstring::new(Rc::clone(&a), 0..4),
+        ];
+        let groups = group_by_source(spans);
+        assert_eq!(groups.len(), 2);
+        assert!(Rc::ptr_eq(&groups[0].0, &b));
+        assert_eq!(groups[0].1, ["sa", "me"]);
+        assert!(Rc::ptr_eq(&groups[1].0, &a));
+        assert_eq!(groups[1].1, ["am", "same"]);
+        assert!(group_by_source([]).is_empty());
+    }
+
+    #[test]
+    fn test_static_spans() {
+        let text = RcSubstring::from_static("static text");
+        let groups = group_by_source([text.reslice(0..6), text.reslice(7..11)]);
+        assert_eq!(groups.len(), 1);
+        let (rcstring, spans) = &groups[0];
+        assert_eq!(rcstring.as_str(), "static text");
+        assert_eq!(spans, &["static", "text"]);
+        for span in spans {
+            assert!(Rc::ptr_eq(span.shared().unwrap(), rcstring));
+        }
+    }
+
+    #[test]
+    fn test_source_id() {
+        let source = Rc::new(String::from("text"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        assert_eq!(text.reslice(1..2).source_id(), text.source_id());
+        assert_eq!(
+            text.source_id().to_string(),
+            format!("{:#x}", Rc::as_ptr(&source) as usize)
+        );
+        let constant = RcSubstring::from_static("text");
+        assert_eq!(constant.reslice(0..2).source_id(), constant.source_id());
+        assert_ne!(constant.source_id(), text.source_id());
+        assert!(constant.source_id().to_string().ends_with("+4"));
+    }
+}
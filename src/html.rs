@@ -0,0 +1,164 @@
+//! Decoding and escaping HTML character references in [RcSubstring]s
+use crate::RcSubstring;
+
+// The named references decoded, the ones HTML escapes and the commonest others
+const ENTITIES: &[(&str, char)] = &[
+    ("amp", '&'),
+    ("lt", '<'),
+    ("gt", '>'),
+    ("quot", '"'),
+    ("apos", '\''),
+    ("nbsp", '\u{a0}'),
+    ("copy", '©'),
+    ("reg", '®'),
+    ("trade", '™'),
+    ("hellip", '…'),
+    ("ndash", '–'),
+    ("mdash", '—'),
+    ("lsquo", '‘'),
+    ("rsquo", '’'),
+    ("ldquo", '“'),
+    ("rdquo", '”'),
+];
+
+impl RcSubstring {
+    /// Decode the HTML character references in this substring, such as `&amp;` and `&#233;`
+    ///
+    /// Numeric references and the common named ones are decoded; others are left as they
+    /// are, as are references without the closing `;`. Numeric references to chars that
+    /// can't be in text are replaced with U+FFFD. If there is nothing to decode this is just a
+    /// clone of `self` and nothing is allocated.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("caf&#xe9; &amp; &unknown;"));
+    /// assert_eq!(text.html_decoded(), "café & &unknown;");
+    /// ```
+    pub fn html_decoded(&self) -> RcSubstring {
+        let Some(first) = self.find('&') else {
+            return self.clone();
+        };
+        let mut decoded = String::with_capacity(self.len());
+        let mut changed = false;
+        let mut rest = &self[first..];
+        decoded.push_str(&self[..first]);
+        while let Some(amp) = rest.find('&') {
+            decoded.push_str(&rest[..amp]);
+            rest = &rest[amp..];
+            match decode_reference(&rest[1..]) {
+                Some((c, len)) => {
+                    decoded.push(c);
+                    rest = &rest[1 + len..];
+                    changed = true;
+                }
+                None => {
+                    decoded.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        if !changed {
+            return self.clone();
+        }
+        decoded.push_str(rest);
+        RcSubstring::from(decoded)
+    }
+
+    /// Escape `&`, `<`, `>`, `"` and `'` so the text can go in HTML, including attribute values
+    ///
+    /// If there is nothing to escape this is just a clone of `self` and nothing is allocated.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("<b>Tom & Jerry</b>"));
+    /// assert_eq!(text.html_escaped(), "&lt;b&gt;Tom &amp; Jerry&lt;/b&gt;");
+    /// ```
+    pub fn html_escaped(&self) -> RcSubstring {
+        let special = ['&', '<', '>', '"', '\''];
+        if !self.contains(special) {
+            return self.clone();
+        }
+        let mut escaped = String::with_capacity(self.len() + 8);
+        for c in self.chars() {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&#39;"),
+                c => escaped.push(c),
+            }
+        }
+        RcSubstring::from(escaped)
+    }
+}
+
+// The char of the reference that text after an & starts with, and the length of the
+// reference up to and including the ;
+fn decode_reference(text: &str) -> Option<(char, usize)> {
+    let end = text.find(';')?;
+    let name = &text[..end];
+    let c = if let Some(number) = name.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => parse_digits(hex, 16)?,
+            None => parse_digits(number, 10)?,
+        };
+        match code {
+            0 => char::REPLACEMENT_CHARACTER,
+            code => char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER),
+        }
+    } else {
+        ENTITIES
+            .iter()
+            .find(|(entity, _)| *entity == name)
+            .map(|&(_, c)| c)?
+    };
+    Some((c, end + 1))
+}
+
+// The value of digits in radix, saturating if it is too big for a u32
+fn parse_digits(digits: &str, radix: u32) -> Option<u32> {
+    if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    Some(u32::from_str_radix(digits, radix).unwrap_or(u32::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_decoded() {
+        let decoded = |text: &str| RcSubstring::from(String::from(text)).html_decoded();
+        assert_eq!(decoded("&lt;p&gt;&quot;&apos;&nbsp;"), "<p>\"'\u{a0}");
+        assert_eq!(decoded("&#65;&#X42;&#x1F600;"), "AB😀");
+        assert_eq!(
+            decoded("&#0;&#xd800;&#99999999999;"),
+            "\u{fffd}\u{fffd}\u{fffd}"
+        );
+        assert_eq!(
+            decoded("a & b &amp c &#; &#xg; &amp;"),
+            "a & b &amp c &#; &#xg; &"
+        );
+        assert_eq!(decoded("&&amp;"), "&&");
+    }
+
+    #[test]
+    fn test_unchanged_is_shared() {
+        let source = Rc::new(String::from("fish & chips &copy"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        assert!(Rc::ptr_eq(text.html_decoded().shared().unwrap(), &source));
+        let plain = text.subslice(0..4);
+        assert!(Rc::ptr_eq(plain.html_escaped().shared().unwrap(), &source));
+    }
+
+    #[test]
+    fn test_escaped() {
+        let text = RcSubstring::from(String::from("a=\"it's\" & é"));
+        let escaped = text.html_escaped();
+        assert_eq!(escaped, "a=&quot;it&#39;s&quot; &amp; é");
+        assert_eq!(escaped.html_decoded(), text);
+    }
+}
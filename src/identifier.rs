@@ -0,0 +1,141 @@
+//! Splitting identifiers into their words
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// Split a `camelCase` or `PascalCase` identifier into its words
+    ///
+    /// A new word starts at an uppercase letter following a lowercase letter or digit, and
+    /// at the last letter of a run of capitals followed by a lowercase letter, so acronyms
+    /// stay together. Digits stay with the word before them.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let name = RcSubstring::from(String::from("parseHTTPServer2Config"));
+    /// let words: Vec<_> = name.split_camel_case().collect();
+    /// assert_eq!(words, ["parse", "HTTP", "Server2", "Config"]);
+    /// ```
+    pub fn split_camel_case(&self) -> IdentifierWords {
+        IdentifierWords {
+            text: self.clone(),
+            pos: 0,
+            separators: false,
+        }
+    }
+
+    /// Split an identifier in any of `camelCase`, `PascalCase`, `snake_case` or `kebab-case` into its words
+    ///
+    /// Words are separated by `_` or `-`, which are not included, as well as split as for
+    /// [RcSubstring::split_camel_case]. Leading, trailing and repeated separators give no
+    /// empty words.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let name = RcSubstring::from(String::from("__XMLHttp_request-id"));
+    /// let words: Vec<_> = name.split_identifier().collect();
+    /// assert_eq!(words, ["XML", "Http", "request", "id"]);
+    /// ```
+    pub fn split_identifier(&self) -> IdentifierWords {
+        IdentifierWords {
+            text: self.clone(),
+            pos: 0,
+            separators: true,
+        }
+    }
+}
+
+/**
+Iterator over the words of an identifier
+
+Created by [RcSubstring::split_camel_case] or [RcSubstring::split_identifier]
+*/
+#[derive(Debug, Clone)]
+pub struct IdentifierWords {
+    text: RcSubstring,
+    pos: usize,
+    separators: bool,
+}
+
+fn is_separator(c: char) -> bool {
+    c == '_' || c == '-'
+}
+
+impl Iterator for IdentifierWords {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.separators {
+            let rest = &self.text[self.pos..];
+            self.pos += rest.len() - rest.trim_start_matches(is_separator).len();
+        }
+        let rest = &self.text[self.pos..];
+        let mut chars = rest.char_indices().peekable();
+        let (_, mut previous) = chars.next()?;
+        let mut len = rest.len();
+        while let Some((i, c)) = chars.next() {
+            let next = chars.peek().map(|&(_, next)| next);
+            let boundary = (self.separators && is_separator(c))
+                || (c.is_uppercase() && (previous.is_lowercase() || previous.is_numeric()))
+                || (c.is_uppercase()
+                    && previous.is_uppercase()
+                    && next.is_some_and(char::is_lowercase));
+            if boundary {
+                len = i;
+                break;
+            }
+            previous = c;
+        }
+        let word = self.text.reslice(self.pos..self.pos + len);
+        self.pos += len;
+        Some(word)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.text.len() - self.pos))
+    }
+}
+
+impl FusedIterator for IdentifierWords {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camel(text: &str) -> Vec<String> {
+        let text = RcSubstring::from(String::from(text));
+        text.split_camel_case()
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    fn identifier(text: &str) -> Vec<String> {
+        let text = RcSubstring::from(String::from(text));
+        text.split_identifier()
+            .map(|word| word.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_camel_case() {
+        assert_eq!(camel("camelCase"), ["camel", "Case"]);
+        assert_eq!(camel("PascalCase"), ["Pascal", "Case"]);
+        assert_eq!(camel("HTTPServer"), ["HTTP", "Server"]);
+        assert_eq!(camel("getURL"), ["get", "URL"]);
+        assert_eq!(camel("utf8Décodeur"), ["utf8", "Décodeur"]);
+        assert_eq!(camel("snake_case"), ["snake_case"]);
+        assert_eq!(camel("ÉtéÀ"), ["Été", "À"]);
+        assert!(camel("").is_empty());
+    }
+
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(identifier("snake_case_name"), ["snake", "case", "name"]);
+        assert_eq!(identifier("kebab-case"), ["kebab", "case"]);
+        assert_eq!(identifier("SCREAMING_SNAKE"), ["SCREAMING", "SNAKE"]);
+        assert_eq!(
+            identifier("_private__fieldName_"),
+            ["private", "field", "Name"]
+        );
+        assert!(identifier("__").is_empty());
+    }
+}
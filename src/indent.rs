@@ -0,0 +1,151 @@
+//! Measuring and removing the indentation of lines in an [RcSubstring]
+use crate::{LinesOwned, RcSubstring};
+use std::iter::FusedIterator;
+
+// The length of the spaces and tabs at the start of text
+fn indent_len(text: &str) -> usize {
+    text.len() - text.trim_start_matches([' ', '\t']).len()
+}
+
+impl RcSubstring {
+    /// The spaces and tabs at the start of this substring
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let line = RcSubstring::from(String::from("\t  return x;"));
+    /// assert_eq!(line.indentation(), "\t  ");
+    /// assert_eq!(line.indent_level(4), 6);
+    /// ```
+    pub fn indentation(&self) -> RcSubstring {
+        self.reslice(0..indent_len(self))
+    }
+
+    /// The column the text starts at after the indentation, with tabs stopping at every
+    /// `tab_width` columns
+    pub fn indent_level(&self, tab_width: usize) -> usize {
+        self[..indent_len(self)]
+            .chars()
+            .fold(0, |column, c| match c {
+                '\t' if tab_width > 0 => (column / tab_width + 1) * tab_width,
+                '\t' => column,
+                _ => column + 1,
+            })
+    }
+
+    /// The lines of this substring without the indentation they all have in common
+    ///
+    /// The common indentation is the longest run of spaces and tabs that starts every line
+    /// that isn't blank; blank lines come out empty. Lines are split as for [str::lines].
+    /// Each line is narrowed rather than copied.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let doc = RcSubstring::from(String::from("    fn main() {\n\n        run();\n    }"));
+    /// let lines: Vec<_> = doc.dedented_lines().collect();
+    /// assert_eq!(lines, ["fn main() {", "", "    run();", "}"]);
+    /// ```
+    pub fn dedented_lines(&self) -> DedentedLines {
+        let mut common: Option<&str> = None;
+        for line in self.lines() {
+            let indent = &line[..indent_len(line)];
+            if indent.len() == line.len() {
+                continue;
+            }
+            common = Some(match common {
+                None => indent,
+                Some(common) => {
+                    let len = common
+                        .bytes()
+                        .zip(indent.bytes())
+                        .take_while(|(a, b)| a == b)
+                        .count();
+                    &common[..len]
+                }
+            });
+        }
+        DedentedLines {
+            lines: self.lines_owned(),
+            indent: common.map_or(0, str::len),
+        }
+    }
+}
+
+/**
+An iterator over lines without their common indentation
+
+Created by [RcSubstring::dedented_lines]
+*/
+#[derive(Debug, Clone)]
+pub struct DedentedLines {
+    lines: LinesOwned,
+    indent: usize,
+}
+
+impl DedentedLines {
+    // The line without the common indentation, or empty if it is blank
+    fn dedent(&self, line: RcSubstring) -> RcSubstring {
+        let indent = indent_len(&line);
+        let start = if indent == line.len() {
+            indent
+        } else {
+            self.indent
+        };
+        line.reslice(start..line.len())
+    }
+}
+
+impl Iterator for DedentedLines {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        let line = self.lines.next()?;
+        Some(self.dedent(line))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for DedentedLines {
+    fn next_back(&mut self) -> Option<RcSubstring> {
+        let line = self.lines.next_back()?;
+        Some(self.dedent(line))
+    }
+}
+
+impl FusedIterator for DedentedLines {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_indent_level() {
+        assert_eq!(RcSubstring::from(String::from("x")).indent_level(4), 0);
+        assert_eq!(RcSubstring::from(String::from("  \tx")).indent_level(4), 4);
+        assert_eq!(
+            RcSubstring::from(String::from("\t\t x")).indent_level(8),
+            17
+        );
+        assert_eq!(RcSubstring::from(String::from(" \t")).indent_level(0), 1);
+        assert_eq!(RcSubstring::from(String::from("   ")).indentation(), "   ");
+    }
+
+    #[test]
+    fn test_dedented_lines() {
+        let source = Rc::new(String::from("\t  a\n    \n\t b\n\t  c\r\n"));
+        let text = RcSubstring::from(Rc::clone(&source));
+        let lines: Vec<_> = text.dedented_lines().collect();
+        assert_eq!(lines, [" a", "", "b", " c"]);
+        assert!(Rc::ptr_eq(lines[2].shared().unwrap(), &source));
+        let back: Vec<_> = text.dedented_lines().rev().collect();
+        assert_eq!(back, [" c", "b", "", " a"]);
+        let flush: Vec<_> = RcSubstring::from(String::from("a\n  b"))
+            .dedented_lines()
+            .collect();
+        assert_eq!(flush, ["a", "  b"]);
+        assert_eq!(RcSubstring::from(String::new()).dedented_lines().count(), 0);
+    }
+}
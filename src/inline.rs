@@ -0,0 +1,83 @@
+//! Short text stored inside an [RcSubstring](crate::RcSubstring) rather than in an `Rc<String>`
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// The most bytes of text stored inline, chosen so the text, its length and its id fit in
+// 23 bytes, the space left beside Backing's tag
+pub(crate) const INLINE_CAPACITY: usize = 18;
+
+// The id of the next text stored inline
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+// Text of up to INLINE_CAPACITY bytes, stored by value
+//
+// The text is copied with each substring, so copies are told apart from equal text stored
+// separately by the id it was given when stored. Equality compares the ids as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct InlineText {
+    // Bytes rather than a u32, so the text isn't aligned and doesn't make Backing bigger
+    id: [u8; 4],
+    len: u8,
+    bytes: [u8; INLINE_CAPACITY],
+}
+
+impl InlineText {
+    // A copy of text, or None if it is too long to store inline
+    pub(crate) fn new(text: &str) -> Option<Self> {
+        if text.len() > INLINE_CAPACITY {
+            return None;
+        }
+        let mut bytes = [0; INLINE_CAPACITY];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        Some(InlineText {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed).to_ne_bytes(),
+            len: text.len() as u8,
+            bytes,
+        })
+    }
+
+    // Which storing of text this is a copy of
+    pub(crate) fn id(&self) -> u32 {
+        u32::from_ne_bytes(self.id)
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: the bytes up to len were copied from a str, and are never changed
+        unsafe { std::str::from_utf8_unchecked(&self.bytes[..usize::from(self.len)]) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RcSubstring;
+
+    #[test]
+    fn test_short_text_is_inline() {
+        let keyword = RcSubstring::from(String::from("if"));
+        assert!(keyword.shared().is_none());
+        assert_eq!(keyword, "if");
+        let longest = RcSubstring::from("é".repeat(9));
+        assert!(longest.shared().is_none());
+        assert_eq!(longest.subslice(2..6), "éé");
+        let long = RcSubstring::from("x".repeat(19));
+        assert!(long.shared().is_some());
+        // Substrings of a shared source still share it
+        assert!(long.subslice(0..2).shared().is_some());
+    }
+
+    #[test]
+    fn test_inline_pieces_concat() {
+        let text = RcSubstring::from(String::from("ab"));
+        let pieces = [text.subslice(0..1), text.subslice(1..2)];
+        let runs: Vec<_> = RcSubstring::concat_adjacent_iter(pieces).collect();
+        assert_eq!(runs, ["ab"]);
+        assert_eq!(runs[0].source_id(), text.source_id());
+        // Equal text stored separately is different text
+        let copy = RcSubstring::from(String::from("ab"));
+        assert_ne!(text.source_id(), copy.source_id());
+        assert_eq!(copy, text);
+        assert!(text.source_id().to_string().starts_with("inline#"));
+        assert_eq!(text.reslice(1..2).relative_range_in(&text), Some(1..2));
+        assert_eq!(copy.relative_range_in(&text), None);
+    }
+}
@@ -0,0 +1,322 @@
+//! Deduplicating equal strings so they share one copy, and [Symbol]s standing for them
+use crate::{Arena, RcSubstring};
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+// Hashed and compared by content so the set can be looked up with a &str
+#[derive(Debug, Clone)]
+struct Interned(RcSubstring);
+
+impl PartialEq for Interned {
+    fn eq(&self, other: &Self) -> bool {
+        *self.0 == *other.0
+    }
+}
+
+impl Eq for Interned {}
+
+impl Hash for Interned {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (*self.0).hash(state)
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+/**
+Returns one canonical [RcSubstring] for each distinct string
+
+Tokenizers that produce the same identifiers over and over can intern them so that every
+copy shares one allocation rather than each getting its own `Rc<String>`.
+
+New strings are copied into the pages of an [Arena], so the distinct strings share a few
+large allocations too. [RcStringInterner::intern_sub] adopts the buffer of an existing
+substring without copying instead.
+
+```rust
+# use rcsubstring::RcStringInterner;
+let mut interner = RcStringInterner::new();
+let a = interner.get_or_intern("ident");
+let b = interner.get_or_intern("ident");
+assert_eq!(*a, *b);
+assert_eq!(interner.len(), 1);
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RcStringInterner {
+    strings: HashSet<Interned>,
+    // Where new strings are copied to
+    arena: Arena,
+}
+
+impl RcStringInterner {
+    /// Create an empty interner
+    pub fn new() -> Self {
+        RcStringInterner::default()
+    }
+
+    /// The number of distinct strings interned
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// True if nothing has been interned
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// The canonical substring for `text` if it has been interned
+    pub fn get(&self, text: &str) -> Option<RcSubstring> {
+        self.strings.get(text).map(|interned| interned.0.clone())
+    }
+
+    /// The canonical substring for `text`, copying it into the interner's arena if it is new
+    pub fn get_or_intern(&mut self, text: &str) -> RcSubstring {
+        if let Some(interned) = self.get(text) {
+            return interned;
+        }
+        let interned = self.arena.push_str(text);
+        self.strings.insert(Interned(interned.clone()));
+        interned
+    }
+
+    /// The canonical substring equal to `substring`
+    ///
+    /// If the text is new `substring` itself becomes the canonical copy, so nothing is
+    /// allocated, but note that keeps the whole of its `Rc<String>` alive.
+    pub fn intern_sub(&mut self, substring: &RcSubstring) -> RcSubstring {
+        if let Some(interned) = self.get(substring) {
+            return interned;
+        }
+        self.strings.insert(Interned(substring.clone()));
+        substring.clone()
+    }
+
+    /// The canonical substring for each of `texts`, in order
+    ///
+    /// The strings not already interned are copied one after another into the arena, so
+    /// unless its page fills up they are all in one allocation.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcStringInterner;
+    /// let mut interner = RcStringInterner::new();
+    /// let words = interner.intern_all("let x = x + y".split(' '));
+    /// assert_eq!(words.len(), 6);
+    /// assert_eq!(interner.len(), 5);
+    /// ```
+    pub fn intern_all<'a>(&mut self, texts: impl IntoIterator<Item = &'a str>) -> Vec<RcSubstring> {
+        texts
+            .into_iter()
+            .map(|text| self.get_or_intern(text))
+            .collect()
+    }
+}
+
+/**
+A small id for an interned string, which can be resolved back into its [RcSubstring]
+
+Symbols are `Copy` and compare and hash as a `u32`, so they are cheap to pass around and
+use as map keys in hot paths. Each belongs to the [SymbolTable] that made it.
+
+```rust
+# use rcsubstring::SymbolTable;
+let mut table = SymbolTable::new();
+let x = table.intern("x");
+let y = table.intern("y");
+assert_eq!(table.intern("x"), x);
+assert_ne!(x, y);
+assert_eq!(y.resolve(&table), "y");
+```
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    /// The text of this symbol
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was made by a table with fewer symbols than this one
+    #[track_caller]
+    pub fn resolve(self, table: &SymbolTable) -> RcSubstring {
+        table.resolve(self).clone()
+    }
+
+    /// The index of this symbol in its table, counting from 0 in the order they were made
+    pub fn index(self) -> u32 {
+        self.0
+    }
+}
+
+/**
+Interns strings as [Symbol]s, keeping one canonical [RcSubstring] for each
+
+Like [RcStringInterner], but it hands out ids rather than the substrings themselves.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<Interned, Symbol>,
+    // Indexed by symbol
+    strings: Vec<RcSubstring>,
+    // Where new strings are copied to
+    arena: Arena,
+}
+
+impl SymbolTable {
+    /// Create an empty table
+    pub fn new() -> Self {
+        SymbolTable::default()
+    }
+
+    /// The number of symbols
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    /// True if nothing has been interned
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// The symbol for `text` if it has been interned
+    pub fn get(&self, text: &str) -> Option<Symbol> {
+        self.symbols.get(text).copied()
+    }
+
+    /// The symbol for `text`, copying it into the table's arena if it is new
+    ///
+    /// # Panics
+    ///
+    /// Panics if there would be more than `u32::MAX` symbols
+    pub fn intern(&mut self, text: &str) -> Symbol {
+        match self.get(text) {
+            Some(symbol) => symbol,
+            None => {
+                let substring = self.arena.push_str(text);
+                self.insert(substring)
+            }
+        }
+    }
+
+    /// The symbol for the text of `substring`, which becomes the canonical copy if it is new
+    ///
+    /// As with [RcStringInterner::intern_sub] nothing is allocated, but the whole of the
+    /// `Rc<String>` is kept alive.
+    pub fn intern_sub(&mut self, substring: &RcSubstring) -> Symbol {
+        match self.get(substring) {
+            Some(symbol) => symbol,
+            None => self.insert(substring.clone()),
+        }
+    }
+
+    fn insert(&mut self, substring: RcSubstring) -> Symbol {
+        let index = u32::try_from(self.strings.len()).expect("too many symbols for a u32");
+        let symbol = Symbol(index);
+        self.symbols.insert(Interned(substring.clone()), symbol);
+        self.strings.push(substring);
+        symbol
+    }
+
+    /// The canonical substring of `symbol`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the symbol was made by a table with fewer symbols than this one
+    #[track_caller]
+    pub fn resolve(&self, symbol: Symbol) -> &RcSubstring {
+        self.strings.get(symbol.0 as usize).unwrap_or_else(|| {
+            panic!(
+                "symbol {} is not in this SymbolTable of {} symbols",
+                symbol.0,
+                self.strings.len()
+            )
+        })
+    }
+
+    /// The symbols and their text, in the order they were made
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (Symbol, &RcSubstring)> {
+        self.strings
+            .iter()
+            .enumerate()
+            .map(|(i, text)| (Symbol(i as u32), text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_get_or_intern_shares() {
+        let mut interner = RcStringInterner::new();
+        assert!(interner.is_empty());
+        let a = interner.get_or_intern("name");
+        let b = interner.get_or_intern("name");
+        assert!(a.rcstring.same_text(&b.rcstring));
+        assert_eq!(a.range, b.range);
+        assert!(interner.get("other").is_none());
+        // Different strings share the arena's page
+        let other = interner.get_or_intern("other");
+        assert!(other.rcstring.same_text(&a.rcstring));
+        assert_eq!(other.range, 4..9);
+        assert_eq!(interner.arena.page_count(), 1);
+    }
+
+    #[test]
+    fn test_intern_sub_adopts_buffer() {
+        let source = Rc::new(String::from("fn main"));
+        let main = RcSubstring::new(Rc::clone(&source), 3..7);
+        let mut interner = RcStringInterner::new();
+        let interned = interner.intern_sub(&main);
+        assert!(Rc::ptr_eq(interned.shared().unwrap(), &source));
+        let again = interner.get_or_intern("main");
+        assert!(Rc::ptr_eq(again.shared().unwrap(), &source));
+    }
+
+    #[test]
+    fn test_intern_all_packs_one_page() {
+        let mut interner = RcStringInterner::new();
+        let old = interner.get_or_intern("a");
+        let interned = interner.intern_all(["a", "bb", "ccc", "bb"]);
+        assert_eq!(interned[0].range, old.range);
+        assert!(interned.iter().all(|s| s.rcstring.same_text(&old.rcstring)));
+        let ranges: Vec<_> = interned.iter().map(|s| s.range.clone()).collect();
+        assert_eq!(ranges, [0..1, 1..3, 3..6, 1..3]);
+        assert_eq!(interned[2], "ccc");
+        assert_eq!(interner.len(), 3);
+        assert_eq!(interner.arena.page_count(), 1);
+    }
+
+    #[test]
+    fn test_symbols() {
+        let source = Rc::new(String::from("fn main"));
+        let mut table = SymbolTable::new();
+        assert!(table.is_empty());
+        let main = table.intern_sub(&RcSubstring::new(Rc::clone(&source), 3..7));
+        let fn_ = table.intern("fn");
+        assert_eq!(table.intern("main"), main);
+        assert_eq!(table.get("fn"), Some(fn_));
+        assert_eq!(table.get("other"), None);
+        assert_eq!((main.index(), fn_.index()), (0, 1));
+        assert!(Rc::ptr_eq(main.resolve(&table).shared().unwrap(), &source));
+        let texts: Vec<_> = table.iter().map(|(_, text)| text.clone()).collect();
+        assert_eq!(texts, ["main", "fn"]);
+        assert_eq!(table.len(), 2);
+        let x = table.intern("x").resolve(&table);
+        assert!(x.rcstring.same_text(&fn_.resolve(&table).rcstring));
+        assert_eq!(x.range, 2..3);
+    }
+
+    #[test]
+    #[should_panic(expected = "not in this SymbolTable")]
+    fn test_resolve_other_table() {
+        let mut other = SymbolTable::new();
+        other.intern("a").resolve(&SymbolTable::new());
+    }
+}
@@ -0,0 +1,307 @@
+//! Finding the string literals in a JSON document without parsing it, and escaping and
+//! unescaping their contents
+use crate::RcSubstring;
+use std::error::Error;
+use std::fmt::{Display, Write};
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// The contents of every string literal in this JSON text, keys included
+    ///
+    /// Strings without escapes are substrings of this one; only those with escapes are unescaped
+    /// into new text. The document is not otherwise checked, so this stops at an unterminated
+    /// string. Invalid `\u` escapes are replaced with U+FFFD.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let json = RcSubstring::from(String::from(r#"{"name": "café", "tags": ["a\"b"]}"#));
+    /// let strings: Vec<_> = json.json_strings().collect();
+    /// assert_eq!(strings, ["name", "café", "tags", "a\"b"]);
+    /// ```
+    pub fn json_strings(&self) -> JsonStrings {
+        JsonStrings {
+            text: self.clone(),
+            pos: 0,
+        }
+    }
+
+    /// This text as the contents of a JSON string literal, with its escapes replaced
+    ///
+    /// Text without a backslash is shared. Unlike [RcSubstring::json_strings], an unknown
+    /// escape or a `\u` escape that isn't four hex digits of a char or a surrogate pair is an
+    /// error.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSubstring, UnescapeError};
+    /// let value = RcSubstring::from(String::from(r#"caf\u00e9 \"au lait\""#));
+    /// assert_eq!(value.json_unescaped().unwrap(), "café \"au lait\"");
+    /// let bad = RcSubstring::from(String::from(r"C:\dos"));
+    /// assert_eq!(bad.json_unescaped(), Err(UnescapeError::InvalidEscape { index: 2 }));
+    /// ```
+    pub fn json_unescaped(&self) -> Result<RcSubstring, UnescapeError> {
+        if !self.contains('\\') {
+            return Ok(self.clone());
+        }
+        unescape_checked(self, true).map(RcSubstring::from)
+    }
+
+    /// This text escaped to be the contents of a JSON string literal
+    ///
+    /// Quotes, backslashes and control chars are escaped, and text without any of them is
+    /// shared.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let value = RcSubstring::from(String::from("say \"hi\"\n"));
+    /// assert_eq!(value.json_escaped(), r#"say \"hi\"\n"#);
+    /// ```
+    pub fn json_escaped(&self) -> RcSubstring {
+        let escaped = |c: char| matches!(c, '"' | '\\') || c < ' ';
+        if !self.contains(escaped) {
+            return self.clone();
+        }
+        let mut text = String::with_capacity(self.len() + 2);
+        for c in self.chars() {
+            match c {
+                '"' => text.push_str("\\\""),
+                '\\' => text.push_str("\\\\"),
+                '\u{8}' => text.push_str("\\b"),
+                '\u{c}' => text.push_str("\\f"),
+                '\n' => text.push_str("\\n"),
+                '\r' => text.push_str("\\r"),
+                '\t' => text.push_str("\\t"),
+                c if c < ' ' => write!(text, "\\u{:04x}", c as u32).unwrap(),
+                c => text.push(c),
+            }
+        }
+        RcSubstring::from(text)
+    }
+}
+
+/// Why [RcSubstring::json_unescaped] couldn't unescape some text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnescapeError {
+    /// A backslash is followed by something that isn't an escape, or ends the text
+    InvalidEscape {
+        /// The byte offset of the backslash
+        index: usize,
+    },
+    /// A `\u` escape isn't four hex digits, or is half of a surrogate pair
+    InvalidUnicode {
+        /// The byte offset of the backslash
+        index: usize,
+    },
+}
+
+impl Display for UnescapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnescapeError::InvalidEscape { index } => write!(f, "invalid escape at {}", index),
+            UnescapeError::InvalidUnicode { index } => {
+                write!(f, "invalid unicode escape at {}", index)
+            }
+        }
+    }
+}
+
+impl Error for UnescapeError {}
+
+/**
+An iterator over the contents of the string literals in a JSON document
+
+Created by [RcSubstring::json_strings]
+*/
+#[derive(Debug, Clone)]
+pub struct JsonStrings {
+    text: RcSubstring,
+    pos: usize,
+}
+
+impl Iterator for JsonStrings {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        let bytes = self.text.as_bytes();
+        let open = self.pos + bytes[self.pos..].iter().position(|&b| b == b'"')?;
+        let start = open + 1;
+        let mut escaped = false;
+        let mut i = start;
+        while i < bytes.len() {
+            match bytes[i] {
+                b'"' => {
+                    self.pos = i + 1;
+                    if escaped {
+                        return Some(RcSubstring::from(unescape(&self.text[start..i])));
+                    }
+                    return Some(self.text.reslice(start..i));
+                }
+                b'\\' => {
+                    escaped = true;
+                    i += 2;
+                }
+                _ => i += 1,
+            }
+        }
+        self.pos = bytes.len();
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Each string takes at least two quotes
+        (0, Some((self.text.len() - self.pos) / 2))
+    }
+}
+
+impl FusedIterator for JsonStrings {}
+
+// The text of a string literal's contents with its escapes replaced
+fn unescape(escaped: &str) -> String {
+    unescape_checked(escaped, false).expect("lenient unescaping can't fail")
+}
+
+// Unescape the contents of a string literal, erroring on bad escapes if strict and otherwise
+// keeping the escaped char or replacing a bad \u escape with U+FFFD
+fn unescape_checked(mut escaped: &str, strict: bool) -> Result<String, UnescapeError> {
+    let len = escaped.len();
+    let mut text = String::with_capacity(len);
+    while let Some(backslash) = escaped.find('\\') {
+        let index = len - escaped.len() + backslash;
+        text.push_str(&escaped[..backslash]);
+        let mut rest = escaped[backslash + 1..].chars();
+        match rest.next() {
+            Some('b') => text.push('\u{8}'),
+            Some('f') => text.push('\u{c}'),
+            Some('n') => text.push('\n'),
+            Some('r') => text.push('\r'),
+            Some('t') => text.push('\t'),
+            Some('u') => {
+                let (c, after) = match unescape_unicode(rest.as_str()) {
+                    Ok(unescaped) => unescaped,
+                    Err(_) if strict => return Err(UnescapeError::InvalidUnicode { index }),
+                    Err(after) => (char::REPLACEMENT_CHARACTER, after),
+                };
+                text.push(c);
+                rest = after.chars();
+            }
+            Some(c @ ('"' | '\\' | '/')) => text.push(c),
+            _ if strict => return Err(UnescapeError::InvalidEscape { index }),
+            Some(c) => text.push(c),
+            None => {}
+        }
+        escaped = rest.as_str();
+    }
+    text.push_str(escaped);
+    Ok(text)
+}
+
+// The char of a \u escape given the text after the u, and the text after the escape,
+// or if it is invalid the text to carry on from
+fn unescape_unicode(text: &str) -> Result<(char, &str), &str> {
+    let Some(high) = hex4(text) else {
+        return Err(text);
+    };
+    let rest = &text[4..];
+    if (0xd800..0xdc00).contains(&high)
+        && let Some(low) = rest.strip_prefix("\\u").and_then(hex4)
+        && (0xdc00..0xe000).contains(&low)
+    {
+        let c = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+        return Ok((char::from_u32(c).unwrap(), &rest[6..]));
+    }
+    char::from_u32(high).map(|c| (c, rest)).ok_or(rest)
+}
+
+// The value of four hex digits at the start of text
+fn hex4(text: &str) -> Option<u32> {
+    let digits = text.get(..4)?;
+    if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_plain_strings_are_shared() {
+        let source = Rc::new(String::from(r#"[1, "one", {"k": true}, ""]"#));
+        let strings: Vec<_> = RcSubstring::from(Rc::clone(&source))
+            .json_strings()
+            .collect();
+        assert_eq!(strings, ["one", "k", ""]);
+        for s in &strings {
+            assert!(Rc::ptr_eq(s.shared().unwrap(), &source));
+        }
+        assert_eq!(strings[0].range, 5..8);
+    }
+
+    #[test]
+    fn test_escapes() {
+        let json = RcSubstring::from(String::from(
+            r#""a\\" "\/\b\f\n\r\t" "\ud83d\ude00" "\ud83d" "\u12" "#,
+        ));
+        let strings: Vec<_> = json.json_strings().collect();
+        assert_eq!(
+            strings,
+            ["a\\", "/\u{8}\u{c}\n\r\t", "😀", "\u{fffd}", "\u{fffd}12"]
+        );
+    }
+
+    #[test]
+    fn test_json_unescaped() {
+        let source = Rc::new(String::from("plain"));
+        let plain = RcSubstring::from(Rc::clone(&source))
+            .json_unescaped()
+            .unwrap();
+        assert!(Rc::ptr_eq(plain.shared().unwrap(), &source));
+        let unescaped = |text: &str| RcSubstring::from(String::from(text)).json_unescaped();
+        assert_eq!(
+            unescaped(r#"\/\b\f\n\r\t\"\\\ud83d\ude00"#).unwrap(),
+            "/\u{8}\u{c}\n\r\t\"\\😀"
+        );
+        assert_eq!(
+            unescaped(r"ab\"),
+            Err(UnescapeError::InvalidEscape { index: 2 })
+        );
+        assert_eq!(
+            unescaped(r"é\x"),
+            Err(UnescapeError::InvalidEscape { index: 2 })
+        );
+        assert_eq!(
+            unescaped(r"\u12"),
+            Err(UnescapeError::InvalidUnicode { index: 0 })
+        );
+        assert_eq!(
+            unescaped(r"\n\ud83d"),
+            Err(UnescapeError::InvalidUnicode { index: 2 })
+        );
+        assert_eq!(
+            UnescapeError::InvalidUnicode { index: 2 }.to_string(),
+            "invalid unicode escape at 2"
+        );
+    }
+
+    #[test]
+    fn test_json_escaped() {
+        let source = Rc::new(String::from("plain é"));
+        let plain = RcSubstring::from(Rc::clone(&source)).json_escaped();
+        assert!(Rc::ptr_eq(plain.shared().unwrap(), &source));
+        let text = RcSubstring::from(String::from("\"\\/\u{8}\u{c}\n\r\t\u{1}\u{7f}"));
+        let escaped = text.json_escaped();
+        assert_eq!(escaped, "\\\"\\\\/\\b\\f\\n\\r\\t\\u0001\u{7f}");
+        assert_eq!(escaped.json_unescaped().unwrap(), text);
+    }
+
+    #[test]
+    fn test_unterminated() {
+        let json = RcSubstring::from(String::from(r#"["done", "not \"done"#));
+        let mut strings = json.json_strings();
+        assert_eq!(strings.next().unwrap(), "done");
+        assert_eq!(strings.next(), None);
+        assert_eq!(strings.next(), None);
+        assert_eq!(strings.size_hint(), (0, Some(0)));
+    }
+}
@@ -68,13 +68,223 @@ drop(it);
 assert_eq!(value, "five");
 ```
 
+# Optional features
+
+- `aho-corasick`: multi-pattern matching with `MultiPattern`, yielding matches as `RcSubstring`s
+- `regex`: regex matches and capture groups as `RcSubstring`s
+- `nom`: nom 7 input traits so parsers can consume an `RcSubstring` and return `RcSubstring` spans
+- `winnow`: winnow stream traits so parsers can consume an `RcSubstring` and return `RcSubstring` spans
+- `pest`: convert pest spans and pair trees into `RcSubstring`s with `RcPair`
+- `chumsky`: `RcSpan`, a chumsky span type carrying its source that converts to and from `RcSubstring`
+- `logos`: `SpannedLexer`, a logos lexer adapter yielding tokens with their text as `RcSubstring`s
+- `tree-sitter`: `node_text()` and the `NodeTexts` walker to keep syntax tree node text as `RcSubstring`s
+- `encoding`: decode legacy encodings with `encoding_rs` into an `Rc<String>`, mapping input byte ranges to `RcSubstring`s
+- `percent-encoding`: `percent_decoded()` and `percent_encoded()` that only allocate when something changes
+- `url`: `parse_url_components()` to check a URL with the `url` crate and return its components as `RcSubstring`s
+- `color`: `show_invisibles()` and `display_escaped()` can colour their symbols and escapes with ANSI codes, as chosen by a [ColorChoice]
+- `rayon`: `par_lines_owned()`, `par_split_owned()` and `par_split_whitespace_owned()` for a [Substring] of `Arc<str>`, `Arc<String>` or `&'static str`, splitting it in parallel into substrings of the same storage
+- `search-index`: `SearchIndex`, a suffix array over an `Rc<String>` for fast repeated searches
+- `cached-hash`: keep a hash of the text in each `RcSubstring` for faster hash map lookups.
+  The hash is kept in a `Cell`, so clippy's `mutable_key_type` lint fires on maps keyed by `RcSubstring`;
+  it is safe to allow as the cache never changes the hash.
+- `cached-char-count`: keep the number of chars in each `RcSubstring` once `char_count()` has counted them
+- `inline`: store text of up to 18 bytes that would be copied into a new `Rc<String>` in the `RcSubstring` itself instead, so short tokens don't allocate.
+  This doesn't make `RcSubstring` bigger. Only text that would be copied is inlined: substrings taken from shared text, eg. by `substring()`, still share it, so their ranges stay offsets in the source
+- `strict-validation`: check substring ranges in release builds too, including that they are on char boundaries
+- `provenance`: keep the label given by `RcSubstring::labeled()` and where each substring was created, to report with invalid ranges and in `Debug` output
+- `schemars`: `JsonSchema` for `RcSubstring`, with the same schema as a `String`
+- `wasm`: conversions between `RcSubstring` and JavaScript strings with `wasm-bindgen`
+- `pyo3`: conversions between `RcSubstring` and Python `str`, and the `PySpan` Python class
+- `ffi`: C functions to read, clone and free `RcSubstring`s through opaque handles
+- `similarity`: Levenshtein distance and Jaro-Winkler similarity for fuzzy matching
+- `json`: `json_strings()` to find the string literals in JSON text, and `json_unescaped()` and `json_escaped()` for their contents, sharing the source unless they have escapes
+- `serde`: [SharedSpans], a list of substrings serialized as a table of their sources and ranges so that they share text again when read back
+- `mmap`: [RcSubstring::map_file] and [RcSubstring::from_mmap] for substrings of memory-mapped files, checked to be UTF-8 once
+- `html`: `html_decoded()` and `html_escaped()` for HTML character references, sharing the text when nothing changes
+- `binary`: [RcSubBytes], a shared slice of bytes, with `decode_base64()` and `decode_hex()` to decode text into it and `encode_base64()` and `encode_hex()` back
+
 */
 #![warn(missing_docs)]
+use crate::storage::sealed::Text;
+use std::cmp::Ordering;
 use std::convert::AsRef;
 use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
 use std::ops::{Deref, Range};
+use std::panic::Location;
 use std::rc::Rc;
 
+#[cfg(feature = "aho-corasick")]
+mod multi_pattern;
+#[cfg(feature = "aho-corasick")]
+pub use crate::multi_pattern::{FindAny, MultiPattern, SplitAny};
+#[cfg(feature = "regex")]
+mod regex;
+#[cfg(feature = "regex")]
+pub use crate::regex::{RcCaptures, RegexMatches, RegexSplit};
+#[cfg(feature = "nom")]
+mod nom;
+#[cfg(feature = "nom")]
+pub use crate::nom::{OwnedCharIndices, OwnedChars};
+#[cfg(feature = "winnow")]
+mod winnow;
+#[cfg(feature = "winnow")]
+pub use crate::winnow::{CharOffsets, StreamCheckpoint};
+#[cfg(feature = "pest")]
+mod pest;
+#[cfg(feature = "pest")]
+pub use crate::pest::RcPair;
+#[cfg(feature = "chumsky")]
+mod chumsky;
+#[cfg(feature = "chumsky")]
+pub use crate::chumsky::RcSpan;
+#[cfg(feature = "logos")]
+mod logos;
+#[cfg(feature = "logos")]
+pub use crate::logos::SpannedLexer;
+#[cfg(feature = "tree-sitter")]
+mod tree_sitter;
+#[cfg(feature = "tree-sitter")]
+pub use crate::tree_sitter::{NodeTexts, node_text};
+#[cfg(feature = "encoding")]
+mod encoding;
+#[cfg(feature = "encoding")]
+pub use crate::encoding::{DecodedText, decode_to_rc};
+mod address;
+mod affix;
+mod arena;
+pub use crate::arena::Arena;
+mod ascii;
+pub use crate::ascii::AsciiSubstring;
+mod borrowed;
+mod builder;
+pub use crate::builder::{PendingSpan, RcStringBuilder};
+mod char_index;
+pub use crate::char_index::CharIndex;
+mod char_set;
+pub use crate::char_set::{CharSet, SplitAnyChar};
+mod chunked;
+pub use crate::chunked::{ChunkedLines, RcChunkedReader};
+mod comments;
+pub use crate::comments::WithoutComments;
+mod compact;
+pub use crate::compact::compact_all;
+mod concat;
+pub use crate::concat::ConcatAdjacent;
+mod convert;
+pub use crate::convert::IntoRcSubstring;
+mod csv;
+pub use crate::csv::QuotedRecords;
+mod debug;
+pub use crate::debug::{DebugFull, DebugSpan};
+mod delimited;
+pub use crate::delimited::DelimitedGroups;
+mod display;
+pub use crate::display::{EllipsizedMiddle, Quoted, Truncated};
+mod edit;
+pub use crate::edit::{AnchoredSubstring, Bias, Edit, Edited, apply_edit};
+mod error;
+pub use crate::error::{
+    EditError, RcSubstringError, RcSubstringErrorKind, SpansError, SpansErrorKind,
+};
+mod escape;
+pub use crate::escape::{DisplayEscaped, ShowInvisibles};
+mod glob;
+mod group;
+pub use crate::group::{SourceId, group_by_source};
+mod identifier;
+pub use crate::identifier::IdentifierWords;
+mod indent;
+pub use crate::indent::DedentedLines;
+mod interner;
+pub use crate::interner::{RcStringInterner, Symbol, SymbolTable};
+mod macros;
+#[doc(hidden)]
+pub use crate::macros::__rcsub_literal;
+mod newlines;
+pub use crate::newlines::NewlinePositions;
+mod number;
+mod offsets;
+mod pattern;
+pub use crate::pattern::{CursorMark, RcPattern, SplitCursor, SplitPattern};
+mod provenance;
+mod query;
+pub use crate::query::QueryPairs;
+mod redact;
+pub use crate::redact::{SpanSet, redact};
+mod registry;
+pub use crate::registry::SourceRegistry;
+mod runs;
+pub use crate::runs::{RunsBy, SpansOf};
+mod semver;
+pub use crate::semver::SemverSpans;
+mod shared_cow;
+pub use crate::shared_cow::SharedCow;
+mod source;
+pub use crate::source::{Source, read_lines_rc, read_to_rc};
+mod spans;
+pub use crate::spans::{OnInvalid, Spans};
+mod split;
+pub use crate::split::{Fields, LinesNumbered, LinesOwned, SplitOwned, WithOffsets};
+mod split_ext;
+pub use crate::split_ext::{FilterNonempty, Pairs, RcSplitExt, SpanWindow, TrimEach, WindowsSpans};
+mod storage;
+pub use crate::storage::StringStorage;
+mod substring_set;
+pub use crate::substring_set::SubstringSet;
+mod tokens;
+pub use crate::tokens::{SimpleTokens, TokenKind};
+mod trie;
+pub use crate::trie::{SubstringTrie, TrieIter};
+#[cfg(feature = "percent-encoding")]
+mod percent_encoding;
+#[cfg(feature = "search-index")]
+mod search_index;
+#[cfg(feature = "search-index")]
+pub use crate::search_index::SearchIndex;
+#[cfg(feature = "pyo3")]
+mod pyo3;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "pyo3")]
+pub use crate::pyo3::PySpan;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "similarity")]
+mod similarity;
+#[cfg(feature = "json")]
+pub use crate::json::{JsonStrings, UnescapeError};
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "serde")]
+pub use crate::serde::SharedSpans;
+#[cfg(feature = "binary")]
+mod binary;
+#[cfg(feature = "html")]
+mod html;
+#[cfg(feature = "binary")]
+pub use crate::binary::{DecodeError, RcSubBytes};
+#[cfg(feature = "url")]
+mod url;
+#[cfg(feature = "url")]
+pub use crate::url::UrlSpans;
+#[cfg(feature = "color")]
+mod color;
+#[cfg(feature = "color")]
+pub use crate::color::ColorChoice;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "rayon")]
+pub use crate::rayon::{ParLines, ParSplit, ParSplitWhitespace};
+#[cfg(feature = "inline")]
+mod inline;
+#[cfg(feature = "mmap")]
+mod mmap;
+
 /**
 A reference counted substring
 
@@ -83,30 +293,157 @@ The deref behaviour means this can be used just like a &str
 The advantage is the internal [Rc] handles the memory management so you don't have to worry about borrow lifetimes
 Useful for returning parts of a string that should live longer than the struct that returned them
 eg. from an iterator over a string stored in the iterator itself
+
+This is the [Substring] of `Rc<String>` storage, which can also be of static, arena pages
+and (with the `inline` feature) inline text.
 */
+pub type RcSubstring = Substring<Rc<String>>;
 
-#[derive(Debug)]
-pub struct RcSubstring {
-    rcstring: Rc<String>,
+/**
+A substring of any kind of [StringStorage]
+
+This is the generic form of [RcSubstring], which is `Substring<Rc<String>>`, for other
+storage, eg. `Substring<Arc<str>>` can be sent between threads. All of them deref to `str`,
+can be sliced further without copying, and compare, order and hash as their text. They don't
+implement `Borrow<str>`, as with the `cached-hash` feature they hash differently to a `str`.
+Most of the rest of the API is only for RcSubstring; use `From` to turn a
+`Substring<&'static str>` into one.
+
+```rust
+# use rcsubstring::Substring;
+# use std::sync::Arc;
+let source: Arc<str> = Arc::from("key=value");
+let value = Substring::new(Arc::clone(&source), 4..9);
+let handle = std::thread::spawn(move || value.substring(0..3).to_string());
+assert_eq!(handle.join().unwrap(), "val");
+```
+*/
+#[derive(Clone)]
+pub struct Substring<S: StringStorage = Rc<String>> {
+    rcstring: S::Repr,
     range: Range<usize>,
+    // Hash of the text, or 0 if not yet computed
+    #[cfg(feature = "cached-hash")]
+    hash: std::cell::Cell<u64>,
+    // Number of chars in the text, once counted
+    #[cfg(feature = "cached-char-count")]
+    char_count: std::cell::Cell<Option<usize>>,
+    // Where this was created, to report if the range turns out to be invalid
+    #[cfg(any(debug_assertions, feature = "provenance"))]
+    created_at: &'static Location<'static>,
+    // What made this, to report with where it was created
+    #[cfg(feature = "provenance")]
+    label: Option<&'static str>,
+}
+
+// The text an RcSubstring is a range of
+#[derive(Clone)]
+enum Backing {
+    Shared(Rc<String>),
+    Static(&'static str),
+    Page(Rc<crate::arena::Page>),
+    #[cfg(feature = "mmap")]
+    Mapped(Rc<crate::mmap::MappedText>),
+    #[cfg(feature = "inline")]
+    Inline(crate::inline::InlineText),
+}
+
+impl Backing {
+    fn as_str(&self) -> &str {
+        match self {
+            Backing::Shared(rcstring) => rcstring,
+            Backing::Static(text) => text,
+            Backing::Page(page) => page.as_str(),
+            #[cfg(feature = "mmap")]
+            Backing::Mapped(mapped) => mapped.as_str(),
+            #[cfg(feature = "inline")]
+            Backing::Inline(text) => text.as_str(),
+        }
+    }
+
+    // True if both are the same text, not just equal text
+    fn same_text(&self, other: &Backing) -> bool {
+        match (self, other) {
+            (Backing::Shared(a), Backing::Shared(b)) => Rc::ptr_eq(a, b),
+            (Backing::Static(a), Backing::Static(b)) => std::ptr::eq(*a, *b),
+            (Backing::Page(a), Backing::Page(b)) => Rc::ptr_eq(a, b),
+            #[cfg(feature = "mmap")]
+            (Backing::Mapped(a), Backing::Mapped(b)) => Rc::ptr_eq(a, b),
+            // Inline text is copied with each substring, so copies are told apart by id
+            #[cfg(feature = "inline")]
+            (Backing::Inline(a), Backing::Inline(b)) => a == b,
+            _ => false,
+        }
+    }
 }
 
-impl Display for RcSubstring {
+impl Debug for Backing {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<S: StringStorage> Display for Substring<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.deref())
     }
 }
 
-impl PartialEq<&str> for RcSubstring {
+impl<S: StringStorage> PartialEq<&str> for Substring<S> {
     fn eq(&self, other: &&str) -> bool {
         self.deref() == *other
     }
 }
 
-impl RcSubstring {
-    /// Construct a new RcSubstring
+impl<S: StringStorage> PartialEq<str> for Substring<S> {
+    fn eq(&self, other: &str) -> bool {
+        self.deref() == other
+    }
+}
+
+impl<S: StringStorage> PartialEq for Substring<S> {
+    fn eq(&self, other: &Self) -> bool {
+        #[cfg(feature = "cached-hash")]
+        if self.hash.get() != 0 && other.hash.get() != 0 && self.hash.get() != other.hash.get() {
+            return false;
+        }
+        self.deref() == other.deref()
+    }
+}
+
+impl<S: StringStorage> Eq for Substring<S> {}
+
+impl<S: StringStorage> PartialOrd for Substring<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: StringStorage> Ord for Substring<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.deref().cmp(other.deref())
+    }
+}
+
+impl<S: StringStorage> Hash for Substring<S> {
+    #[cfg(not(feature = "cached-hash"))]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.deref().hash(state)
+    }
+
+    /// With the `cached-hash` feature this writes a 64-bit hash of the text that is
+    /// computed the first time it is needed and then kept
+    #[cfg(feature = "cached-hash")]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.cached_hash())
+    }
+}
+
+impl<S: StringStorage> Substring<S> {
+    /// Construct a new substring
     ///
-    /// Takes the `Rc<String>` to wrap and the range for the substring in this text
+    /// Takes the shared text to wrap, such as an `Rc<String>`, and the range for the
+    /// substring in this text
     ///
     /// # Panics (in debug)
     ///
@@ -117,41 +454,247 @@ impl RcSubstring {
     /// If it didn't panic here it would panic during the slice when the RcSubstring is used
     /// so it is better to catch the issues at source.
     ///
-    /// These panics come from debug_assert! macros that are removed in release build
+    /// These panics come from checks that are removed in release build
     /// for efficiency. You will still get a panic when you try to get the slice.
-    pub fn new(rcstring: Rc<String>, range: Range<usize>) -> Self {
-        debug_assert!(
-            range.end >= range.start,
-            "begin < end ({} < {}) when creating RcSubstring",
-            range.start,
-            range.end
-        );
-        debug_assert!(
-            range.start <= rcstring.len(),
-            "start index {} out of bounds when creating RcSubstring",
-            range.start
-        );
+    /// In debug builds that panic says where the RcSubstring was created.
+    ///
+    /// With the `strict-validation` feature the checks are kept in release builds and
+    /// also panic if either end of `range` is not on a char boundary.
+    #[track_caller]
+    pub fn new(storage: S, range: Range<usize>) -> Self {
+        validate(storage.as_str(), &range);
+        Substring::from_parts(storage.into_repr(), range)
+    }
+
+    // Every substring is made here so any cached state starts out empty
+    #[track_caller]
+    fn from_parts(rcstring: S::Repr, range: Range<usize>) -> Self {
+        Substring {
+            rcstring,
+            range,
+            #[cfg(feature = "cached-hash")]
+            hash: std::cell::Cell::new(0),
+            #[cfg(feature = "cached-char-count")]
+            char_count: std::cell::Cell::new(None),
+            #[cfg(any(debug_assertions, feature = "provenance"))]
+            created_at: Location::caller(),
+            #[cfg(feature = "provenance")]
+            label: None,
+        }
+    }
+
+    // Change the range within the same text, eg. as a parser consumes it
+    pub(crate) fn set_range(&mut self, range: Range<usize>) {
+        self.range = range;
+        #[cfg(feature = "cached-hash")]
+        self.hash.set(0);
+        #[cfg(feature = "cached-char-count")]
+        self.char_count.set(None);
+    }
+
+    // The hash of the text, computing it if it hasn't been yet
+    #[cfg(feature = "cached-hash")]
+    fn cached_hash(&self) -> u64 {
+        if self.hash.get() == 0 {
+            // A fixed hasher so that equal text always has an equal hash
+            #[allow(deprecated)]
+            let mut hasher = std::hash::SipHasher::new();
+            self.deref().hash(&mut hasher);
+            // 0 means not computed so is never stored
+            self.hash.set(hasher.finish().max(1));
+        }
+        self.hash.get()
+    }
+
+    /// Construct a new substring sharing the same text
+    /// from a range relative to this substring
+    #[track_caller]
+    pub(crate) fn reslice(&self, range: Range<usize>) -> Self {
+        let range = self.range.start + range.start..self.range.start + range.end;
         debug_assert!(
-            range.end <= rcstring.len(),
-            "end index {} out of bounds when creating RcSubstring",
-            range.end
+            range.start <= range.end && range.end <= self.range.end,
+            "range {:?} out of bounds when reslicing RcSubstring",
+            range
         );
-        RcSubstring { rcstring, range }
+        #[cfg(feature = "strict-validation")]
+        validate(self.rcstring.as_str(), &range);
+        #[allow(unused_mut)]
+        let mut substring = Substring::from_parts(self.rcstring.clone(), range);
+        #[cfg(feature = "provenance")]
+        {
+            substring.label = self.label;
+        }
+        substring
+    }
+
+    /// The text of this substring as a `str`
+    ///
+    /// The same as dereferencing, but useful where a `&str` is needed and
+    /// deref coercion doesn't apply, eg. to record a field in a `tracing` event
+    /// without allocating: `info!(token = sub.as_str())`.
+    /// (`tracing`'s `Value` trait is sealed so can't be implemented for RcSubstring.)
+    pub fn as_str(&self) -> &str {
+        self
+    }
+
+    /// True if the range of this substring can be used to slice its text
+    ///
+    /// This is always true for substrings from checked constructors such as
+    /// [RcSubstring::try_new], but [RcSubstring::new] doesn't check everything.
+    pub fn is_valid(&self) -> bool {
+        self.rcstring.as_str().get(self.range.clone()).is_some()
+    }
+
+    /// The text of this substring, or an error if its range is invalid
+    ///
+    /// Unlike using it as a `str`, which panics if the range is invalid.
+    /// In debug builds, or with the `provenance` feature, the error says where the substring
+    /// was created.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSubstring, RcSubstringErrorKind};
+    /// # use std::rc::Rc;
+    /// let text = RcSubstring::new(Rc::new(String::from("née")), 0..3);
+    /// assert!(text.is_valid());
+    /// assert_eq!(text.as_str_checked().unwrap(), "né");
+    /// ```
+    pub fn as_str_checked(&self) -> Result<&str, RcSubstringError> {
+        let text = self.rcstring.as_str();
+        match RcSubstringError::check_kind(text, &self.range) {
+            Ok(()) => Ok(&text[self.range.clone()]),
+            Err(kind) => {
+                let error = RcSubstringError::new(kind, self.created_at(), text, &self.range);
+                Err(error.with_label(self.label()))
+            }
+        }
+    }
+
+    // Panic explaining why the range can't be used
+    #[cold]
+    #[inline(never)]
+    fn invalid_range(&self) -> ! {
+        let error = self
+            .as_str_checked()
+            .expect_err("only called for a range that can't be used");
+        panic!("{}", error)
+    }
+}
+
+impl RcSubstring {
+    /// The number of chars in the text, as `chars().count()` gives
+    ///
+    /// With the `cached-char-count` feature the count is kept after the first call, so
+    /// asking again for the same substring (or a clone of it) doesn't count them again.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("naïve"));
+    /// assert_eq!(text.len(), 6);
+    /// assert_eq!(text.char_count(), 5);
+    /// ```
+    pub fn char_count(&self) -> usize {
+        #[cfg(feature = "cached-char-count")]
+        if let Some(count) = self.char_count.get() {
+            return count;
+        }
+        let count = self.chars().count();
+        #[cfg(feature = "cached-char-count")]
+        self.char_count.set(Some(count));
+        count
+    }
+
+    /// Construct an RcSubstring for a `&'static str`
+    ///
+    /// Nothing is allocated or reference counted, so this is free for string constants.
+    /// It behaves just the same as any other RcSubstring.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let fallback = RcSubstring::from_static("(none)");
+    /// let parsed = RcSubstring::new(Rc::new(String::from("name=(none)")), 5..11);
+    /// assert_eq!(fallback, parsed);
+    /// ```
+    #[track_caller]
+    pub fn from_static(text: &'static str) -> Self {
+        RcSubstring::from_parts(Backing::Static(text), 0..text.len())
     }
+
+    // The shared Rc<String>, or None if this is a static, arena, memory-mapped or inline substring
+    pub(crate) fn shared(&self) -> Option<&Rc<String>> {
+        match &self.rcstring {
+            Backing::Shared(rcstring) => Some(rcstring),
+            _ => None,
+        }
+    }
+
+    // The Rc<String> and range, copying the text into a new Rc<String> if it isn't shared
+    pub(crate) fn into_shared(self) -> (Rc<String>, Range<usize>) {
+        match self.rcstring {
+            Backing::Shared(rcstring) => (rcstring, self.range),
+            backing => (Rc::new(String::from(backing.as_str())), self.range),
+        }
+    }
+}
+
+// Panic if range can't be used for a substring of text, as far as is checked in this build
+#[track_caller]
+fn validate(text: &str, range: &Range<usize>) {
+    validate_labeled(text, range, None)
 }
 
-impl Deref for RcSubstring {
+// As validate, naming the label of the substring in the panic
+#[track_caller]
+fn validate_labeled(text: &str, range: &Range<usize>, label: Option<&'static str>) {
+    #[cfg(feature = "strict-validation")]
+    let result = RcSubstringError::check_kind(text, range);
+    #[cfg(all(debug_assertions, not(feature = "strict-validation")))]
+    let result = RcSubstringError::check_bounds(text, range);
+    #[cfg(not(any(debug_assertions, feature = "strict-validation")))]
+    let result: Result<(), RcSubstringErrorKind> = Ok(());
+    if let Err(kind) = result {
+        let error =
+            RcSubstringError::new(kind, Some(Location::caller()), text, range).with_label(label);
+        panic!("{}", error);
+    }
+}
+
+impl From<Rc<String>> for RcSubstring {
+    /// Wrap the whole of an `Rc<String>`
+    fn from(rcstring: Rc<String>) -> Self {
+        let len = rcstring.len();
+        RcSubstring::new(rcstring, 0..len)
+    }
+}
+
+impl From<String> for RcSubstring {
+    /// Move a `String` into a new `Rc` and wrap the whole of it
+    ///
+    /// With the `inline` feature a short string is copied into the RcSubstring instead.
+    fn from(string: String) -> Self {
+        #[cfg(feature = "inline")]
+        if let Some(text) = crate::inline::InlineText::new(&string) {
+            return RcSubstring::from_parts(Backing::Inline(text), 0..string.len());
+        }
+        RcSubstring::from(Rc::new(string))
+    }
+}
+
+impl<S: StringStorage> Deref for Substring<S> {
     type Target = str;
 
     fn deref(&self) -> &Self::Target {
-        &self.rcstring[self.range.start..self.range.end]
+        match self.rcstring.as_str().get(self.range.clone()) {
+            Some(text) => text,
+            None => self.invalid_range(),
+        }
     }
 }
 
-impl<T> AsRef<T> for RcSubstring
+impl<S: StringStorage, T> AsRef<T> for Substring<S>
 where
     T: ?Sized,
-    <RcSubstring as Deref>::Target: AsRef<T>,
+    str: AsRef<T>,
 {
     fn as_ref(&self) -> &T {
         self.deref().as_ref()
@@ -162,6 +705,22 @@ where
 mod tests {
     use super::*;
 
+    // The same with and without the inline feature, as inline text fits in the space Backing
+    // already needs for a static str
+    #[test]
+    #[cfg(all(
+        target_pointer_width = "64",
+        not(any(
+            feature = "cached-hash",
+            feature = "cached-char-count",
+            feature = "provenance"
+        ))
+    ))]
+    fn test_size() {
+        let created_at = if cfg!(debug_assertions) { 8 } else { 0 };
+        assert_eq!(std::mem::size_of::<RcSubstring>(), 40 + created_at);
+    }
+
     #[test]
     fn test_basic_usage() {
         let text = "Line 1\nLine 2\nLine 3";
@@ -173,14 +732,15 @@ mod tests {
         let debug_rep = format!("{:?}", rcsubstring);
         assert_eq!(
             debug_rep,
-            "RcSubstring { rcstring: \"Line 1\\nLine 2\\nLine 3\", range: 0..6 }"
+            "RcSubstring { text: \"Line 1\", range: 0..6, source_len: 20, source: \"Line 1\\nLine 2\\nLine 3\" }"
         );
-        let pretty_rep = format!("{:#?}", rcsubstring);
+        let full_rep = format!("{:?}", rcsubstring.debug_full());
         assert_eq!(
-            pretty_rep,
-            "RcSubstring {\n    rcstring: \"Line 1\\nLine 2\\nLine 3\",\n    range: 0..6,\n}"
+            full_rep,
+            "RcSubstring { rcstring: \"Line 1\\nLine 2\\nLine 3\", range: 0..6 }"
         );
         assert_eq!(&rcsubstring[1..2], "i");
+        assert_eq!(rcsubstring.as_str(), "Line 1");
     }
 
     #[test]
@@ -201,23 +761,121 @@ mod tests {
         is_hello(rcss);
     }
 
+    #[test]
+    fn test_from() {
+        let rcstring = Rc::new(String::from("whole text"));
+        let rcsubstring = RcSubstring::from(Rc::clone(&rcstring));
+        assert_eq!(rcsubstring, "whole text");
+        assert_eq!(rcsubstring.range, 0..10);
+        assert_eq!(RcSubstring::from(String::from("owned")), "owned");
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_from_static() {
+        use std::collections::HashSet;
+        let constant = RcSubstring::from_static("key: value");
+        assert!(constant.shared().is_none());
+        let shared = RcSubstring::new(Rc::new(String::from("-- key: value")), 3..13);
+        assert_eq!(constant, shared);
+        assert_eq!(constant.reslice(5..10), "value");
+        let set: HashSet<_> = [constant, shared].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert_eq!(
+            format!("{:?}", RcSubstring::from_static("a").debug_full()),
+            "RcSubstring { rcstring: \"a\", range: 0..1 }"
+        );
+    }
+
+    #[cfg(feature = "cached-hash")]
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_cached_hash() {
+        use std::collections::HashMap;
+        let long = "x".repeat(1000);
+        let a = RcSubstring::from(long.clone() + "a");
+        let b = RcSubstring::new(Rc::new(long.clone() + "ab"), 0..1001);
+        let mut map = HashMap::new();
+        map.insert(a.clone(), 1);
+        assert_ne!(map.keys().next().unwrap().hash.get(), 0);
+        assert_eq!(map.get(&b), Some(&1));
+        let c = RcSubstring::from(long + "c");
+        assert!(!map.contains_key(&c));
+        assert_ne!(a, c);
+        assert_eq!(a.reslice(0..1000).hash.get(), 0);
+    }
+
+    #[test]
+    fn test_char_count() {
+        let mut text = RcSubstring::from(String::from("añb€"));
+        assert_eq!(text.char_count(), 4);
+        assert_eq!(text.clone().char_count(), 4);
+        #[cfg(feature = "cached-char-count")]
+        assert_eq!(text.char_count.get(), Some(4));
+        text.set_range(1..3);
+        assert_eq!(text.char_count(), 1);
+        assert_eq!(text.reslice(0..0).char_count(), 0);
+    }
+
     // Test these bad uses panic with our own message - ie. not in some other downstream code
 
+    // new only checks ranges in debug builds or with strict-validation
+    #[cfg(any(debug_assertions, feature = "strict-validation"))]
     #[test]
     #[should_panic(expected = "RcSubstring")]
+    #[allow(clippy::reversed_empty_ranges)]
     fn test_end_before_start() {
         let _ = RcSubstring::new(Rc::new(String::from("Random text")), 3..0);
     }
 
+    // new only checks ranges in debug builds or with strict-validation
+    #[cfg(any(debug_assertions, feature = "strict-validation"))]
     #[test]
     #[should_panic(expected = "RcSubstring")]
     fn test_start_out_of_range() {
         let _ = RcSubstring::new(Rc::new(String::from("Random text")), 100..101);
     }
 
+    // new only checks ranges in debug builds or with strict-validation
+    #[cfg(any(debug_assertions, feature = "strict-validation"))]
     #[test]
     #[should_panic(expected = "RcSubstring")]
     fn test_end_out_of_range() {
         let _ = RcSubstring::new(Rc::new(String::from("Random text")), 0..101);
     }
+
+    #[cfg(not(feature = "strict-validation"))]
+    #[test]
+    fn test_as_str_checked() {
+        let rcsubstring = RcSubstring::new(Rc::new(String::from("née")), 0..2);
+        assert!(!rcsubstring.is_valid());
+        let error = rcsubstring.as_str_checked().unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &RcSubstringErrorKind::NotCharBoundary { index: 2 }
+        );
+        assert_eq!(error.excerpt(), "née");
+    }
+
+    #[cfg(feature = "strict-validation")]
+    #[test]
+    #[should_panic(expected = "index 2 is not a char boundary for RcSubstring")]
+    fn test_strict_char_boundary() {
+        let _ = RcSubstring::new(Rc::new(String::from("née")), 0..2);
+    }
+
+    #[cfg(not(feature = "strict-validation"))]
+    #[test]
+    fn test_panic_says_where_created() {
+        let line = line!() + 1;
+        let rcsubstring = RcSubstring::new(Rc::new(String::from("Random é")), 0..8);
+        let message = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| rcsubstring.len()))
+            .unwrap_err()
+            .downcast::<String>()
+            .unwrap();
+        assert!(message.starts_with("index 8 is not a char boundary for RcSubstring"));
+        #[cfg(debug_assertions)]
+        assert!(message.contains(&format!("created at src/lib.rs:{}:27", line)));
+        assert!(message.ends_with("of text \"Random é\""));
+    }
 }
@@ -0,0 +1,110 @@
+//! A `logos` lexer adapter yielding tokens with their text as [RcSubstring]s
+use crate::RcSubstring;
+use ::logos::{Lexer, Logos};
+use std::rc::Rc;
+
+/**
+A `logos` lexer over an `Rc<String>` yielding each token with its text as an [RcSubstring]
+
+The `slice()` of a plain [Lexer] borrows the source, so tokens can't outlive the lexer input.
+This wraps the lexer and turns each token's span into an [RcSubstring] sharing the source.
+
+```rust
+# use rcsubstring::SpannedLexer;
+# use std::rc::Rc;
+use logos::Logos;
+
+#[derive(Logos, Debug, PartialEq)]
+#[logos(skip r"\s+")]
+enum Token {
+    #[regex("[a-z]+")]
+    Word,
+    #[regex("[0-9]+")]
+    Number,
+}
+
+let source = Rc::new(String::from("route 66"));
+let tokens: Vec<_> = SpannedLexer::<Token>::new(&source).collect();
+drop(source);
+assert_eq!(tokens[0].0, Ok(Token::Word));
+assert_eq!(tokens[0].1, "route");
+assert_eq!(tokens[1].0, Ok(Token::Number));
+assert_eq!(tokens[1].1, "66");
+```
+*/
+pub struct SpannedLexer<'s, T: Logos<'s, Source = str>> {
+    source: &'s Rc<String>,
+    lexer: Lexer<'s, T>,
+}
+
+impl<'s, T: Logos<'s, Source = str>> SpannedLexer<'s, T> {
+    /// Create a lexer over the whole of `source`
+    pub fn new(source: &'s Rc<String>) -> Self
+    where
+        T::Extras: Default,
+    {
+        SpannedLexer {
+            source,
+            lexer: Lexer::new(source.as_str()),
+        }
+    }
+
+    /// Create a lexer over the whole of `source` with the given extras
+    pub fn with_extras(source: &'s Rc<String>, extras: T::Extras) -> Self {
+        SpannedLexer {
+            source,
+            lexer: Lexer::with_extras(source.as_str(), extras),
+        }
+    }
+
+    /// The wrapped lexer
+    pub fn lexer(&self) -> &Lexer<'s, T> {
+        &self.lexer
+    }
+
+    /// The wrapped lexer, eg. to update its extras
+    pub fn lexer_mut(&mut self) -> &mut Lexer<'s, T> {
+        &mut self.lexer
+    }
+
+    /// Unwrap the lexer
+    pub fn into_lexer(self) -> Lexer<'s, T> {
+        self.lexer
+    }
+}
+
+impl<'s, T: Logos<'s, Source = str>> Iterator for SpannedLexer<'s, T> {
+    type Item = (Result<T, T::Error>, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lexer.next()?;
+        let text = RcSubstring::new(Rc::clone(self.source), self.lexer.span());
+        Some((token, text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Logos, Debug, PartialEq)]
+    #[logos(skip r" +")]
+    enum Token {
+        #[token("=")]
+        Equals,
+        #[regex("[a-z]+")]
+        Name,
+    }
+
+    #[test]
+    fn test_errors_have_spans() {
+        let source = Rc::new(String::from("a = b ? c"));
+        let tokens: Vec<_> = SpannedLexer::<Token>::new(&source).collect();
+        assert_eq!(tokens.len(), 5);
+        assert_eq!(tokens[1].0, Ok(Token::Equals));
+        assert_eq!(tokens[3].0, Err(()));
+        assert_eq!(tokens[3].1, "?");
+        assert_eq!(tokens[3].1.range, 6..7);
+        assert_eq!(tokens[4].1, "c");
+    }
+}
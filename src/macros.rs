@@ -0,0 +1,140 @@
+//! The [rcsub!](crate::rcsub) and [subslice!](crate::subslice) macros
+use crate::{RcStringInterner, RcSubstring, RcSubstringError};
+use std::cell::RefCell;
+use std::ops::{Bound, RangeBounds};
+
+thread_local! {
+    static LITERALS: RefCell<RcStringInterner> = RefCell::new(RcStringInterner::new());
+}
+
+// Called by the rcsub! expansion
+#[doc(hidden)]
+pub fn __rcsub_literal(literal: &'static str) -> RcSubstring {
+    LITERALS.with(|literals| literals.borrow_mut().get_or_intern(literal))
+}
+
+/**
+An [RcSubstring] for a string literal
+
+Each thread keeps a cache of the literals it has seen so every use of the same literal on
+that thread shares one copy, which is handy for test fixtures and default values.
+The cache lives until the thread exits.
+
+```rust
+# use rcsubstring::rcsub;
+let a = rcsub!("default");
+let b = rcsub!("default");
+assert_eq!(a, "default");
+assert_eq!(*a, *b);
+```
+*/
+#[macro_export]
+macro_rules! rcsub {
+    ($literal:literal) => {{
+        let literal: &'static str = $literal;
+        $crate::__rcsub_literal(literal)
+    }};
+}
+
+impl RcSubstring {
+    /// A substring of this substring sharing the same `Rc<String>`, with `range` relative to it
+    ///
+    /// Takes any kind of range, like slicing a `str`, and checks it just as thoroughly.
+    /// [subslice!](crate::subslice) is shorthand for this.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or either end is not on a char boundary
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("hello world"));
+    /// assert_eq!(text.subslice(6..), "world");
+    /// assert_eq!(text.subslice(..=4), "hello");
+    /// ```
+    #[track_caller]
+    pub fn subslice(&self, range: impl RangeBounds<usize>) -> RcSubstring {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        let range = start..end;
+        if let Err(error) = RcSubstringError::check(self, &range) {
+            panic!("{}", error);
+        }
+        self.reslice(range)
+    }
+}
+
+/**
+A substring of an [RcSubstring], like slicing a `str` but keeping the result shared
+
+`subslice!(text, range)` is the same as `text.subslice(range)`, for any kind of range.
+
+```rust
+# use rcsubstring::{RcSubstring, subslice};
+let text = RcSubstring::from(String::from("key=value"));
+assert_eq!(subslice!(text, ..3), "key");
+assert_eq!(subslice!(text, 4..), "value");
+assert_eq!(subslice!(text, 4..=6), "val");
+assert_eq!(subslice!(text, ..), "key=value");
+```
+*/
+#[macro_export]
+macro_rules! subslice {
+    ($substring:expr, $range:expr) => {
+        $crate::RcSubstring::subslice(&$substring, $range)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RcSubstring;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_literals_shared_per_thread() {
+        let make = || rcsub!("fixture");
+        let a = make();
+        let b = rcsub!("fixture");
+        assert!(a.rcstring.same_text(&b.rcstring));
+        assert_eq!(a.range, b.range);
+        let other = std::thread::spawn(|| rcsub!("fixture").to_string())
+            .join()
+            .unwrap();
+        assert_eq!(other, "fixture");
+        assert_eq!(rcsub!("other"), "other");
+    }
+
+    #[test]
+    fn test_subslice() {
+        let source = Rc::new(String::from("aé b"));
+        let text = RcSubstring::new(Rc::clone(&source), 1..5);
+        let b = subslice!(text, 3..);
+        assert_eq!(b, "b");
+        assert!(Rc::ptr_eq(b.shared().unwrap(), &source));
+        assert_eq!(subslice!(text, ..2), "é");
+        assert_eq!(subslice!(text, 0..=2), "é ");
+        assert_eq!(subslice!(text, 4..), "");
+    }
+
+    #[test]
+    #[should_panic(expected = "char boundary")]
+    fn test_subslice_not_char_boundary() {
+        let text = RcSubstring::from(String::from("é"));
+        subslice!(text, 1..);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_subslice_out_of_bounds() {
+        let text = RcSubstring::from(String::from("abc"));
+        text.subslice(1..=3);
+    }
+}
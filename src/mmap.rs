@@ -0,0 +1,104 @@
+//! Substrings of memory-mapped files with `memmap2`
+use crate::{Backing, RcSubstring};
+use ::memmap2::Mmap;
+use std::fs::File;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::rc::Rc;
+
+// A memory map that has been checked to be valid UTF-8
+pub(crate) struct MappedText(Mmap);
+
+impl MappedText {
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: checked to be UTF-8 when created, and the contents of the map are not
+        // changed through it
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+}
+
+impl RcSubstring {
+    /// Construct an RcSubstring of the whole of a memory map, checking it is UTF-8 once
+    ///
+    /// Substrings of the result share the map, which is unmapped once they are all dropped.
+    /// [crate::compact_all] leaves them as they are, so copy out any that should outlive it.
+    ///
+    /// Errors if the map is not valid UTF-8
+    pub fn from_mmap(mmap: Mmap) -> io::Result<RcSubstring> {
+        let len = std::str::from_utf8(&mmap)
+            .map_err(|error| io::Error::new(ErrorKind::InvalidData, error))?
+            .len();
+        Ok(RcSubstring::from_parts(
+            Backing::Mapped(Rc::new(MappedText(mmap))),
+            0..len,
+        ))
+    }
+
+    /// Memory-map the file at `path` and construct an RcSubstring of all of it
+    ///
+    /// This avoids reading huge files into a `String`, but the whole file is still checked
+    /// to be UTF-8 straight away.
+    ///
+    /// Errors if the file can't be opened or mapped, or is not valid UTF-8
+    ///
+    /// # Safety
+    ///
+    /// As for [Mmap::map], the file must not be changed, by this or any other process,
+    /// while the substring or any of its substrings are alive. Otherwise the text could
+    /// change or stop being UTF-8.
+    ///
+    /// ```rust,no_run
+    /// # use rcsubstring::RcSubstring;
+    /// # fn main() -> std::io::Result<()> {
+    /// let corpus = unsafe { RcSubstring::map_file("corpus.txt")? };
+    /// let first_line = corpus.lines_owned().next();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub unsafe fn map_file(path: impl AsRef<Path>) -> io::Result<RcSubstring> {
+        let file = File::open(path)?;
+        // SAFETY: passed on to the caller
+        let mmap = unsafe { Mmap::map(&file)? };
+        RcSubstring::from_mmap(mmap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapped(name: &str, contents: &[u8]) -> io::Result<RcSubstring> {
+        let path = std::env::temp_dir().join(format!(
+            "rcsubstring-mmap-{name}-{}.txt",
+            std::process::id()
+        ));
+        std::fs::write(&path, contents).unwrap();
+        let text = unsafe { RcSubstring::map_file(&path) };
+        std::fs::remove_file(&path).unwrap();
+        text
+    }
+
+    #[test]
+    fn test_map_file() {
+        let text = mapped("valid", "one\ntwo\n".as_bytes()).unwrap();
+        assert!(text.shared().is_none());
+        let lines: Vec<_> = text.lines_owned().collect();
+        assert_eq!(lines, ["one", "two"]);
+        assert!(lines[0].rcstring.same_text(&text.rcstring));
+        assert!(
+            !lines[1]
+                .rcstring
+                .same_text(&RcSubstring::from_static("x").rcstring)
+        );
+        assert_eq!(lines[0].clone() + &lines[1], "onetwo");
+        let (rcstring, range) = lines[1].clone().into_shared();
+        assert_eq!(&rcstring[range], "two");
+    }
+
+    #[test]
+    fn test_invalid_and_empty() {
+        let error = mapped("invalid", b"\xff").unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(mapped("empty", b"").unwrap(), "");
+    }
+}
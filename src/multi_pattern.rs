@@ -0,0 +1,288 @@
+//! Multi-pattern matching over an [RcSubstring] using an Aho–Corasick automaton
+use crate::RcSubstring;
+use ::aho_corasick::{AhoCorasick, BuildError, Input, MatchKind};
+use std::cell::RefCell;
+use std::iter::FusedIterator;
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+A reusable set of patterns to search for
+
+Wraps a compiled Aho–Corasick automaton in an [Rc] so it can be built once
+and shared cheaply between any number of searches.
+Matches are reported leftmost-first, so when two patterns match at the same
+position the one given first wins.
+
+```rust
+# use rcsubstring::{MultiPattern, RcSubstring};
+# use std::rc::Rc;
+let patterns = MultiPattern::new(&["cat", "dog"]).unwrap();
+let text = RcSubstring::new(Rc::new(String::from("hotdog and catfish")), 0..18);
+let found: Vec<_> = patterns.find_iter(&text).collect();
+assert_eq!(found[0].0, 1);
+assert_eq!(found[0].1, "dog");
+assert_eq!(found[1].0, 0);
+assert_eq!(found[1].1, "cat");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct MultiPattern {
+    automaton: Rc<AhoCorasick>,
+}
+
+impl MultiPattern {
+    /// Build the automaton for the given patterns
+    ///
+    /// Returns an error if the automaton would exceed the size limits of `aho-corasick`
+    pub fn new<I, P>(patterns: I) -> Result<Self, BuildError>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<[u8]>,
+    {
+        let automaton = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostFirst)
+            .build(patterns)?;
+        Ok(MultiPattern {
+            automaton: Rc::new(automaton),
+        })
+    }
+
+    /// The number of patterns in this set
+    pub fn patterns_len(&self) -> usize {
+        self.automaton.patterns_len()
+    }
+
+    /// Iterate over the non-overlapping matches in `haystack`
+    ///
+    /// Each item is the index of the pattern that matched and the matched text
+    pub fn find_iter(&self, haystack: &RcSubstring) -> FindAny {
+        FindAny {
+            automaton: Rc::clone(&self.automaton),
+            haystack: haystack.clone(),
+            pos: 0,
+        }
+    }
+
+    /// Iterate over the parts of `haystack` separated by matches of any of the patterns
+    pub fn split(&self, haystack: &RcSubstring) -> SplitAny {
+        SplitAny {
+            matches: self.find_iter(haystack),
+            last: 0,
+            finished: false,
+        }
+    }
+}
+
+// How many lists of patterns each thread keeps automatons for
+const CACHED_AUTOMATONS: usize = 8;
+
+thread_local! {
+    // The patterns given to find_any_owned or split_any_owned, most recently used first
+    static AUTOMATONS: RefCell<Vec<(Vec<String>, MultiPattern)>> = const { RefCell::new(Vec::new()) };
+}
+
+// The MultiPattern for patterns, building it only if it isn't one this thread used recently
+#[track_caller]
+fn cached_multi_pattern(patterns: &[&str], caller: &str) -> MultiPattern {
+    AUTOMATONS.with(|automatons| {
+        let mut automatons = automatons.borrow_mut();
+        let found = automatons
+            .iter()
+            .position(|(cached, _)| cached.as_slice() == patterns);
+        let entry = match found {
+            Some(i) => automatons.remove(i),
+            None => {
+                let multi_pattern = MultiPattern::new(patterns).unwrap_or_else(|error| {
+                    panic!("could not build automaton for RcSubstring::{caller}: {error}")
+                });
+                let patterns = patterns.iter().map(|&pattern| String::from(pattern));
+                automatons.truncate(CACHED_AUTOMATONS - 1);
+                (patterns.collect(), multi_pattern)
+            }
+        };
+        let multi_pattern = entry.1.clone();
+        automatons.insert(0, entry);
+        multi_pattern
+    })
+}
+
+impl RcSubstring {
+    /// Find all non-overlapping matches of any of `patterns`
+    ///
+    /// Each item is the index of the pattern that matched and the matched text.
+    /// The automaton is built the first time `patterns` is used, and each thread keeps the
+    /// automatons for the last 8 lists of patterns to reuse. Use [MultiPattern] directly to
+    /// decide how long an automaton is kept.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the automaton for `patterns` cannot be built
+    #[track_caller]
+    pub fn find_any_owned(&self, patterns: &[&str]) -> FindAny {
+        cached_multi_pattern(patterns, "find_any_owned").find_iter(self)
+    }
+
+    /// Split this substring on matches of any of `patterns`
+    ///
+    /// The automaton is kept to reuse as by [RcSubstring::find_any_owned].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the automaton for `patterns` cannot be built
+    #[track_caller]
+    pub fn split_any_owned(&self, patterns: &[&str]) -> SplitAny {
+        cached_multi_pattern(patterns, "split_any_owned").split(self)
+    }
+}
+
+/// Iterator over the matches of a [MultiPattern]
+///
+/// Created by [MultiPattern::find_iter] or [RcSubstring::find_any_owned]
+#[derive(Debug, Clone)]
+pub struct FindAny {
+    automaton: Rc<AhoCorasick>,
+    haystack: RcSubstring,
+    pos: usize,
+}
+
+impl FindAny {
+    // Next match as a pattern index and range relative to the haystack
+    fn next_match(&mut self) -> Option<(usize, Range<usize>)> {
+        let len = self.haystack.len();
+        if self.pos > len {
+            return None;
+        }
+        let input = Input::new(&*self.haystack).span(self.pos..len);
+        let Some(found) = self.automaton.find(input) else {
+            self.pos = len + 1;
+            return None;
+        };
+        self.pos = if found.is_empty() {
+            // Step over the next char so an empty match can't repeat forever
+            found.end()
+                + self.haystack[found.end()..]
+                    .chars()
+                    .next()
+                    .map_or(1, char::len_utf8)
+        } else {
+            found.end()
+        };
+        Some((found.pattern().as_usize(), found.range()))
+    }
+}
+
+impl Iterator for FindAny {
+    type Item = (usize, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (pattern, range) = self.next_match()?;
+        Some((pattern, self.haystack.reslice(range)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every match moves the search on by at least one byte
+        (0, Some((self.haystack.len() + 1).saturating_sub(self.pos)))
+    }
+}
+
+impl FusedIterator for FindAny {}
+
+/// Iterator over the parts of a substring between matches of a [MultiPattern]
+///
+/// Not double ended, as the automaton only searches forwards.
+/// Created by [MultiPattern::split] or [RcSubstring::split_any_owned]
+#[derive(Debug, Clone)]
+pub struct SplitAny {
+    matches: FindAny,
+    last: usize,
+    finished: bool,
+}
+
+impl Iterator for SplitAny {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.matches.next_match() {
+            Some((_, range)) => {
+                let piece = self.matches.haystack.reslice(self.last..range.start);
+                self.last = range.end;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                let len = self.matches.haystack.len();
+                Some(self.matches.haystack.reslice(self.last..len))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+        let (_, matches) = self.matches.size_hint();
+        (1, matches.map(|matches| matches + 1))
+    }
+}
+
+impl FusedIterator for SplitAny {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_any() {
+        let text = RcSubstring::from(String::from("password=hunter2 token=abc"));
+        let found: Vec<_> = text.find_any_owned(&["password", "token"]).collect();
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, 0);
+        assert_eq!(found[0].1, "password");
+        assert_eq!(found[1].0, 1);
+        assert_eq!(found[1].1, "token");
+        drop(text);
+        assert_eq!(found[1].1, "token");
+    }
+
+    #[test]
+    fn test_split_any() {
+        let text = RcSubstring::from(String::from("a, b; c"));
+        let parts: Vec<_> = text.split_any_owned(&[", ", "; "]).collect();
+        assert_eq!(parts, ["a", "b", "c"]);
+        let parts: Vec<_> = text.split_any_owned(&["x"]).collect();
+        assert_eq!(parts, ["a, b; c"]);
+    }
+
+    #[test]
+    fn test_automaton_is_reused() {
+        let text = RcSubstring::from(String::from("a-b_c"));
+        let first = text.find_any_owned(&["-", "_"]);
+        let again = text.split_any_owned(&["-", "_"]);
+        assert!(Rc::ptr_eq(&first.automaton, &again.matches.automaton));
+        let other = text.find_any_owned(&["-"]);
+        assert!(!Rc::ptr_eq(&first.automaton, &other.automaton));
+        for i in 0..CACHED_AUTOMATONS {
+            text.find_any_owned(&[&i.to_string()]);
+        }
+        let rebuilt = text.find_any_owned(&["-", "_"]);
+        assert!(!Rc::ptr_eq(&first.automaton, &rebuilt.automaton));
+        assert_eq!(AUTOMATONS.with(|automatons| automatons.borrow().len()), 8);
+    }
+
+    #[test]
+    fn test_empty_pattern_terminates() {
+        let text = RcSubstring::from(String::from("håj"));
+        let found: Vec<_> = text.find_any_owned(&[""]).map(|(_, s)| s).collect();
+        assert_eq!(found.len(), 4);
+        assert!(found.iter().all(|s| s.is_empty()));
+        let mut matches = text.find_any_owned(&[""]);
+        assert_eq!(matches.size_hint(), (0, Some(5)));
+        matches.by_ref().for_each(drop);
+        assert_eq!(matches.size_hint(), (0, Some(0)));
+        assert!(matches.next().is_none());
+    }
+}
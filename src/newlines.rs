@@ -0,0 +1,136 @@
+//! Finding and normalising the line endings in an [RcSubstring]
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// True if there are any `\r\n` line endings
+    pub fn has_crlf(&self) -> bool {
+        self.contains("\r\n")
+    }
+
+    /// The text with `\r\n` and lone `\r` line endings changed to `\n`
+    ///
+    /// Returns this substring if there are no `\r`s, otherwise copies the text into a new
+    /// `Rc<String>`.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("one\r\ntwo\rthree\n"));
+    /// assert!(text.has_crlf());
+    /// assert_eq!(text.normalize_newlines(), "one\ntwo\nthree\n");
+    /// ```
+    pub fn normalize_newlines(&self) -> RcSubstring {
+        if !self.contains('\r') {
+            return self.clone();
+        }
+        let mut text = String::with_capacity(self.len());
+        let mut rest = self.as_str();
+        while let Some(cr) = rest.find('\r') {
+            text.push_str(&rest[..cr]);
+            text.push('\n');
+            rest = &rest[cr + 1..];
+            rest = rest.strip_prefix('\n').unwrap_or(rest);
+        }
+        text.push_str(rest);
+        RcSubstring::from(text)
+    }
+
+    /// Iterate over the byte offsets of the `\n`s in this substring
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("a\nbc\n"));
+    /// assert_eq!(text.newline_positions().collect::<Vec<_>>(), [1, 4]);
+    /// ```
+    pub fn newline_positions(&self) -> NewlinePositions {
+        NewlinePositions {
+            text: self.clone(),
+            start: 0,
+            end: self.len(),
+        }
+    }
+}
+
+/**
+An iterator over the byte offsets of the `\n`s in a substring
+
+Created by [RcSubstring::newline_positions]
+*/
+#[derive(Debug, Clone)]
+pub struct NewlinePositions {
+    text: RcSubstring,
+    // The part of the text not yet searched
+    start: usize,
+    end: usize,
+}
+
+impl Iterator for NewlinePositions {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let Some(i) = self.text[self.start..self.end].find('\n') else {
+            self.start = self.end;
+            return None;
+        };
+        let position = self.start + i;
+        self.start = position + 1;
+        Some(position)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end - self.start))
+    }
+}
+
+impl DoubleEndedIterator for NewlinePositions {
+    fn next_back(&mut self) -> Option<usize> {
+        let Some(i) = self.text[self.start..self.end].rfind('\n') else {
+            self.end = self.start;
+            return None;
+        };
+        let position = self.start + i;
+        self.end = position;
+        Some(position)
+    }
+}
+
+impl FusedIterator for NewlinePositions {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_normalize_newlines() {
+        let source = Rc::new(String::from("unix\nonly\n"));
+        let unix = RcSubstring::from(Rc::clone(&source));
+        assert!(!unix.has_crlf());
+        assert!(Rc::ptr_eq(
+            unix.normalize_newlines().shared().unwrap(),
+            &source
+        ));
+        assert_eq!(
+            RcSubstring::from(String::from("\r\r\n\r")).normalize_newlines(),
+            "\n\n\n"
+        );
+        assert!(!RcSubstring::from(String::from("old mac\r")).has_crlf());
+    }
+
+    #[test]
+    fn test_newline_positions() {
+        let text = RcSubstring::from(String::from("\na\n\nb"));
+        let mut positions = text.newline_positions();
+        assert_eq!(positions.next(), Some(0));
+        assert_eq!(positions.next_back(), Some(3));
+        assert_eq!(positions.next_back(), Some(2));
+        assert_eq!(positions.next(), None);
+        assert_eq!(positions.next_back(), None);
+        assert_eq!(
+            RcSubstring::from(String::from("none"))
+                .newline_positions()
+                .count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Input trait implementations so `nom` parsers can consume an [RcSubstring] directly
+use crate::RcSubstring;
+use ::nom::{
+    Compare, CompareResult, FindSubstring, InputIter, InputLength, InputTake, Needed, Offset,
+    Slice, UnspecializedInput,
+};
+use std::iter::FusedIterator;
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+
+impl InputLength for RcSubstring {
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl InputTake for RcSubstring {
+    fn take(&self, count: usize) -> Self {
+        self.reslice(0..count)
+    }
+
+    // Returns (suffix, prefix) as nom expects
+    fn take_split(&self, count: usize) -> (Self, Self) {
+        (self.reslice(count..self.len()), self.reslice(0..count))
+    }
+}
+
+impl InputIter for RcSubstring {
+    type Item = char;
+    type Iter = OwnedCharIndices;
+    type IterElem = OwnedChars;
+
+    fn iter_indices(&self) -> Self::Iter {
+        OwnedCharIndices {
+            chars: self.iter_elements(),
+        }
+    }
+
+    fn iter_elements(&self) -> Self::IterElem {
+        OwnedChars {
+            substring: self.clone(),
+            pos: 0,
+            end: self.len(),
+        }
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.find(predicate)
+    }
+
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        let mut chars = self.char_indices().map(|(i, _)| i).chain([self.len()]);
+        chars.nth(count).ok_or(Needed::Unknown)
+    }
+}
+
+// Gives us nom's default `InputTakeAtPosition` so the character parsers work too
+impl UnspecializedInput for RcSubstring {}
+
+impl<'a> Compare<&'a str> for RcSubstring {
+    fn compare(&self, t: &'a str) -> CompareResult {
+        (&**self).compare(t)
+    }
+
+    fn compare_no_case(&self, t: &'a str) -> CompareResult {
+        (&**self).compare_no_case(t)
+    }
+}
+
+impl<'a> FindSubstring<&'a str> for RcSubstring {
+    fn find_substring(&self, substr: &'a str) -> Option<usize> {
+        self.find(substr)
+    }
+}
+
+impl Slice<Range<usize>> for RcSubstring {
+    fn slice(&self, range: Range<usize>) -> Self {
+        self.reslice(range)
+    }
+}
+
+impl Slice<RangeFrom<usize>> for RcSubstring {
+    fn slice(&self, range: RangeFrom<usize>) -> Self {
+        self.reslice(range.start..self.len())
+    }
+}
+
+impl Slice<RangeTo<usize>> for RcSubstring {
+    fn slice(&self, range: RangeTo<usize>) -> Self {
+        self.reslice(0..range.end)
+    }
+}
+
+impl Slice<RangeFull> for RcSubstring {
+    fn slice(&self, _: RangeFull) -> Self {
+        self.clone()
+    }
+}
+
+impl Offset for RcSubstring {
+    /// Offset of `second` from the start of `self`
+    ///
+    /// As for `&str`, `second` is expected to be a later part of the same source
+    fn offset(&self, second: &Self) -> usize {
+        second.range.start - self.range.start
+    }
+}
+
+/// Owning iterator over the chars of an [RcSubstring]
+///
+/// Used as [InputIter::IterElem] as nom needs an iterator that doesn't borrow the input
+#[derive(Debug, Clone)]
+pub struct OwnedChars {
+    substring: RcSubstring,
+    pos: usize,
+    end: usize,
+}
+
+impl Iterator for OwnedChars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let c = self.substring[self.pos..self.end].chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len.div_ceil(4), Some(len))
+    }
+}
+
+impl DoubleEndedIterator for OwnedChars {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let c = self.substring[self.pos..self.end].chars().next_back()?;
+        self.end -= c.len_utf8();
+        Some(c)
+    }
+}
+
+impl FusedIterator for OwnedChars {}
+
+/// Owning iterator over the chars of an [RcSubstring] and their byte positions
+///
+/// Used as [InputIter::Iter] as nom needs an iterator that doesn't borrow the input
+#[derive(Debug, Clone)]
+pub struct OwnedCharIndices {
+    chars: OwnedChars,
+}
+
+impl Iterator for OwnedCharIndices {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.chars.pos;
+        self.chars.next().map(|c| (pos, c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chars.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for OwnedCharIndices {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let c = self.chars.next_back()?;
+        Some((self.chars.end, c))
+    }
+}
+
+impl FusedIterator for OwnedCharIndices {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::nom::IResult;
+    use ::nom::bytes::complete::{tag, take_until};
+    use ::nom::character::complete::{alpha1, digit1, space1};
+    use ::nom::sequence::{separated_pair, terminated};
+
+    fn assignment(input: RcSubstring) -> IResult<RcSubstring, (RcSubstring, RcSubstring)> {
+        terminated(separated_pair(alpha1, tag(" = "), digit1), tag(";"))(input)
+    }
+
+    #[test]
+    fn test_parser() {
+        let (rest, (name, value)) =
+            assignment(RcSubstring::from(String::from("answer = 42; more"))).unwrap();
+        assert_eq!(name, "answer");
+        assert_eq!(value, "42");
+        assert_eq!(rest, " more");
+        assert_eq!(value.range, 9..11);
+
+        let (rest, _) = space1::<_, ::nom::error::Error<_>>(rest).unwrap();
+        assert_eq!(rest, "more");
+        assert!(assignment(RcSubstring::from(String::from("answer: 42;"))).is_err());
+    }
+
+    #[test]
+    fn test_take_until_and_offset() {
+        let input = RcSubstring::from(String::from("héllo, world"));
+        let (rest, word) = take_until::<_, _, ::nom::error::Error<_>>(",")(input.clone()).unwrap();
+        assert_eq!(word, "héllo");
+        assert_eq!(rest, ", world");
+        assert_eq!(input.offset(&rest), 6);
+        assert_eq!(input.slice_index(2), Ok(3));
+        assert_eq!(input.slice_index(12), Ok(13));
+        assert!(input.slice_index(13).is_err());
+    }
+
+    #[test]
+    fn test_iterators() {
+        let input = RcSubstring::from(String::from("aé"));
+        assert_eq!(input.iter_elements().collect::<String>(), "aé");
+        assert_eq!(
+            input.iter_indices().collect::<Vec<_>>(),
+            [(0, 'a'), (1, 'é')]
+        );
+        assert_eq!(
+            input.iter_indices().rev().collect::<Vec<_>>(),
+            [(1, 'é'), (0, 'a')]
+        );
+        let mut chars = input.iter_elements();
+        assert_eq!(chars.size_hint(), (1, Some(3)));
+        assert_eq!(chars.next_back(), Some('é'));
+        assert_eq!(chars.next(), Some('a'));
+        assert_eq!(chars.next_back(), None);
+    }
+}
@@ -0,0 +1,89 @@
+//! Parsing a number off the start of an [RcSubstring]
+use crate::RcSubstring;
+use std::str::FromStr;
+
+impl RcSubstring {
+    /// Parse the longest number at the start of the text, returning it and the rest
+    ///
+    /// The number can have a sign, a fractional part and an exponent, as in `-1.5e3`, but
+    /// only as much of that as `T` accepts is used, so parsing `12.5` as an integer gives
+    /// `12` and leaves `.5`. The sign and digits before any fraction are always all used, so
+    /// an integer too big for `T` gives `None` rather than a shorter number. Returns `None`
+    /// if no prefix parses as `T`.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let input = RcSubstring::from(String::from("42px"));
+    /// let (size, unit) = input.parse_prefix::<u32>().unwrap();
+    /// assert_eq!(size, 42);
+    /// assert_eq!(unit, "px");
+    ///
+    /// let input = RcSubstring::from(String::from("-2.5e-1, 7"));
+    /// let (value, rest) = input.parse_prefix::<f64>().unwrap();
+    /// assert_eq!(value, -0.25);
+    /// assert_eq!(rest, ", 7");
+    /// assert!(rest.parse_prefix::<f64>().is_none());
+    /// ```
+    pub fn parse_prefix<T: FromStr>(&self) -> Option<(T, RcSubstring)> {
+        let (integer, candidate) = number_len(self.as_bytes());
+        (integer.max(1)..=candidate).rev().find_map(|len| {
+            let value = self[..len].parse().ok()?;
+            Some((value, self.reslice(len..self.len())))
+        })
+    }
+}
+
+// The length of the sign and integer digits at the start, and of the longest prefix that
+// looks like a number, all of it ASCII
+fn number_len(bytes: &[u8]) -> (usize, usize) {
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let sign = |from: usize| from + matches!(bytes.get(from), Some(b'+' | b'-')) as usize;
+    let integer = digits(sign(0));
+    let mut len = integer;
+    if bytes.get(len) == Some(&b'.') {
+        len = digits(len + 1);
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        len = digits(sign(len + 1));
+    }
+    (integer, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsed<T: FromStr>(text: &str) -> Option<(T, String)> {
+        let (value, rest) = RcSubstring::from(String::from(text)).parse_prefix::<T>()?;
+        Some((value, rest.to_string()))
+    }
+
+    #[test]
+    fn test_integers() {
+        assert_eq!(parsed::<i32>("-12.5"), Some((-12, String::from(".5"))));
+        assert_eq!(parsed::<u8>("+7e2"), Some((7, String::from("e2"))));
+        assert_eq!(parsed::<u8>("300"), None);
+        assert_eq!(parsed::<i8>("-129px"), None);
+        assert_eq!(parsed::<u8>("255.9"), Some((255, String::from(".9"))));
+        assert_eq!(parsed::<u8>("-1"), None);
+        assert_eq!(parsed::<u8>(""), None);
+        assert_eq!(parsed::<i64>("١٢"), None);
+    }
+
+    #[test]
+    fn test_floats() {
+        assert_eq!(parsed::<f64>("1.e3x"), Some((1000.0, String::from("x"))));
+        assert_eq!(parsed::<f64>(".5."), Some((0.5, String::from("."))));
+        assert_eq!(parsed::<f32>("2e+"), Some((2.0, String::from("e+"))));
+        assert_eq!(parsed::<f64>("-."), None);
+        let source = RcSubstring::from(String::from("x=3;"));
+        let (three, rest) = source.reslice(2..4).parse_prefix::<f64>().unwrap();
+        assert_eq!(three, 3.0);
+        assert_eq!(rest.range, 3..4);
+    }
+}
@@ -0,0 +1,134 @@
+//! Saying whether offsets are in the source text or relative to an [RcSubstring]
+use crate::storage::sealed::Text;
+use crate::{RcSubstring, RcSubstringError, RcSubstringErrorKind, StringStorage, Substring};
+use std::ops::Range;
+use std::rc::Rc;
+
+impl RcSubstring {
+    /// Construct a new RcSubstring of the `len` bytes at `start` in the source text
+    ///
+    /// The range is checked as by [RcSubstring::try_new], and `start + len` overflowing is
+    /// reported as out of bounds rather than wrapping.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let rc = Rc::new(String::from("key = value"));
+    /// let value = RcSubstring::from_absolute(Rc::clone(&rc), 6, 5).unwrap();
+    /// assert_eq!(value, "value");
+    /// assert!(RcSubstring::from_absolute(rc, 6, usize::MAX).is_err());
+    /// ```
+    #[track_caller]
+    pub fn from_absolute(
+        rcstring: Rc<String>,
+        start: usize,
+        len: usize,
+    ) -> Result<Self, RcSubstringError> {
+        let Some(end) = start.checked_add(len) else {
+            let kind = RcSubstringErrorKind::OutOfBounds {
+                index: usize::MAX,
+                len: rcstring.len(),
+            };
+            let range = start..rcstring.len();
+            let location = std::panic::Location::caller();
+            return Err(RcSubstringError::new(
+                kind,
+                Some(location),
+                &rcstring,
+                &range,
+            ));
+        };
+        RcSubstring::try_new(rcstring, start..end)
+    }
+}
+
+impl<S: StringStorage> Substring<S> {
+    /// The range of this substring in the source text, the same as [Substring::range]
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("a, b"));
+    /// let b = text.split_owned(", ").nth(1).unwrap();
+    /// assert_eq!(b.absolute_range(), 3..4);
+    /// ```
+    pub fn absolute_range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The range of this substring relative to the start of `parent`
+    ///
+    /// Returns `None` if `parent` is of different text, or doesn't contain all of this
+    /// substring. Slicing `parent` with the range gives the same text as this.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("let x = 1;"));
+    /// let statement = text.trim_end_matches_owned(';');
+    /// let value = statement.split_owned(" = ").nth(1).unwrap();
+    /// assert_eq!(value.relative_range_in(&statement), Some(8..9));
+    /// assert_eq!(statement.relative_range_in(&value), None);
+    /// ```
+    pub fn relative_range_in(&self, parent: &Substring<S>) -> Option<Range<usize>> {
+        let contained = self.rcstring.same_text(&parent.rcstring)
+            && parent.range.start <= self.range.start
+            && self.range.end <= parent.range.end;
+        contained
+            .then(|| self.range.start - parent.range.start..self.range.end - parent.range.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_absolute() {
+        let rc = Rc::new(String::from("ñu"));
+        assert_eq!(
+            RcSubstring::from_absolute(Rc::clone(&rc), 2, 1).unwrap(),
+            "u"
+        );
+        assert_eq!(
+            RcSubstring::from_absolute(Rc::clone(&rc), 3, 0).unwrap(),
+            ""
+        );
+        let kind = |start, len| {
+            RcSubstring::from_absolute(Rc::clone(&rc), start, len)
+                .unwrap_err()
+                .kind()
+                .clone()
+        };
+        assert_eq!(
+            kind(1, 1),
+            RcSubstringErrorKind::NotCharBoundary { index: 1 }
+        );
+        assert_eq!(
+            kind(2, 2),
+            RcSubstringErrorKind::OutOfBounds { index: 4, len: 3 }
+        );
+        assert_eq!(
+            kind(1, usize::MAX),
+            RcSubstringErrorKind::OutOfBounds {
+                index: usize::MAX,
+                len: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_relative_range_in() {
+        let text = RcSubstring::from(String::from("abcdef"));
+        let outer = text.reslice(1..5);
+        let inner = outer.reslice(1..3);
+        assert_eq!(inner.absolute_range(), 2..4);
+        let relative = inner.relative_range_in(&outer).unwrap();
+        assert_eq!(relative, 1..3);
+        assert_eq!(&outer[relative], inner.as_str());
+        assert_eq!(outer.relative_range_in(&outer), Some(0..4));
+        assert_eq!(text.relative_range_in(&outer), None);
+        let overlapping = text.reslice(4..6);
+        assert_eq!(overlapping.relative_range_in(&outer), None);
+        let copy = RcSubstring::from(String::from("abcdef"));
+        assert_eq!(inner.relative_range_in(&copy), None);
+    }
+}
@@ -0,0 +1,513 @@
+//! Patterns to find, split, strip and trim an [RcSubstring] with
+use crate::{CharSet, RcSubstring};
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+/**
+Something to search an [RcSubstring] for
+
+The crate's own version of std's unstable `Pattern` trait. It is implemented for `char`,
+`&str`, `&String`, `&[char]`, `[char; N]`, `&CharSet`, `FnMut(char) -> bool` and, with the
+`regex` feature, `&Regex`. Other crates can implement it for their own matchers.
+
+Only [RcPattern::find_in] has to be written; the others have defaults that use it.
+
+```rust
+# use rcsubstring::{RcPattern, RcSubstring};
+# use std::ops::Range;
+// Matches runs of digits
+struct Digits;
+
+impl RcPattern for Digits {
+    fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+        let first = start + haystack[start..].find(|c: char| c.is_ascii_digit())?;
+        let len = haystack[first..].bytes().take_while(u8::is_ascii_digit).count();
+        Some(first..first + len)
+    }
+}
+
+let text = RcSubstring::from(String::from("a1b22c"));
+let parts: Vec<_> = text.split_pattern(Digits).collect();
+assert_eq!(parts, ["a", "b", "c"]);
+```
+*/
+pub trait RcPattern {
+    /// The byte range of the first match in `haystack` that starts at or after `start`
+    ///
+    /// `start` is always on a char boundary.
+    fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>>;
+
+    /// The length of a match at the start of `haystack`, if there is one
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.find_in(haystack, 0)
+            .filter(|found| found.start == 0)
+            .map(|found| found.end)
+    }
+
+    /// The length of a match that ends at the end of `haystack`, if there is one
+    ///
+    /// By default this is the first match found that ends there.
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        let mut start = 0;
+        while let Some(found) = self.find_in(haystack, start) {
+            if found.end == haystack.len() {
+                return Some(found.len());
+            }
+            let next = haystack[found.start..].chars().next()?;
+            start = found.start + next.len_utf8();
+        }
+        None
+    }
+}
+
+// The first char at or after start that pred matches, as a range
+fn find_char(
+    haystack: &str,
+    start: usize,
+    mut pred: impl FnMut(char) -> bool,
+) -> Option<Range<usize>> {
+    haystack[start..]
+        .char_indices()
+        .find(|&(_, c)| pred(c))
+        .map(|(i, c)| start + i..start + i + c.len_utf8())
+}
+
+// The length of the first char of haystack if pred matches it
+fn prefix_char(haystack: &str, mut pred: impl FnMut(char) -> bool) -> Option<usize> {
+    let c = haystack.chars().next()?;
+    pred(c).then(|| c.len_utf8())
+}
+
+// The length of the last char of haystack if pred matches it
+fn suffix_char(haystack: &str, mut pred: impl FnMut(char) -> bool) -> Option<usize> {
+    let c = haystack.chars().next_back()?;
+    pred(c).then(|| c.len_utf8())
+}
+
+macro_rules! char_pattern {
+    ([$($generics:tt)*] $pattern:ty, |$this:ident, $c:ident| $matches:expr) => {
+        impl<$($generics)*> RcPattern for $pattern {
+            fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+                let $this = self;
+                find_char(haystack, start, |$c| $matches)
+            }
+
+            fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+                let $this = self;
+                prefix_char(haystack, |$c| $matches)
+            }
+
+            fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+                let $this = self;
+                suffix_char(haystack, |$c| $matches)
+            }
+        }
+    };
+}
+
+char_pattern!([] char, |this, c| c == *this);
+char_pattern!([] & [char], |this, c| this.contains(&c));
+char_pattern!([const N: usize] [char; N], |this, c| this.contains(&c));
+char_pattern!([] & CharSet, |this, c| this.contains(c));
+char_pattern!([F: FnMut(char) -> bool] F, |this, c| this(c));
+
+impl RcPattern for &str {
+    fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+        let found = start + haystack[start..].find(*self)?;
+        Some(found..found + self.len())
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.starts_with(*self).then_some(self.len())
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        haystack.ends_with(*self).then_some(self.len())
+    }
+}
+
+impl RcPattern for &String {
+    fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+        self.as_str().find_in(haystack, start)
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.as_str().prefix_len(haystack)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.as_str().suffix_len(haystack)
+    }
+}
+
+impl RcSubstring {
+    /// The first match of `pattern` in this substring
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("id: 42"));
+    /// let digit = text.find_owned(|c: char| c.is_ascii_digit()).unwrap();
+    /// assert_eq!(digit, "4");
+    /// ```
+    pub fn find_owned(&self, mut pattern: impl RcPattern) -> Option<RcSubstring> {
+        pattern.find_in(self, 0).map(|found| self.reslice(found))
+    }
+
+    /// Split this substring on the matches of `pattern`, as [str::split] does
+    ///
+    /// [RcSubstring::split_owned] is a little faster for a `&str` separator.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("a,b;c"));
+    /// let parts: Vec<_> = text.split_pattern([',', ';']).collect();
+    /// assert_eq!(parts, ["a", "b", "c"]);
+    /// ```
+    pub fn split_pattern<P: RcPattern>(&self, pattern: P) -> SplitPattern<P> {
+        SplitPattern {
+            text: self.clone(),
+            pattern,
+            piece_start: 0,
+            search_from: 0,
+            last_match_end: None,
+            finished: false,
+        }
+    }
+
+    /// A cursor splitting this substring on the matches of `pattern`, that can go back
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("let x = 1"));
+    /// let mut cursor = text.split_cursor(' ');
+    /// assert_eq!(cursor.next_token().unwrap(), "let");
+    /// let mark = cursor.save();
+    /// assert_eq!(cursor.next_token().unwrap(), "x");
+    /// cursor.restore(mark);
+    /// assert_eq!(cursor.next_token().unwrap(), "x");
+    /// ```
+    pub fn split_cursor<P: RcPattern>(&self, pattern: P) -> SplitCursor<P> {
+        SplitCursor {
+            split: self.split_pattern(pattern),
+        }
+    }
+
+    /// The rest of this substring after a match of `pattern` at the start
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let line = RcSubstring::from(String::from("# heading"));
+    /// assert_eq!(line.strip_prefix_owned("# ").unwrap(), "heading");
+    /// ```
+    pub fn strip_prefix_owned(&self, mut pattern: impl RcPattern) -> Option<RcSubstring> {
+        let len = pattern.prefix_len(self)?;
+        Some(self.reslice(len..self.len()))
+    }
+
+    /// The rest of this substring before a match of `pattern` at the end
+    pub fn strip_suffix_owned(&self, mut pattern: impl RcPattern) -> Option<RcSubstring> {
+        let len = pattern.suffix_len(self)?;
+        Some(self.reslice(0..self.len() - len))
+    }
+
+    /// This substring without any matches of `pattern` at the start
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let number = RcSubstring::from(String::from("000120"));
+    /// assert_eq!(number.trim_start_matches_owned('0'), "120");
+    /// ```
+    pub fn trim_start_matches_owned(&self, mut pattern: impl RcPattern) -> RcSubstring {
+        let start = trimmed_start(self, &mut pattern);
+        self.reslice(start..self.len())
+    }
+
+    /// This substring without any matches of `pattern` at the end
+    pub fn trim_end_matches_owned(&self, mut pattern: impl RcPattern) -> RcSubstring {
+        self.reslice(0..trimmed_end(self, &mut pattern))
+    }
+
+    /// This substring without any matches of `pattern` at either end
+    pub fn trim_matches_owned(&self, mut pattern: impl RcPattern) -> RcSubstring {
+        let start = trimmed_start(self, &mut pattern);
+        let end = start + trimmed_end(&self[start..], &mut pattern);
+        self.reslice(start..end)
+    }
+}
+
+// Where text starts after taking off matches of pattern
+fn trimmed_start(text: &str, pattern: &mut impl RcPattern) -> usize {
+    let mut start = 0;
+    while let Some(len) = pattern.prefix_len(&text[start..]) {
+        if len == 0 {
+            break;
+        }
+        start += len;
+    }
+    start
+}
+
+// Where text ends after taking off matches of pattern
+fn trimmed_end(text: &str, pattern: &mut impl RcPattern) -> usize {
+    let mut end = text.len();
+    while let Some(len) = pattern.suffix_len(&text[..end]) {
+        if len == 0 {
+            break;
+        }
+        end -= len;
+    }
+    end
+}
+
+/**
+An iterator over the parts of an [RcSubstring] separated by matches of an [RcPattern]
+
+Created by [RcSubstring::split_pattern]
+*/
+#[derive(Debug, Clone)]
+pub struct SplitPattern<P> {
+    text: RcSubstring,
+    pattern: P,
+    // Where the next part starts
+    piece_start: usize,
+    // Where to look for the next match
+    search_from: usize,
+    // An empty match here would be part of the last match, so isn't counted
+    last_match_end: Option<usize>,
+    finished: bool,
+}
+
+impl<P: RcPattern> SplitPattern<P> {
+    // The next match, skipping an empty match right after the last one
+    fn next_match(&mut self) -> Option<Range<usize>> {
+        loop {
+            if self.search_from > self.text.len() {
+                return None;
+            }
+            let found = self.pattern.find_in(&self.text, self.search_from)?;
+            let skip = found.is_empty() && Some(found.start) == self.last_match_end;
+            self.search_from = if found.is_empty() {
+                let next = self.text[found.end..].chars().next();
+                found.end + next.map_or(1, char::len_utf8)
+            } else {
+                found.end
+            };
+            if !skip {
+                self.last_match_end = Some(found.end);
+                return Some(found);
+            }
+        }
+    }
+}
+
+impl<P: RcPattern> Iterator for SplitPattern<P> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        if self.finished {
+            return None;
+        }
+        match self.next_match() {
+            Some(found) => {
+                let piece = self.text.reslice(self.piece_start..found.start);
+                self.piece_start = found.end;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                Some(self.text.reslice(self.piece_start..self.text.len()))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            (0, Some(0))
+        } else {
+            // Up to one part for each position a match can end at, and one more
+            (
+                1,
+                Some(self.text.len() - self.search_from.min(self.text.len()) + 2),
+            )
+        }
+    }
+}
+
+impl<P: RcPattern> FusedIterator for SplitPattern<P> {}
+
+/**
+Splits an [RcSubstring] on the matches of an [RcPattern], keeping its place so it can go back
+
+Splits just as [SplitPattern] does, but [SplitCursor::save] gives a [CursorMark] that
+[SplitCursor::restore] can later return to, which backtracking parsers need. It is also an
+iterator over the tokens.
+
+Created by [RcSubstring::split_cursor]
+*/
+#[derive(Debug, Clone)]
+pub struct SplitCursor<P> {
+    split: SplitPattern<P>,
+}
+
+/**
+A place in a [SplitCursor] to go back to
+
+Created by [SplitCursor::save]
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorMark {
+    piece_start: usize,
+    search_from: usize,
+    last_match_end: Option<usize>,
+    finished: bool,
+}
+
+impl<P: RcPattern> SplitCursor<P> {
+    /// The next token, or `None` once all have been taken
+    pub fn next_token(&mut self) -> Option<RcSubstring> {
+        self.split.next()
+    }
+
+    /// The next token without moving past it
+    pub fn peek_token(&mut self) -> Option<RcSubstring> {
+        let mark = self.save();
+        let token = self.next_token();
+        self.restore(mark);
+        token
+    }
+
+    /// The current place, to go back to with [SplitCursor::restore]
+    pub fn save(&self) -> CursorMark {
+        CursorMark {
+            piece_start: self.split.piece_start,
+            search_from: self.split.search_from,
+            last_match_end: self.split.last_match_end,
+            finished: self.split.finished,
+        }
+    }
+
+    /// Go back (or forward) to a place saved from this cursor
+    ///
+    /// A mark from a cursor over different text gives meaningless tokens, and may panic.
+    pub fn restore(&mut self, mark: CursorMark) {
+        self.split.piece_start = mark.piece_start;
+        self.split.search_from = mark.search_from;
+        self.split.last_match_end = mark.last_match_end;
+        self.split.finished = mark.finished;
+    }
+
+    /// The byte offset of the next token in the substring being split
+    pub fn position(&self) -> usize {
+        self.split.piece_start
+    }
+
+    /// The text not yet taken, or `None` once all the tokens have been
+    pub fn remainder(&self) -> Option<RcSubstring> {
+        let text = &self.split.text;
+        (!self.split.finished).then(|| text.reslice(self.split.piece_start..text.len()))
+    }
+}
+
+impl<P: RcPattern> Iterator for SplitCursor<P> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        self.next_token()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.split.size_hint()
+    }
+}
+
+impl<P: RcPattern> FusedIterator for SplitCursor<P> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn split<P: RcPattern>(text: &str, pattern: P) -> Vec<String> {
+        RcSubstring::from(String::from(text))
+            .split_pattern(pattern)
+            .map(|part| part.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_split_matches_str_split() {
+        for text in ["", "a", "a,b", ",a,,b,", "é,€"] {
+            assert_eq!(split(text, ','), text.split(',').collect::<Vec<_>>());
+            assert_eq!(split(text, ",,"), text.split(",,").collect::<Vec<_>>());
+            assert_eq!(split(text, ""), text.split("").collect::<Vec<_>>());
+            let chars: &[char] = &[',', 'a'];
+            assert_eq!(split(text, chars), text.split(chars).collect::<Vec<_>>());
+        }
+        let set = CharSet::new(&['-', '+']);
+        assert_eq!(split("1-2+3", &set), ["1", "2", "3"]);
+        let separator = String::from("::");
+        assert_eq!(split("a::b", &separator), ["a", "b"]);
+    }
+
+    #[test]
+    fn test_split_shares_source() {
+        let source = Rc::new(String::from("x y"));
+        let parts: Vec<_> = RcSubstring::from(Rc::clone(&source))
+            .split_pattern(char::is_whitespace)
+            .collect();
+        assert_eq!(parts, ["x", "y"]);
+        assert!(Rc::ptr_eq(parts[1].shared().unwrap(), &source));
+        assert_eq!(parts[1].range, 2..3);
+    }
+
+    #[test]
+    fn test_strip_and_trim() {
+        let text = RcSubstring::from(String::from("--é--"));
+        assert_eq!(text.strip_prefix_owned('-').unwrap(), "-é--");
+        assert_eq!(text.strip_suffix_owned("--").unwrap(), "--é");
+        assert!(text.strip_prefix_owned('é').is_none());
+        assert_eq!(text.trim_start_matches_owned('-'), "é--");
+        assert_eq!(text.trim_end_matches_owned("-"), "--é");
+        assert_eq!(text.trim_matches_owned(['-']), "é");
+        assert_eq!(text.trim_matches_owned(""), "--é--");
+        assert_eq!(text.find_owned('é').unwrap().range, 2..4);
+        assert!(text.find_owned("x").is_none());
+    }
+
+    #[test]
+    fn test_default_suffix_len() {
+        struct Ab;
+        impl RcPattern for Ab {
+            fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+                let found = start + haystack[start..].find("ab")?;
+                Some(found..found + 2)
+            }
+        }
+        assert_eq!(Ab.suffix_len("abxab"), Some(2));
+        assert_eq!(Ab.suffix_len("aba"), None);
+        assert_eq!(Ab.prefix_len("xab"), None);
+        assert_eq!(
+            RcSubstring::from(String::from("ababx")).trim_start_matches_owned(Ab),
+            "x"
+        );
+    }
+
+    #[test]
+    fn test_split_cursor() {
+        let text = RcSubstring::from(String::from("a,,b"));
+        let mut cursor = text.split_cursor(',');
+        let start = cursor.save();
+        assert_eq!(cursor.peek_token().unwrap(), "a");
+        assert_eq!(cursor.next_token().unwrap(), "a");
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.remainder().unwrap(), ",b");
+        let mark = cursor.save();
+        assert_eq!(cursor.by_ref().collect::<Vec<_>>(), ["", "b"]);
+        assert!(cursor.remainder().is_none());
+        assert!(cursor.next_token().is_none());
+        cursor.restore(mark);
+        assert_eq!(cursor.next_token().unwrap(), "");
+        cursor.restore(start);
+        assert_eq!(cursor.collect::<Vec<_>>(), ["a", "", "b"]);
+    }
+}
@@ -0,0 +1,81 @@
+//! Percent-decoding and encoding of [RcSubstring]s with the `percent-encoding` crate
+use crate::RcSubstring;
+use ::percent_encoding::{AsciiSet, percent_decode_str, utf8_percent_encode};
+use std::borrow::Cow;
+
+impl RcSubstring {
+    /// Decode `%` escapes in this substring
+    ///
+    /// If there is nothing to decode this is just a clone of `self` and nothing is allocated.
+    /// Otherwise the result wraps a new `Rc<String>` holding the decoded text, with any
+    /// escapes that don't decode to valid UTF-8 replaced with U+FFFD.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let path = RcSubstring::from(String::from("/files/my%20notes.txt"));
+    /// assert_eq!(path.percent_decoded(), "/files/my notes.txt");
+    /// ```
+    pub fn percent_decoded(&self) -> RcSubstring {
+        if !self.contains('%') {
+            return self.clone();
+        }
+        match percent_decode_str(self).decode_utf8_lossy() {
+            Cow::Borrowed(_) => self.clone(),
+            Cow::Owned(decoded) => RcSubstring::from(decoded),
+        }
+    }
+
+    /// Percent-encode the bytes of this substring that are in `ascii_set` as well as all non-ASCII bytes
+    ///
+    /// If there is nothing to encode this is just a clone of `self` and nothing is allocated.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// use percent_encoding::NON_ALPHANUMERIC;
+    /// let value = RcSubstring::from(String::from("a&b"));
+    /// assert_eq!(value.percent_encoded(NON_ALPHANUMERIC), "a%26b");
+    /// ```
+    pub fn percent_encoded(&self, ascii_set: &'static AsciiSet) -> RcSubstring {
+        match Cow::from(utf8_percent_encode(self, ascii_set)) {
+            Cow::Borrowed(_) => self.clone(),
+            Cow::Owned(encoded) => RcSubstring::from(encoded),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::percent_encoding::{CONTROLS, NON_ALPHANUMERIC};
+    use std::rc::Rc;
+
+    #[test]
+    fn test_decoded_without_escapes_is_shared() {
+        let text = RcSubstring::new(Rc::new(String::from("q=plain&x=%41")), 2..7);
+        let decoded = text.percent_decoded();
+        assert_eq!(decoded, "plain");
+        assert!(decoded.rcstring.same_text(&text.rcstring));
+        assert_eq!(decoded.range, 2..7);
+        // Invalid escapes are left alone
+        let text = RcSubstring::from(String::from("100%"));
+        let decoded = text.percent_decoded();
+        assert!(decoded.rcstring.same_text(&text.rcstring));
+        assert_eq!(decoded.range, text.range);
+    }
+
+    #[test]
+    fn test_decoded() {
+        let text = RcSubstring::from(String::from("caf%C3%A9 %FF"));
+        assert_eq!(text.percent_decoded(), "café \u{FFFD}");
+    }
+
+    #[test]
+    fn test_encoded() {
+        let text = RcSubstring::from(String::from("plain"));
+        let encoded = text.percent_encoded(NON_ALPHANUMERIC);
+        assert!(encoded.rcstring.same_text(&text.rcstring));
+        assert_eq!(encoded.range, text.range);
+        let text = RcSubstring::from(String::from("café\n"));
+        assert_eq!(text.percent_encoded(CONTROLS), "caf%C3%A9%0A");
+    }
+}
@@ -0,0 +1,169 @@
+//! Conversion of `pest` spans and pairs into [RcSubstring]s
+use crate::RcSubstring;
+use ::pest::RuleType;
+use ::pest::Span;
+use ::pest::iterators::{Pair, Pairs};
+use std::rc::Rc;
+
+impl RcSubstring {
+    /// Construct an RcSubstring for the text of a pest [Span]
+    ///
+    /// The span must borrow from the text held in `rc`, which is checked by comparing pointers.
+    /// The parser input can be the whole of `rc` or any part of it, so parsing the deref'd
+    /// text of an RcSubstring works too.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the text of `span` does not lie inside `rc`
+    pub fn from_pest_span(rc: &Rc<String>, span: Span) -> RcSubstring {
+        let source = rc.as_ptr() as usize;
+        let start = (span.as_str().as_ptr() as usize).wrapping_sub(source);
+        assert!(
+            start <= rc.len() && span.as_str().len() <= rc.len() - start,
+            "pest span does not borrow from the Rc<String> given to RcSubstring::from_pest_span"
+        );
+        RcSubstring::new(Rc::clone(rc), start..start + span.as_str().len())
+    }
+}
+
+/**
+A pest [Pair] converted into a tree of [RcSubstring] spans
+
+Unlike a [Pair] this doesn't borrow the parser input so the tree can be kept around,
+returned from functions or stored in other structures without a lifetime.
+
+```rust
+# use rcsubstring::RcPair;
+# use std::rc::Rc;
+# #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+# enum Rule { Word }
+let text = Rc::new(String::from("hello"));
+let pairs = pest::state(text.as_str(), |state| {
+    state.rule(Rule::Word, |state| state.match_string("hello"))
+})
+.unwrap();
+let tree = RcPair::from_pairs(&text, pairs);
+drop(text);
+assert_eq!(tree[0].rule(), Rule::Word);
+assert_eq!(*tree[0].span(), "hello");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct RcPair<R> {
+    rule: R,
+    span: RcSubstring,
+    children: Vec<RcPair<R>>,
+}
+
+impl<R: RuleType> RcPair<R> {
+    /// Convert a [Pair] and all its inner pairs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pair was not parsed from the text held in `rc`
+    pub fn from_pair(rc: &Rc<String>, pair: Pair<R>) -> Self {
+        RcPair {
+            rule: pair.as_rule(),
+            span: RcSubstring::from_pest_span(rc, pair.as_span()),
+            children: RcPair::from_pairs(rc, pair.into_inner()),
+        }
+    }
+
+    /// Convert each of the top-level [Pairs] and all their inner pairs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the pairs were not parsed from the text held in `rc`
+    pub fn from_pairs(rc: &Rc<String>, pairs: Pairs<R>) -> Vec<Self> {
+        pairs.map(|pair| RcPair::from_pair(rc, pair)).collect()
+    }
+
+    /// The rule that matched
+    pub fn rule(&self) -> R {
+        self.rule
+    }
+
+    /// The text that was matched
+    pub fn span(&self) -> &RcSubstring {
+        &self.span
+    }
+
+    /// The inner pairs that were matched
+    pub fn children(&self) -> &[RcPair<R>] {
+        &self.children
+    }
+
+    /// Consume the pair returning its text and inner pairs
+    pub fn into_parts(self) -> (R, RcSubstring, Vec<RcPair<R>>) {
+        (self.rule, self.span, self.children)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+    enum Rule {
+        Assignment,
+        Name,
+        Value,
+    }
+
+    // pest treats the end of `match_range` as inclusive
+    #[allow(clippy::almost_complete_range)]
+    fn parse(input: &str) -> Pairs<'_, Rule> {
+        ::pest::state(input, |state| {
+            state.rule(Rule::Assignment, |state| {
+                state.sequence(|state| {
+                    state
+                        .rule(Rule::Name, |state| {
+                            state.repeat(|state| state.match_range('a'..'z'))
+                        })
+                        .and_then(|state| state.match_string("="))
+                        .and_then(|state| {
+                            state.rule(Rule::Value, |state| {
+                                state.repeat(|state| state.match_range('0'..'9'))
+                            })
+                        })
+                })
+            })
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pair_tree() {
+        let text = Rc::new(String::from("answer=42"));
+        let tree = RcPair::from_pairs(&text, parse(&text));
+        drop(text);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].rule(), Rule::Assignment);
+        assert_eq!(*tree[0].span(), "answer=42");
+        let children = tree[0].children();
+        assert_eq!(children[0].rule(), Rule::Name);
+        assert_eq!(*children[0].span(), "answer");
+        assert_eq!(children[1].rule(), Rule::Value);
+        assert_eq!(*children[1].span(), "42");
+        assert_eq!(children[1].span().range, 7..9);
+    }
+
+    #[test]
+    fn test_parse_substring() {
+        let text = Rc::new(String::from("let x=1;"));
+        let part = RcSubstring::new(Rc::clone(&text), 4..7);
+        let pair = parse(&part).next().unwrap();
+        let value = RcSubstring::from_pest_span(&text, pair.into_inner().nth(1).unwrap().as_span());
+        assert_eq!(value, "1");
+        assert_eq!(value.range, 6..7);
+    }
+
+    #[test]
+    #[should_panic(expected = "RcSubstring::from_pest_span")]
+    fn test_foreign_span() {
+        let text = Rc::new(String::from("answer=42"));
+        let other = String::from("answer=42");
+        let pair = parse(&other).next().unwrap();
+        let _ = RcSubstring::from_pest_span(&text, pair.as_span());
+    }
+}
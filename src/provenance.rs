@@ -0,0 +1,94 @@
+//! Recording what made each [RcSubstring] and where, for tracking down bad spans
+//!
+//! Labels are only kept with the `provenance` feature, so without it these cost nothing.
+use crate::{Backing, RcSubstring, StringStorage, Substring, validate_labeled};
+use std::ops::Range;
+use std::panic::Location;
+use std::rc::Rc;
+
+impl RcSubstring {
+    /// Construct a new RcSubstring labeled with what made it, such as `"lexer"`
+    ///
+    /// As [RcSubstring::new], but if `range` is invalid the panic, and with the `provenance`
+    /// feature any later error from [RcSubstring::as_str_checked], names the label.
+    /// Substrings of this one keep the label.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let token = RcSubstring::labeled("lexer", Rc::new(String::from("let x")), 0..3);
+    /// assert_eq!(token, "let");
+    /// # #[cfg(feature = "provenance")]
+    /// assert_eq!(token.subslice(1..2).label(), Some("lexer"));
+    /// ```
+    #[track_caller]
+    pub fn labeled(label: &'static str, rcstring: Rc<String>, range: Range<usize>) -> Self {
+        validate_labeled(&rcstring, &range, Some(label));
+        RcSubstring::from_parts(Backing::Shared(rcstring), range).with_label(label)
+    }
+}
+
+impl<S: StringStorage> Substring<S> {
+    /// This substring labeled with what made it
+    ///
+    /// Does nothing without the `provenance` feature.
+    #[allow(unused_mut, unused_variables)]
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        #[cfg(feature = "provenance")]
+        {
+            self.label = Some(label);
+        }
+        self
+    }
+
+    /// The label given by [RcSubstring::labeled] or [RcSubstring::with_label]
+    ///
+    /// Always `None` without the `provenance` feature.
+    pub fn label(&self) -> Option<&'static str> {
+        #[cfg(feature = "provenance")]
+        return self.label;
+        #[cfg(not(feature = "provenance"))]
+        None
+    }
+
+    /// Where this substring was created
+    ///
+    /// Only known in debug builds or with the `provenance` feature.
+    pub fn created_at(&self) -> Option<&'static Location<'static>> {
+        #[cfg(any(debug_assertions, feature = "provenance"))]
+        return Some(self.created_at);
+        #[cfg(not(any(debug_assertions, feature = "provenance")))]
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_labels() {
+        let source = Rc::new(String::from("fn main"));
+        let name = RcSubstring::labeled("parser", Rc::clone(&source), 3..7);
+        assert_eq!(name, "main");
+        let plain = RcSubstring::new(source, 0..2);
+        assert_eq!(plain.label(), None);
+        #[cfg(feature = "provenance")]
+        {
+            assert_eq!(name.label(), Some("parser"));
+            assert_eq!(name.reslice(0..1).label(), Some("parser"));
+            assert_eq!(plain.with_label("other").label(), Some("other"));
+        }
+        #[cfg(not(feature = "provenance"))]
+        assert_eq!(name.label(), None);
+        #[cfg(debug_assertions)]
+        assert_eq!(name.created_at().unwrap().file(), file!());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "for RcSubstring \"parser\" created at")]
+    fn test_labeled_panics() {
+        RcSubstring::labeled("parser", Rc::new(String::from("abc")), 2..4);
+    }
+}
@@ -0,0 +1,136 @@
+//! Conversions between [RcSubstring] and Python `str` with `pyo3`
+use crate::{IntoRcSubstring, RcSubstring};
+use ::pyo3::prelude::*;
+use ::pyo3::types::PyString;
+use std::convert::Infallible;
+
+impl<'py> IntoPyObject<'py> for RcSubstring {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = Infallible;
+
+    /// Copy the text into a new Python `str`
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, &self))
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &RcSubstring {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = Infallible;
+
+    /// Copy the text into a new Python `str`
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, self))
+    }
+}
+
+impl FromPyObject<'_, '_> for RcSubstring {
+    type Error = PyErr;
+
+    /// Copy a Python `str` into a new `Rc<String>` and wrap the whole of it
+    fn extract(obj: Borrowed<'_, '_, PyAny>) -> Result<Self, Self::Error> {
+        String::extract(obj).map(RcSubstring::from)
+    }
+}
+
+/**
+A Python class wrapping an [RcSubstring], exposing its `text` and its `start` and `end` in the source
+
+`start` and `end` are byte offsets into the UTF-8 source text, not char indices.
+The class is `unsendable` as an [RcSubstring] can't be shared between threads.
+*/
+#[pyclass(unsendable, from_py_object, name = "Span", module = "rcsubstring")]
+#[derive(Debug, Clone)]
+pub struct PySpan {
+    substring: RcSubstring,
+}
+
+impl PySpan {
+    /// Wrap `substring`
+    pub fn new(substring: impl IntoRcSubstring) -> Self {
+        PySpan {
+            substring: substring.into_rc_substring(),
+        }
+    }
+
+    /// The wrapped substring
+    pub fn substring(&self) -> &RcSubstring {
+        &self.substring
+    }
+}
+
+impl From<RcSubstring> for PySpan {
+    fn from(substring: RcSubstring) -> Self {
+        PySpan::new(substring)
+    }
+}
+
+#[pymethods]
+impl PySpan {
+    /// The text of the span
+    #[getter]
+    fn text(&self) -> &str {
+        &self.substring
+    }
+
+    /// Byte offset of the start of the span in the source
+    #[getter]
+    fn start(&self) -> usize {
+        self.substring.range.start
+    }
+
+    /// Byte offset of the end of the span in the source
+    #[getter]
+    fn end(&self) -> usize {
+        self.substring.range.end
+    }
+
+    fn __str__(&self) -> &str {
+        &self.substring
+    }
+
+    fn __len__(&self) -> usize {
+        self.substring.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Span({:?}, {}, {})",
+            self.substring.as_str(),
+            self.start(),
+            self.end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_conversions() {
+        Python::initialize();
+        Python::attach(|py| {
+            let substring = RcSubstring::new(Rc::new(String::from("key=value")), 4..9);
+            let text = (&substring).into_pyobject(py).unwrap();
+            assert_eq!(text.to_str().unwrap(), "value");
+            let back: RcSubstring = text.extract().unwrap();
+            assert_eq!(back, "value");
+
+            let span = Bound::new(py, PySpan::from(substring)).unwrap();
+            assert_eq!(
+                span.getattr("start").unwrap().extract::<usize>().unwrap(),
+                4
+            );
+            assert_eq!(span.getattr("end").unwrap().extract::<usize>().unwrap(), 9);
+            assert_eq!(span.str().unwrap().to_str().unwrap(), "value");
+            assert_eq!(
+                span.repr().unwrap().to_str().unwrap(),
+                "Span(\"value\", 4, 9)"
+            );
+        });
+    }
+}
@@ -0,0 +1,172 @@
+//! Splitting URL query strings into key/value [RcSubstring]s
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+/**
+Iterator over the `key=value` pairs of a query string
+
+Created by [RcSubstring::query_pairs]
+*/
+#[derive(Debug, Clone)]
+pub struct QueryPairs {
+    query: RcSubstring,
+    pos: usize,
+    end: usize,
+}
+
+impl QueryPairs {
+    /// Percent-decode each key and value
+    ///
+    /// Keys and values without escapes still share the query string.
+    /// Note `+` is not treated as a space.
+    #[cfg(feature = "percent-encoding")]
+    pub fn decoded(self) -> impl Iterator<Item = (RcSubstring, Option<RcSubstring>)> {
+        self.map(|(key, value)| {
+            (
+                key.percent_decoded(),
+                value.map(|value| value.percent_decoded()),
+            )
+        })
+    }
+}
+
+impl QueryPairs {
+    // Split the pair of length len at start into its key and value
+    fn pair(&self, start: usize, len: usize) -> (RcSubstring, Option<RcSubstring>) {
+        match self.query[start..start + len].find('=') {
+            Some(i) => (
+                self.query.reslice(start..start + i),
+                Some(self.query.reslice(start + i + 1..start + len)),
+            ),
+            None => (self.query.reslice(start..start + len), None),
+        }
+    }
+}
+
+impl Iterator for QueryPairs {
+    type Item = (RcSubstring, Option<RcSubstring>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.end {
+            let rest = &self.query[self.pos..self.end];
+            let len = rest.find('&').unwrap_or(rest.len());
+            let start = self.pos;
+            self.pos = (start + len + 1).min(self.end);
+            if len > 0 {
+                return Some(self.pair(start, len));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Each pair but the last needs at least one byte and a separator
+        let remaining = self.end - self.pos;
+        (0, Some(remaining.div_ceil(2)))
+    }
+}
+
+impl DoubleEndedIterator for QueryPairs {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.pos < self.end {
+            let rest = &self.query[self.pos..self.end];
+            let start = rest.rfind('&').map_or(self.pos, |i| self.pos + i + 1);
+            let len = self.end - start;
+            self.end = start.saturating_sub(1).max(self.pos);
+            if len > 0 {
+                return Some(self.pair(start, len));
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for QueryPairs {}
+
+impl RcSubstring {
+    /// Split a URL query string into its `key=value` pairs
+    ///
+    /// Pairs are separated by `&` and empty pairs are skipped. The key is split from the
+    /// value at the first `=`; a pair with no `=` has a value of `None`.
+    /// Nothing is decoded, so the keys and values all share this substring's `Rc<String>`.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let url = Rc::new(String::from("/search?q=rust&page=2&verbose"));
+    /// let query = RcSubstring::new(Rc::clone(&url), 8..url.len());
+    /// let pairs: Vec<_> = query.query_pairs().collect();
+    /// drop(url);
+    /// assert_eq!(pairs[0].0, "q");
+    /// assert_eq!(pairs[0].1.as_ref().unwrap(), &"rust");
+    /// assert_eq!(pairs[2].0, "verbose");
+    /// assert!(pairs[2].1.is_none());
+    /// ```
+    pub fn query_pairs(&self) -> QueryPairs {
+        QueryPairs {
+            query: self.clone(),
+            pos: 0,
+            end: self.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn pairs(query: &str) -> Vec<(String, Option<String>)> {
+        RcSubstring::from(String::from(query))
+            .query_pairs()
+            .map(|(key, value)| (key.to_string(), value.map(|value| value.to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn test_query_pairs() {
+        assert_eq!(
+            pairs("a=1&&b=&c&d=x=y&"),
+            [
+                (String::from("a"), Some(String::from("1"))),
+                (String::from("b"), Some(String::new())),
+                (String::from("c"), None),
+                (String::from("d"), Some(String::from("x=y"))),
+            ]
+        );
+        assert!(pairs("").is_empty());
+        assert!(pairs("&&").is_empty());
+    }
+
+    #[test]
+    fn test_pairs_from_back() {
+        let query = RcSubstring::from(String::from("&a=1&&b&c="));
+        let keys: Vec<_> = query.query_pairs().rev().map(|(key, _)| key).collect();
+        assert_eq!(keys, ["c", "b", "a"]);
+        let mut pairs = query.query_pairs();
+        assert_eq!(pairs.next_back().unwrap().0, "c");
+        assert_eq!(pairs.next().unwrap().0, "a");
+        assert_eq!(pairs.next_back().unwrap().0, "b");
+        assert!(pairs.next().is_none());
+        assert!(pairs.next_back().is_none());
+        assert!(query.query_pairs().size_hint().1.unwrap() >= 3);
+    }
+
+    #[test]
+    fn test_pairs_share_source() {
+        let source = Rc::new(String::from("?id=7"));
+        let query = RcSubstring::new(Rc::clone(&source), 1..5);
+        let (key, value) = query.query_pairs().next().unwrap();
+        assert!(Rc::ptr_eq(key.shared().unwrap(), &source));
+        assert_eq!(value.unwrap().range, 4..5);
+    }
+
+    #[cfg(feature = "percent-encoding")]
+    #[test]
+    fn test_decoded() {
+        let query = RcSubstring::from(String::from("name=J%C3%BCrgen&x"));
+        let pairs: Vec<_> = query.query_pairs().decoded().collect();
+        assert_eq!(pairs[0].1.as_ref().unwrap(), &"Jürgen");
+        assert_eq!(pairs[1].0, "x");
+    }
+}
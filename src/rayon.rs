@@ -0,0 +1,187 @@
+//! Splitting the text of a thread-safe [Substring] in parallel with `rayon`
+use crate::{StringStorage, Substring};
+use ::rayon::iter::ParallelIterator;
+use ::rayon::iter::plumbing::UnindexedConsumer;
+use ::rayon::str::ParallelString;
+use std::ops::Range;
+use std::sync::Arc;
+
+macro_rules! par_split {
+    ($($storage:ty),*) => {
+        $(
+            impl Substring<$storage> {
+                /// The lines of the text, split in parallel as by [str::lines]
+                ///
+                /// Each line is a substring of the same storage, so no text is copied.
+                pub fn par_lines_owned(&self) -> ParLines<$storage> {
+                    ParLines {
+                        storage: self.storage().clone(),
+                        range: self.range(),
+                    }
+                }
+
+                /// The parts of the text separated by `separator`, split in parallel
+                pub fn par_split_owned(&self, separator: char) -> ParSplit<$storage> {
+                    ParSplit {
+                        storage: self.storage().clone(),
+                        range: self.range(),
+                        separator,
+                    }
+                }
+
+                /// The words of the text separated by whitespace, split in parallel as by
+                /// [str::split_whitespace]
+                pub fn par_split_whitespace_owned(&self) -> ParSplitWhitespace<$storage> {
+                    ParSplitWhitespace {
+                        storage: self.storage().clone(),
+                        range: self.range(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+par_split!(Arc<str>, Arc<String>, &'static str);
+
+// Drive the pieces that split finds in the text of range into consumer, as substrings of storage
+fn drive_pieces<'a, S, I, C>(
+    storage: &'a S,
+    range: Range<usize>,
+    split: impl FnOnce(&'a str) -> I,
+    consumer: C,
+) -> C::Result
+where
+    S: StringStorage + Send + Sync,
+    Substring<S>: Send,
+    I: ParallelIterator<Item = &'a str>,
+    C: UnindexedConsumer<Substring<S>>,
+{
+    let text = &storage.as_str()[range.clone()];
+    split(text)
+        .map(|piece| {
+            let start = range.start + (piece.as_ptr() as usize - text.as_ptr() as usize);
+            Substring::new(storage.clone(), start..start + piece.len())
+        })
+        .drive_unindexed(consumer)
+}
+
+/**
+Parallel iterator over the lines of a [Substring]
+
+Created by [`Substring::par_lines_owned`]
+
+```rust
+# use rcsubstring::Substring;
+# use rayon::prelude::*;
+# use std::sync::Arc;
+let log = Substring::whole(Arc::<str>::from("GET /\nPOST /login\nGET /about\n"));
+let gets: Vec<_> = log
+    .par_lines_owned()
+    .filter(|line| line.starts_with("GET"))
+    .collect();
+assert_eq!(gets, ["GET /", "GET /about"]);
+assert_eq!(gets[1].range(), 18..28);
+```
+*/
+#[derive(Debug, Clone)]
+pub struct ParLines<S> {
+    storage: S,
+    range: Range<usize>,
+}
+
+impl<S> ParallelIterator for ParLines<S>
+where
+    S: StringStorage + Send + Sync,
+    Substring<S>: Send,
+{
+    type Item = Substring<S>;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        drive_pieces(&self.storage, self.range, |text| text.par_lines(), consumer)
+    }
+}
+
+/**
+Parallel iterator over the parts of a [Substring] between separators
+
+Created by [`Substring::par_split_owned`]
+*/
+#[derive(Debug, Clone)]
+pub struct ParSplit<S> {
+    storage: S,
+    range: Range<usize>,
+    separator: char,
+}
+
+impl<S> ParallelIterator for ParSplit<S>
+where
+    S: StringStorage + Send + Sync,
+    Substring<S>: Send,
+{
+    type Item = Substring<S>;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        let separator = self.separator;
+        let split = move |text| ParallelString::par_split(text, separator);
+        drive_pieces(&self.storage, self.range, split, consumer)
+    }
+}
+
+/**
+Parallel iterator over the words of a [Substring]
+
+Created by [`Substring::par_split_whitespace_owned`]
+*/
+#[derive(Debug, Clone)]
+pub struct ParSplitWhitespace<S> {
+    storage: S,
+    range: Range<usize>,
+}
+
+impl<S> ParallelIterator for ParSplitWhitespace<S>
+where
+    S: StringStorage + Send + Sync,
+    Substring<S>: Send,
+{
+    type Item = Substring<S>;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        let split = |text| ParallelString::par_split_whitespace(text);
+        drive_pieces(&self.storage, self.range, split, consumer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_split() {
+        let source: Arc<str> = Arc::from("  a,bé ,,c\r\nd\n\n");
+        let text = Substring::new(Arc::clone(&source), 2..source.len());
+        let lines: Vec<_> = text.par_lines_owned().collect();
+        assert_eq!(lines, ["a,bé ,,c", "d", ""]);
+        assert!(Arc::ptr_eq(lines[1].storage(), &source));
+        assert_eq!(lines[1].range(), 13..14);
+        let fields: Vec<_> = text.substring(0..10).par_split_owned(',').collect();
+        assert_eq!(fields, ["a", "bé ", "", "c\r"]);
+        assert_eq!(fields[3].range(), 10..12);
+        let words: Vec<_> = Substring::whole(" x  yz\t")
+            .par_split_whitespace_owned()
+            .collect();
+        assert_eq!(words, ["x", "yz"]);
+        assert_eq!(words[1].range(), 4..6);
+    }
+
+    #[test]
+    fn test_many_lines() {
+        let source = Arc::new((0..10_000).map(|i| format!("{i}\n")).collect::<String>());
+        let text = Substring::whole(Arc::clone(&source));
+        let total: usize = text
+            .par_lines_owned()
+            .map(|line| line.parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(total, 49_995_000);
+    }
+}
@@ -0,0 +1,181 @@
+//! Masking spans of a text, eg. to scrub secrets from logs
+use crate::RcSubstring;
+use std::ops::Range;
+use std::rc::Rc;
+
+/// A copy of `source` with the text of each of `spans` replaced by `mask`
+///
+/// Each char of a span is replaced by one `mask`, so the text keeps its length in chars,
+/// and in bytes too if the masked chars are the same length as `mask` (eg. ASCII text and
+/// an ASCII mask). The spans can overlap and be in any order. If there is nothing to mask
+/// the result shares `source`.
+///
+/// # Panics
+///
+/// Panics if any of `spans` is not of `source`
+///
+/// ```rust
+/// # use rcsubstring::{RcSubstring, redact};
+/// # use std::rc::Rc;
+/// let log = Rc::new(String::from("user=bob password=hunter2"));
+/// let secret = RcSubstring::new(Rc::clone(&log), 18..25);
+/// assert_eq!(redact(&log, &[secret], '*'), "user=bob password=*******");
+/// ```
+#[track_caller]
+pub fn redact(source: &Rc<String>, spans: &[RcSubstring], mask: char) -> RcSubstring {
+    let mut set = SpanSet::new(Rc::clone(source));
+    for span in spans {
+        set.insert(span);
+    }
+    set.redact(mask)
+}
+
+/**
+A set of spans of one `Rc<String>`, with overlapping and adjacent spans merged
+
+This collects what to mask over several passes (eg. one per search pattern), ready for
+[SpanSet::redact].
+
+```rust
+# use rcsubstring::{RcSubstring, SpanSet};
+# use std::rc::Rc;
+let source = Rc::new(String::from("token abc123 key xyz"));
+let mut secrets = SpanSet::new(Rc::clone(&source));
+secrets.insert(&RcSubstring::new(Rc::clone(&source), 6..9));
+secrets.insert(&RcSubstring::new(Rc::clone(&source), 9..12));
+secrets.insert(&RcSubstring::new(Rc::clone(&source), 17..20));
+assert_eq!(secrets.iter().collect::<Vec<_>>(), ["abc123", "xyz"]);
+assert_eq!(secrets.redact('#'), "token ###### key ###");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct SpanSet {
+    source: Rc<String>,
+    // Sorted, and none overlap or touch
+    ranges: Vec<Range<usize>>,
+}
+
+impl SpanSet {
+    /// An empty set of spans of `source`
+    pub fn new(source: Rc<String>) -> Self {
+        SpanSet {
+            source,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// The source the spans are of
+    pub fn source(&self) -> &Rc<String> {
+        &self.source
+    }
+
+    /// Add `span` to the set, merging it with any spans it overlaps or touches
+    ///
+    /// Empty spans are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `span` is not of the source
+    #[track_caller]
+    pub fn insert(&mut self, span: &RcSubstring) {
+        assert!(
+            span.shared().is_some_and(|rc| Rc::ptr_eq(rc, &self.source)),
+            "span {:?} is not of the SpanSet source",
+            span.as_str()
+        );
+        let mut range = span.range.clone();
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.end < range.start);
+        let last = self.ranges.partition_point(|r| r.start <= range.end);
+        if first < last {
+            range.start = range.start.min(self.ranges[first].start);
+            range.end = range.end.max(self.ranges[last - 1].end);
+        }
+        self.ranges.splice(first..last, [range]);
+    }
+
+    /// True if the byte at `offset` of the source is in one of the spans
+    pub fn contains(&self, offset: usize) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= offset);
+        self.ranges.get(i).is_some_and(|r| r.start <= offset)
+    }
+
+    /// The number of merged spans
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// True if there are no spans
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The merged spans in order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = RcSubstring> + ExactSizeIterator + '_ {
+        self.ranges
+            .iter()
+            .map(|range| RcSubstring::new(Rc::clone(&self.source), range.clone()))
+    }
+
+    /// A copy of the source with each char of the spans replaced by `mask`, as for [redact]
+    pub fn redact(&self, mask: char) -> RcSubstring {
+        if self.ranges.is_empty() {
+            return RcSubstring::from(Rc::clone(&self.source));
+        }
+        let mut text = String::with_capacity(self.source.len());
+        let mut copied = 0;
+        for range in &self.ranges {
+            text.push_str(&self.source[copied..range.start]);
+            text.extend(self.source[range.clone()].chars().map(|_| mask));
+            copied = range.end;
+        }
+        text.push_str(&self.source[copied..]);
+        RcSubstring::from(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_set() {
+        let source = Rc::new(String::from("0123456789"));
+        let mut set = SpanSet::new(Rc::clone(&source));
+        let span = |range| RcSubstring::new(Rc::clone(&source), range);
+        for range in [6..8, 1..2, 3..4, 5..5, 2..3, 8..9] {
+            set.insert(&span(range));
+        }
+        assert_eq!(set.iter().collect::<Vec<_>>(), ["123", "678"]);
+        set.insert(&span(0..7));
+        assert_eq!(set.iter().collect::<Vec<_>>(), ["012345678"]);
+        assert_eq!(set.len(), 1);
+        assert!(set.contains(8));
+        assert!(!set.contains(9));
+        assert!(Rc::ptr_eq(set.source(), &source));
+    }
+
+    #[test]
+    fn test_redact() {
+        let source = Rc::new(String::from("pin: 1234, név: Éva"));
+        let spans = [
+            RcSubstring::new(Rc::clone(&source), 5..9),
+            RcSubstring::new(Rc::clone(&source), 17..21),
+            RcSubstring::new(Rc::clone(&source), 7..8),
+        ];
+        let redacted = redact(&source, &spans, 'x');
+        assert_eq!(redacted, "pin: xxxx, név: xxx");
+        assert_eq!(redact(&source, &spans[..1], '█'), "pin: ████, név: Éva");
+        let unchanged = redact(&source, &[], 'x');
+        assert!(Rc::ptr_eq(unchanged.shared().unwrap(), &source));
+    }
+
+    #[test]
+    #[should_panic(expected = "not of the SpanSet source")]
+    fn test_other_source() {
+        let source = Rc::new(String::from("abc"));
+        redact(&source, &[RcSubstring::from(String::from("abc"))], '*');
+    }
+}
@@ -0,0 +1,319 @@
+//! Regular expression matching over an [RcSubstring] using the `regex` crate
+use crate::{RcPattern, RcSubstring};
+use ::regex::{Regex, Replacer};
+use std::borrow::Cow;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+impl RcSubstring {
+    /// Find the leftmost-first match of `re` in this substring
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// # use regex::Regex;
+    /// let text = RcSubstring::new(Rc::new(String::from("version 1.42 released")), 0..21);
+    /// let re = Regex::new(r"\d+\.\d+").unwrap();
+    /// let version = text.regex_find_owned(&re).unwrap();
+    /// drop(text);
+    /// assert_eq!(version, "1.42");
+    /// ```
+    pub fn regex_find_owned(&self, re: &Regex) -> Option<RcSubstring> {
+        re.find(self).map(|m| self.reslice(m.range()))
+    }
+
+    /// Iterate over all non-overlapping matches of `re` in this substring
+    pub fn regex_find_iter_owned(&self, re: &Regex) -> RegexMatches {
+        RegexMatches {
+            regex: re.clone(),
+            haystack: self.clone(),
+            pos: 0,
+            last_match_end: None,
+        }
+    }
+
+    /// Find the capture groups of the leftmost-first match of `re` in this substring
+    pub fn regex_captures_owned(&self, re: &Regex) -> Option<RcCaptures> {
+        let captures = re.captures(self)?;
+        let groups = captures
+            .iter()
+            .map(|group| group.map(|m| self.reslice(m.range())))
+            .collect();
+        Some(RcCaptures {
+            regex: re.clone(),
+            groups,
+        })
+    }
+
+    /// Split this substring on the matches of `re`
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// # use regex::Regex;
+    /// let text = RcSubstring::new(Rc::new(String::from("a,  b,c")), 0..7);
+    /// let re = Regex::new(r",\s*").unwrap();
+    /// let parts: Vec<_> = text.regex_split_owned(&re).collect();
+    /// assert_eq!(parts, ["a", "b", "c"]);
+    /// ```
+    pub fn regex_split_owned(&self, re: &Regex) -> RegexSplit {
+        RegexSplit {
+            matches: self.regex_find_iter_owned(re),
+            last: 0,
+            finished: false,
+        }
+    }
+
+    /// Replace all matches of `re` with `rep`
+    ///
+    /// If there are no matches this is just a clone of `self` and nothing is allocated.
+    /// Otherwise the result wraps a new `Rc<String>` holding the replaced text.
+    pub fn regex_replace_all_owned<R: Replacer>(&self, re: &Regex, rep: R) -> RcSubstring {
+        match re.replace_all(self, rep) {
+            Cow::Borrowed(_) => self.clone(),
+            Cow::Owned(replaced) => RcSubstring::from(replaced),
+        }
+    }
+}
+
+/// Iterator over the matches of a [Regex] in an [RcSubstring]
+///
+/// Created by [RcSubstring::regex_find_iter_owned]
+#[derive(Debug, Clone)]
+pub struct RegexMatches {
+    regex: Regex,
+    haystack: RcSubstring,
+    pos: usize,
+    last_match_end: Option<usize>,
+}
+
+impl RegexMatches {
+    // Next match as a range relative to the haystack
+    //
+    // Follows the same rules as `Regex::find_iter` so an empty match
+    // is never reported directly after the end of the previous match
+    fn next_range(&mut self) -> Option<Range<usize>> {
+        let len = self.haystack.len();
+        while self.pos <= len {
+            let Some(found) = self.regex.find_at(&self.haystack, self.pos) else {
+                break;
+            };
+            if found.is_empty() && Some(found.end()) == self.last_match_end {
+                self.pos = found.end()
+                    + self.haystack[found.end()..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8);
+                continue;
+            }
+            self.pos = found.end();
+            self.last_match_end = Some(found.end());
+            return Some(found.range());
+        }
+        self.pos = len + 1;
+        None
+    }
+}
+
+impl Iterator for RegexMatches {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = self.next_range()?;
+        Some(self.haystack.reslice(range))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every match moves the search on by at least one byte
+        (0, Some((self.haystack.len() + 1).saturating_sub(self.pos)))
+    }
+}
+
+impl FusedIterator for RegexMatches {}
+
+/// Iterator over the parts of an [RcSubstring] between matches of a [Regex]
+///
+/// Not double ended, as [Regex] can't search backwards.
+/// Created by [RcSubstring::regex_split_owned]
+#[derive(Debug, Clone)]
+pub struct RegexSplit {
+    matches: RegexMatches,
+    last: usize,
+    finished: bool,
+}
+
+impl Iterator for RegexSplit {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        match self.matches.next_range() {
+            Some(range) => {
+                let piece = self.matches.haystack.reslice(self.last..range.start);
+                self.last = range.end;
+                Some(piece)
+            }
+            None => {
+                self.finished = true;
+                let len = self.matches.haystack.len();
+                Some(self.matches.haystack.reslice(self.last..len))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+        let (_, matches) = self.matches.size_hint();
+        (1, matches.map(|matches| matches + 1))
+    }
+}
+
+impl FusedIterator for RegexSplit {}
+
+/**
+The capture groups of a single regex match
+
+Each group that participated in the match is held as an [RcSubstring]
+sharing the source of the text that was searched.
+
+Created by [RcSubstring::regex_captures_owned]
+*/
+#[derive(Debug, Clone)]
+pub struct RcCaptures {
+    regex: Regex,
+    groups: Vec<Option<RcSubstring>>,
+}
+
+impl RcCaptures {
+    /// The group with index `i`, if it participated in the match
+    ///
+    /// Group 0 is always the whole match
+    pub fn get(&self, i: usize) -> Option<&RcSubstring> {
+        self.groups.get(i)?.as_ref()
+    }
+
+    /// The group with the given name, if it exists and participated in the match
+    pub fn name(&self, name: &str) -> Option<&RcSubstring> {
+        let i = self
+            .regex
+            .capture_names()
+            .position(|group_name| group_name == Some(name))?;
+        self.get(i)
+    }
+
+    /// The number of groups in the regex, including the implicit group 0
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Always false as there is always at least the implicit group 0
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Iterate over all groups in order
+    pub fn iter(&self) -> impl Iterator<Item = Option<&RcSubstring>> {
+        self.groups.iter().map(Option::as_ref)
+    }
+}
+
+/// Matches of the regex, so it can be used with [RcSubstring::split_pattern] and the like
+///
+/// A prefix is only found if the leftmost-first match starts at the start.
+impl RcPattern for &Regex {
+    fn find_in(&mut self, haystack: &str, start: usize) -> Option<Range<usize>> {
+        self.find_at(haystack, start).map(|m| m.range())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_find_iter() {
+        let text = RcSubstring::from(String::from("a1 b22 c333"));
+        let re = Regex::new(r"\d+").unwrap();
+        let found: Vec<_> = text.regex_find_iter_owned(&re).collect();
+        assert_eq!(found, ["1", "22", "333"]);
+
+        // Empty matches behave the same as Regex::find_iter
+        let re = Regex::new(r"\d*").unwrap();
+        let expected: Vec<_> = re.find_iter(&text).map(|m| m.range()).collect();
+        let found: Vec<_> = text
+            .regex_find_iter_owned(&re)
+            .map(|s| s.range.clone())
+            .collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_find_in_substring() {
+        let text = RcSubstring::new(Rc::new(String::from("x=1, y=2")), 5..8);
+        let re = Regex::new(r"\d").unwrap();
+        let found = text.regex_find_owned(&re).unwrap();
+        assert_eq!(found, "2");
+        assert_eq!(found.range, 7..8);
+    }
+
+    #[test]
+    fn test_captures() {
+        let text = RcSubstring::from(String::from("key: value"));
+        let re = Regex::new(r"(?<key>\w+): (\w+)(!)?").unwrap();
+        let captures = text.regex_captures_owned(&re).unwrap();
+        drop(text);
+        assert_eq!(captures.len(), 4);
+        assert_eq!(*captures.get(0).unwrap(), "key: value");
+        assert_eq!(*captures.name("key").unwrap(), "key");
+        assert_eq!(*captures.get(2).unwrap(), "value");
+        assert!(captures.get(3).is_none());
+        assert!(captures.name("missing").is_none());
+    }
+
+    #[test]
+    fn test_split() {
+        let re = Regex::new(r"\s+").unwrap();
+        let text = RcSubstring::from(String::from(" one two  three "));
+        let expected: Vec<_> = re.split(&text).collect();
+        let parts: Vec<_> = text.regex_split_owned(&re).collect();
+        assert_eq!(parts, expected);
+        let mut split = text.regex_split_owned(&re);
+        assert_eq!(split.size_hint(), (1, Some(18)));
+        split.by_ref().for_each(drop);
+        assert_eq!(split.size_hint(), (0, Some(0)));
+        assert!(split.next().is_none());
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let text = RcSubstring::from(String::from("width=10 height=20"));
+        let re = Regex::new(r"(\w+)=(\d+)").unwrap();
+        let replaced = text.regex_replace_all_owned(&re, "${1}:${2}px");
+        assert_eq!(replaced, "width:10px height:20px");
+        assert!(!replaced.rcstring.same_text(&text.rcstring));
+
+        let re = Regex::new(r"\d{3}").unwrap();
+        let unchanged = text.regex_replace_all_owned(&re, "");
+        assert_eq!(unchanged, "width=10 height=20");
+        assert!(unchanged.rcstring.same_text(&text.rcstring));
+        assert_eq!(unchanged.range, text.range);
+    }
+
+    #[test]
+    fn test_regex_pattern() {
+        let re = Regex::new(r"\s*;\s*").unwrap();
+        let text = RcSubstring::from(String::from(" ;a ; b;"));
+        let parts: Vec<_> = text.split_pattern(&re).collect();
+        assert_eq!(parts, ["", "a", "b", ""]);
+        let digits = Regex::new(r"\d+").unwrap();
+        let text = RcSubstring::from(String::from("12ab34"));
+        assert_eq!(text.match_prefix(&digits).unwrap(), "12");
+        assert_eq!(text.match_suffix(&digits).unwrap(), "34");
+        assert_eq!(text.trim_matches_owned(&digits), "ab");
+    }
+}
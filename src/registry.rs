@@ -0,0 +1,178 @@
+//! A central store of named sources, for tools that work on many files
+use crate::{RcSubstring, RcSubstringError};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+Owns the `Rc<String>` source of each name, such as the files a compiler has read
+
+Sources with identical text are stored once, however many names they are added under.
+Spans are made by name and range, and [SourceRegistry::live_sources] shows which sources
+are still pinned by substrings outside the registry.
+
+```rust
+# use rcsubstring::SourceRegistry;
+let mut sources = SourceRegistry::new();
+sources.insert("main.rs", "fn main() {}");
+sources.insert("copy.rs", "fn main() {}");
+let name = sources.span("main.rs", 3..7).unwrap();
+assert_eq!(name, "main");
+assert!(sources.span("missing.rs", 0..1).is_none());
+assert_eq!(sources.retained_bytes(), 12);
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct SourceRegistry {
+    // In the order they were first added
+    names: Vec<(String, Rc<String>)>,
+    index: HashMap<String, usize>,
+    contents: HashSet<Rc<String>>,
+}
+
+impl SourceRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        SourceRegistry::default()
+    }
+
+    /// Add `text` under `name`, returning the shared source
+    ///
+    /// If the registry already has a source with the same text that is shared instead.
+    /// Adding a name again replaces its source, though substrings of the old one still work.
+    pub fn insert(&mut self, name: impl Into<String>, text: impl Into<String>) -> Rc<String> {
+        let text = text.into();
+        let source = match self.contents.get(&text) {
+            Some(source) => Rc::clone(source),
+            None => {
+                let source = Rc::new(text);
+                self.contents.insert(Rc::clone(&source));
+                source
+            }
+        };
+        let name = name.into();
+        match self.index.get(&name) {
+            Some(&i) => {
+                let old = std::mem::replace(&mut self.names[i].1, Rc::clone(&source));
+                self.forget_unnamed(&old);
+            }
+            None => {
+                self.index.insert(name.clone(), self.names.len());
+                self.names.push((name, Rc::clone(&source)));
+            }
+        }
+        source
+    }
+
+    // Stop deduplicating against source if no name uses it any more
+    fn forget_unnamed(&mut self, source: &Rc<String>) {
+        if !self
+            .names
+            .iter()
+            .any(|(_, other)| Rc::ptr_eq(other, source))
+        {
+            self.contents.remove(source);
+        }
+    }
+
+    /// The source added under `name`
+    pub fn get(&self, name: &str) -> Option<Rc<String>> {
+        let &i = self.index.get(name)?;
+        Some(Rc::clone(&self.names[i].1))
+    }
+
+    /// A substring of the source added under `name`
+    ///
+    /// Returns `None` if there is no such source, or `range` can't be used for a substring of it.
+    pub fn span(&self, name: &str, range: Range<usize>) -> Option<RcSubstring> {
+        let source = self.get(name)?;
+        RcSubstringError::check_kind(&source, &range).ok()?;
+        Some(RcSubstring::new(source, range))
+    }
+
+    /// The names and their sources, in the order the names were first added
+    pub fn sources(&self) -> impl ExactSizeIterator<Item = (&str, &Rc<String>)> {
+        self.names
+            .iter()
+            .map(|(name, source)| (name.as_str(), source))
+    }
+
+    /// The names whose sources are still used outside the registry, with the size of each
+    ///
+    /// These are the sources that would stay alive if the registry were dropped.
+    pub fn live_sources(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.sources().filter_map(|(name, source)| {
+            // The registry holds a count for each name with this source and one in contents
+            let held = 1 + self
+                .names
+                .iter()
+                .filter(|(_, other)| Rc::ptr_eq(other, source))
+                .count();
+            (Rc::strong_count(source) > held).then_some((name, source.len()))
+        })
+    }
+
+    /// The total size in bytes of the distinct sources held
+    pub fn retained_bytes(&self) -> usize {
+        self.contents.iter().map(|source| source.len()).sum()
+    }
+
+    /// The number of names
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// True if nothing has been added
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deduplicates() {
+        let mut registry = SourceRegistry::new();
+        let a = registry.insert("a", "same");
+        let b = registry.insert(String::from("b"), String::from("same"));
+        assert!(Rc::ptr_eq(&a, &b));
+        assert!(Rc::ptr_eq(&registry.get("b").unwrap(), &a));
+        assert_eq!(registry.len(), 2);
+        assert_eq!(registry.retained_bytes(), 4);
+        let names: Vec<_> = registry.sources().map(|(name, _)| name).collect();
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_replace_and_spans() {
+        let mut registry = SourceRegistry::new();
+        registry.insert("file", "old text");
+        let old = registry.span("file", 0..3).unwrap();
+        registry.insert("file", "néw");
+        assert_eq!(old, "old");
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.retained_bytes(), 4);
+        assert_eq!(registry.span("file", 1..3).unwrap(), "é");
+        assert!(registry.span("file", 1..2).is_none());
+        assert!(registry.span("file", 0..5).is_none());
+        let fresh = registry.insert("again", "old text");
+        assert_eq!(Rc::strong_count(&fresh), 3);
+    }
+
+    #[test]
+    fn test_live_sources() {
+        let mut registry = SourceRegistry::new();
+        registry.insert("kept", "kept");
+        registry.insert("dropped", "dropped");
+        registry.insert("same", "dropped");
+        assert_eq!(registry.live_sources().count(), 0);
+        let span = registry.span("kept", 0..2).unwrap();
+        let live: Vec<_> = registry.live_sources().collect();
+        assert_eq!(live, [("kept", 4)]);
+        drop(span);
+        assert!(registry.live_sources().next().is_none());
+        assert!(!registry.is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! Grouping the chars of an [RcSubstring] into runs of the same class
+use crate::RcSubstring;
+use std::fmt::Debug;
+use std::iter::FusedIterator;
+
+impl RcSubstring {
+    /// Split this substring into maximal runs of chars that `classify` puts in the same class
+    ///
+    /// Each item is the class of the run and its text.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// #[derive(Debug, PartialEq)]
+    /// enum Class { Digit, Alpha, Other }
+    /// let text = RcSubstring::from(String::from("abc123+x"));
+    /// let runs: Vec<_> = text
+    ///     .runs_by(|c| match c {
+    ///         '0'..='9' => Class::Digit,
+    ///         c if c.is_alphabetic() => Class::Alpha,
+    ///         _ => Class::Other,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(runs[1].0, Class::Digit);
+    /// assert_eq!(runs[1].1, "123");
+    /// assert_eq!(runs.len(), 4);
+    /// ```
+    pub fn runs_by<K, F>(&self, classify: F) -> RunsBy<F>
+    where
+        K: PartialEq,
+        F: Fn(char) -> K,
+    {
+        RunsBy {
+            text: self.clone(),
+            start: 0,
+            end: self.len(),
+            classify,
+        }
+    }
+
+    /// The maximal runs of chars that `pred` is true for
+    ///
+    /// This is the opposite of splitting on `pred`: it gives the runs that match and drops
+    /// the gaps between them.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("order 66, line 1024"));
+    /// let numbers: Vec<_> = text.spans_of(|c| c.is_ascii_digit()).collect();
+    /// assert_eq!(numbers, ["66", "1024"]);
+    /// ```
+    pub fn spans_of<F: Fn(char) -> bool>(&self, pred: F) -> SpansOf<F> {
+        SpansOf {
+            runs: self.runs_by(pred),
+        }
+    }
+}
+
+/**
+Iterator over the runs of chars of an [RcSubstring] in the same class
+
+Created by [RcSubstring::runs_by]
+*/
+#[derive(Clone)]
+pub struct RunsBy<F> {
+    text: RcSubstring,
+    start: usize,
+    end: usize,
+    classify: F,
+}
+
+impl<F> Debug for RunsBy<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunsBy")
+            .field("text", &self.text)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: PartialEq, F: Fn(char) -> K> Iterator for RunsBy<F> {
+    type Item = (K, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.start..self.end];
+        let mut chars = rest.char_indices();
+        let (_, first) = chars.next()?;
+        let class = (self.classify)(first);
+        let len = chars
+            .find(|&(_, c)| (self.classify)(c) != class)
+            .map_or(rest.len(), |(i, _)| i);
+        let run = self.text.reslice(self.start..self.start + len);
+        self.start += len;
+        Some((class, run))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining.min(1), Some(remaining))
+    }
+}
+
+impl<K: PartialEq, F: Fn(char) -> K> DoubleEndedIterator for RunsBy<F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.start..self.end];
+        let mut chars = rest.char_indices();
+        let (_, last) = chars.next_back()?;
+        let class = (self.classify)(last);
+        let start = chars
+            .rfind(|&(_, c)| (self.classify)(c) != class)
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let run = self.text.reslice(self.start + start..self.end);
+        self.end = self.start + start;
+        Some((class, run))
+    }
+}
+
+impl<K: PartialEq, F: Fn(char) -> K> FusedIterator for RunsBy<F> {}
+
+/**
+Iterator over the maximal runs of chars of an [RcSubstring] that match a predicate
+
+Created by [RcSubstring::spans_of]
+*/
+#[derive(Clone)]
+pub struct SpansOf<F> {
+    runs: RunsBy<F>,
+}
+
+impl<F> Debug for SpansOf<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpansOf").field("runs", &self.runs).finish()
+    }
+}
+
+impl<F: Fn(char) -> bool> Iterator for SpansOf<F> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runs.find_map(|(matched, run)| matched.then_some(run))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.runs.size_hint().1)
+    }
+}
+
+impl<F: Fn(char) -> bool> DoubleEndedIterator for SpansOf<F> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.runs
+            .by_ref()
+            .rev()
+            .find_map(|(matched, run)| matched.then_some(run))
+    }
+}
+
+impl<F: Fn(char) -> bool> FusedIterator for SpansOf<F> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn runs(text: &str) -> Vec<(bool, String)> {
+        RcSubstring::from(String::from(text))
+            .runs_by(char::is_whitespace)
+            .map(|(class, run)| (class, run.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_runs_by() {
+        assert_eq!(
+            runs("ab  é\t"),
+            [
+                (false, String::from("ab")),
+                (true, String::from("  ")),
+                (false, String::from("é")),
+                (true, String::from("\t")),
+            ]
+        );
+        assert!(runs("").is_empty());
+        assert_eq!(runs("x"), [(false, String::from("x"))]);
+    }
+
+    #[test]
+    fn test_runs_from_back() {
+        let text = RcSubstring::from(String::from("aa11bé22"));
+        let forward: Vec<_> = text.runs_by(|c| c.is_ascii_digit()).collect();
+        let mut backward: Vec<_> = text.runs_by(|c| c.is_ascii_digit()).rev().collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        let mut runs = text.runs_by(|c| c.is_ascii_digit());
+        assert_eq!(runs.next_back().unwrap().1, "22");
+        assert_eq!(runs.next().unwrap().1, "aa");
+        assert_eq!(runs.next_back().unwrap().1, "bé");
+        assert_eq!(runs.next().unwrap().1, "11");
+        assert!(runs.next().is_none());
+    }
+
+    #[test]
+    fn test_spans_of() {
+        let text = RcSubstring::from(String::from("ÉTÉ and AUTUMN"));
+        let upper: Vec<_> = text.spans_of(char::is_uppercase).collect();
+        assert_eq!(upper, ["ÉTÉ", "AUTUMN"]);
+        assert_eq!(upper[1].range, 10..16);
+        let mut spans = text.spans_of(char::is_lowercase);
+        assert_eq!(spans.next_back().unwrap(), "and");
+        assert!(spans.next().is_none());
+        assert_eq!(text.spans_of(char::is_numeric).count(), 0);
+    }
+}
@@ -0,0 +1,48 @@
+//! `schemars` JSON schema for [RcSubstring], the same as for a `String`
+use crate::RcSubstring;
+use ::schemars::{JsonSchema, Schema, SchemaGenerator};
+use std::borrow::Cow;
+
+impl JsonSchema for RcSubstring {
+    fn inline_schema() -> bool {
+        <str as JsonSchema>::inline_schema()
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        <str as JsonSchema>::schema_name()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        <str as JsonSchema>::schema_id()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        <str as JsonSchema>::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::schemars::schema_for;
+
+    #[test]
+    fn test_string_schema() {
+        assert_eq!(schema_for!(RcSubstring), schema_for!(String));
+        #[allow(dead_code)]
+        #[derive(JsonSchema)]
+        struct Token {
+            text: RcSubstring,
+            alias: Option<RcSubstring>,
+        }
+        #[allow(dead_code)]
+        #[derive(JsonSchema)]
+        struct Expected {
+            text: String,
+            alias: Option<String>,
+        }
+        let mut schema = schema_for!(Token);
+        schema.insert(String::from("title"), "Expected".into());
+        assert_eq!(schema, schema_for!(Expected));
+    }
+}
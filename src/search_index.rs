@@ -0,0 +1,207 @@
+//! A suffix array index for repeated searches of one `Rc<String>`
+use crate::RcSubstring;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+/**
+A suffix array with longest common prefixes over an `Rc<String>`
+
+Building the index takes O(n log² n) time for a text of n bytes, after which
+[SearchIndex::find_all] takes O(m log n) for a needle of m bytes plus the number of matches.
+Worth it for tools that run many searches over the same large text.
+
+```rust
+# use rcsubstring::SearchIndex;
+# use std::rc::Rc;
+let text = Rc::new(String::from("to be or not to be"));
+let index = SearchIndex::new(Rc::clone(&text));
+let found = index.find_all("be");
+assert_eq!(found.len(), 2);
+assert_eq!(index.longest_repeated().unwrap(), "to be");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    source: Rc<String>,
+    // Offsets of each suffix starting at a char boundary, in sorted order
+    suffixes: Vec<usize>,
+    // Length of the common prefix of each suffix with the one before it
+    lcp: Vec<usize>,
+}
+
+impl SearchIndex {
+    /// Build the index for `source`
+    pub fn new(source: Rc<String>) -> Self {
+        let bytes = source.as_bytes();
+        let all = suffix_array(bytes);
+        let all_lcp = kasai_lcp(bytes, &all);
+        // Keep the suffixes starting at char boundaries; the common prefix of
+        // two kept suffixes is the minimum over the skipped ones between them
+        let mut suffixes = Vec::new();
+        let mut lcp = Vec::new();
+        let mut common = usize::MAX;
+        for (&start, &prefix) in all.iter().zip(&all_lcp) {
+            common = common.min(prefix);
+            if source.is_char_boundary(start) {
+                lcp.push(if suffixes.is_empty() { 0 } else { common });
+                suffixes.push(start);
+                common = usize::MAX;
+            }
+        }
+        SearchIndex {
+            source,
+            suffixes,
+            lcp,
+        }
+    }
+
+    /// The indexed text
+    pub fn source(&self) -> &Rc<String> {
+        &self.source
+    }
+
+    /// Every occurrence of `needle`, in order of position
+    ///
+    /// Occurrences may overlap. An empty `needle` matches nothing.
+    pub fn find_all(&self, needle: &str) -> Vec<RcSubstring> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let needle = needle.as_bytes();
+        let compare = |&start: &usize| {
+            let suffix = &self.source.as_bytes()[start..];
+            suffix[..suffix.len().min(needle.len())].cmp(needle)
+        };
+        let first = self
+            .suffixes
+            .partition_point(|start| compare(start) == Ordering::Less);
+        let last = first
+            + self.suffixes[first..].partition_point(|start| compare(start) == Ordering::Equal);
+        let mut starts = self.suffixes[first..last].to_vec();
+        starts.sort_unstable();
+        starts
+            .into_iter()
+            .map(|start| RcSubstring::new(Rc::clone(&self.source), start..start + needle.len()))
+            .collect()
+    }
+
+    /// The longest substring that occurs more than once
+    ///
+    /// Returns the first by position if there are several of the same length,
+    /// or `None` if no char occurs twice.
+    pub fn longest_repeated(&self) -> Option<RcSubstring> {
+        let mut best: Option<(usize, usize)> = None;
+        for (i, &prefix) in self.lcp.iter().enumerate() {
+            let start = self.suffixes[i].min(self.suffixes[i.saturating_sub(1)]);
+            let mut len = prefix;
+            while !self.source.is_char_boundary(start + len) {
+                len -= 1;
+            }
+            let better = match best {
+                None => len > 0,
+                Some((best_start, best_len)) => {
+                    len > best_len || (len == best_len && start < best_start)
+                }
+            };
+            if better {
+                best = Some((start, len));
+            }
+        }
+        best.map(|(start, len)| RcSubstring::new(Rc::clone(&self.source), start..start + len))
+    }
+}
+
+// Sort all suffixes of bytes by prefix doubling
+fn suffix_array(bytes: &[u8]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut suffixes: Vec<usize> = (0..n).collect();
+    let mut rank: Vec<usize> = bytes.iter().map(|&b| b as usize).collect();
+    let mut next_rank = vec![0; n];
+    if n < 2 {
+        return suffixes;
+    }
+    let mut k = 1;
+    loop {
+        let key = |i: usize| (rank[i], if i + k < n { rank[i + k] + 1 } else { 0 });
+        suffixes.sort_unstable_by_key(|&i| key(i));
+        next_rank[suffixes[0]] = 0;
+        for w in 1..n {
+            let step = (key(suffixes[w]) != key(suffixes[w - 1])) as usize;
+            next_rank[suffixes[w]] = next_rank[suffixes[w - 1]] + step;
+        }
+        std::mem::swap(&mut rank, &mut next_rank);
+        if rank[suffixes[n - 1]] == n - 1 {
+            break;
+        }
+        k *= 2;
+    }
+    suffixes
+}
+
+// Kasai's algorithm for the common prefix length of each suffix with the one before
+fn kasai_lcp(bytes: &[u8], suffixes: &[usize]) -> Vec<usize> {
+    let n = bytes.len();
+    let mut rank = vec![0; n];
+    for (i, &start) in suffixes.iter().enumerate() {
+        rank[start] = i;
+    }
+    let mut lcp = vec![0; n];
+    let mut h: usize = 0;
+    for start in 0..n {
+        if rank[start] == 0 {
+            h = 0;
+            continue;
+        }
+        let other = suffixes[rank[start] - 1];
+        while start + h < n && other + h < n && bytes[start + h] == bytes[other + h] {
+            h += 1;
+        }
+        lcp[rank[start]] = h;
+        h = h.saturating_sub(1);
+    }
+    lcp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suffix_array() {
+        assert_eq!(suffix_array(b"banana"), [5, 3, 1, 0, 4, 2]);
+        assert_eq!(
+            kasai_lcp(b"banana", &[5, 3, 1, 0, 4, 2]),
+            [0, 1, 3, 0, 0, 2]
+        );
+        assert!(suffix_array(b"").is_empty());
+    }
+
+    #[test]
+    fn test_find_all() {
+        let index = SearchIndex::new(Rc::new(String::from("aaaa é-é")));
+        let found = index.find_all("aa");
+        let ranges: Vec<_> = found.iter().map(|s| s.range.clone()).collect();
+        assert_eq!(ranges, [0..2, 1..3, 2..4]);
+        assert_eq!(index.find_all("é").len(), 2);
+        assert!(index.find_all("b").is_empty());
+        assert!(index.find_all("").is_empty());
+        assert_eq!(index.longest_repeated().unwrap(), "aaa");
+    }
+
+    #[test]
+    fn test_longest_repeated_multibyte() {
+        // The repeats share the first byte of 'é' and 'è' which must not be included
+        let index = SearchIndex::new(Rc::new(String::from("xé xè")));
+        assert_eq!(index.longest_repeated().unwrap(), "x");
+        assert!(
+            SearchIndex::new(Rc::new(String::from("abc")))
+                .longest_repeated()
+                .is_none()
+        );
+        assert!(
+            SearchIndex::new(Rc::new(String::new()))
+                .longest_repeated()
+                .is_none()
+        );
+    }
+}
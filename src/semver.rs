@@ -0,0 +1,176 @@
+//! Splitting dotted identifiers and semantic versions into [RcSubstring]s
+use crate::{RcSubstring, SplitOwned};
+
+impl RcSubstring {
+    /// The segments of a dotted identifier such as `serde.derive` or `1.2.3`, split at each `.`
+    ///
+    /// Empty segments are kept, as [RcSubstring::split_owned] keeps them.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let path = RcSubstring::from(String::from("std.collections.HashMap"));
+    /// let segments: Vec<_> = path.dotted_segments().collect();
+    /// assert_eq!(segments, ["std", "collections", "HashMap"]);
+    /// ```
+    pub fn dotted_segments(&self) -> SplitOwned<'static> {
+        self.split_owned(".")
+    }
+
+    /// Split a semantic version such as `1.2.3-beta.1+build.5` into its parts
+    ///
+    /// Returns `None` unless this is a version as the semver spec defines it: three numbers
+    /// without leading zeros, then optionally `-` and the pre-release and `+` and the build,
+    /// each dot separated identifiers of ASCII letters, digits and `-`. Identifiers of only
+    /// digits in the pre-release can't have leading zeros either, though in the build they can.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let version = RcSubstring::from(String::from("1.20.3-rc.1+sha.5114f85"));
+    /// let parts = version.split_semver().unwrap();
+    /// assert_eq!(*parts.major(), "1");
+    /// assert_eq!(*parts.minor(), "20");
+    /// assert_eq!(*parts.patch(), "3");
+    /// assert_eq!(*parts.pre().unwrap(), "rc.1");
+    /// assert_eq!(*parts.build().unwrap(), "sha.5114f85");
+    /// assert!(RcSubstring::from_static("1.02.3").split_semver().is_none());
+    /// ```
+    pub fn split_semver(&self) -> Option<SemverSpans> {
+        let (core_end, build) = match self.find('+') {
+            Some(plus) => (plus, Some(plus + 1..self.len())),
+            None => (self.len(), None),
+        };
+        let (numbers_end, pre) = match self[..core_end].find('-') {
+            Some(dash) => (dash, Some(dash + 1..core_end)),
+            None => (core_end, None),
+        };
+        let mut numbers = self.reslice(0..numbers_end).dotted_segments();
+        let (major, minor, patch) = (numbers.next()?, numbers.next()?, numbers.next()?);
+        if numbers.next().is_some() || ![&major, &minor, &patch].into_iter().all(is_number) {
+            return None;
+        }
+        let pre = pre.map(|range| self.reslice(range));
+        let build = build.map(|range| self.reslice(range));
+        let pre_valid = pre.as_ref().is_none_or(|pre| {
+            is_identifiers(pre) && pre.split('.').all(|identifier| !is_zero_padded(identifier))
+        });
+        if !pre_valid || !build.as_ref().is_none_or(is_identifiers) {
+            return None;
+        }
+        Some(SemverSpans {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+}
+
+// A version number: digits without a leading zero
+fn is_number(text: &RcSubstring) -> bool {
+    !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit()) && !is_zero_padded(text)
+}
+
+// Digits only, with a leading zero that isn't the whole number
+fn is_zero_padded(text: &str) -> bool {
+    text.len() > 1 && text.starts_with('0') && text.bytes().all(|b| b.is_ascii_digit())
+}
+
+// Dot separated identifiers of a pre-release or build
+fn is_identifiers(text: &RcSubstring) -> bool {
+    text.split('.').all(|identifier| {
+        !identifier.is_empty()
+            && identifier
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+    })
+}
+
+/**
+The parts of a semantic version as substrings of its text
+
+Created by [RcSubstring::split_semver]
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SemverSpans {
+    major: RcSubstring,
+    minor: RcSubstring,
+    patch: RcSubstring,
+    pre: Option<RcSubstring>,
+    build: Option<RcSubstring>,
+}
+
+impl SemverSpans {
+    /// The major version
+    pub fn major(&self) -> &RcSubstring {
+        &self.major
+    }
+
+    /// The minor version
+    pub fn minor(&self) -> &RcSubstring {
+        &self.minor
+    }
+
+    /// The patch version
+    pub fn patch(&self) -> &RcSubstring {
+        &self.patch
+    }
+
+    /// The pre-release, without the `-`, if there is one
+    pub fn pre(&self) -> Option<&RcSubstring> {
+        self.pre.as_ref()
+    }
+
+    /// The build metadata, without the `+`, if there is any
+    pub fn build(&self) -> Option<&RcSubstring> {
+        self.build.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dotted_segments() {
+        let text = RcSubstring::from(String::from("a..b."));
+        let segments: Vec<_> = text.dotted_segments().collect();
+        assert_eq!(segments, ["a", "", "b", ""]);
+        assert_eq!(segments[2].range, 3..4);
+    }
+
+    #[test]
+    fn test_split_semver() {
+        let semver = |text: &str| RcSubstring::from(String::from(text)).split_semver();
+        let parts = semver("0.0.10").unwrap();
+        assert_eq!(*parts.patch(), "10");
+        assert_eq!(parts.patch().range, 4..6);
+        assert!(parts.pre().is_none() && parts.build().is_none());
+        let parts = semver("1.0.0+build-1.x").unwrap();
+        assert!(parts.pre().is_none());
+        assert_eq!(*parts.build().unwrap(), "build-1.x");
+        let parts = semver("1.0.0-x-y.7").unwrap();
+        assert_eq!(*parts.pre().unwrap(), "x-y.7");
+        let parts = semver("1.0.0-0.0a.00-x+001").unwrap();
+        assert_eq!(*parts.pre().unwrap(), "0.0a.00-x");
+        assert_eq!(*parts.build().unwrap(), "001");
+        for invalid in [
+            "",
+            "1",
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.x",
+            "1.2.3-",
+            "1.2.3+",
+            "1.2.3-a..b",
+            "1.2.3-é",
+            "1.2.3+a+b",
+            "1.2.3-01",
+            "1.2.3-rc.00",
+            " 1.2.3",
+        ] {
+            assert!(semver(invalid).is_none(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! `serde` support for [RcSubstring]s that keeps the sources they share
+use crate::{RcSubstring, RcSubstringError, SourceId};
+use ::serde::de::Error;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/**
+A list of [RcSubstring]s that is serialized as its sources and the range of each
+
+Each source is written once however many substrings share it, followed by a
+`(source, start, end)` index and range for each substring. Deserializing creates one
+`Rc<String>` per source, so the substrings share their text again just as they did before.
+
+```rust
+# use rcsubstring::{RcSubstring, SharedSpans};
+# use std::rc::Rc;
+let source = Rc::new(String::from("let x = y;"));
+let spans = SharedSpans(vec![
+    RcSubstring::new(Rc::clone(&source), 4..5),
+    RcSubstring::new(Rc::clone(&source), 8..9),
+]);
+let json = serde_json::to_string(&spans).unwrap();
+assert_eq!(json, r#"{"sources":["let x = y;"],"spans":[[0,4,5],[0,8,9]]}"#);
+
+let spans: SharedSpans = serde_json::from_str(&json).unwrap();
+assert_eq!(spans[0], "x");
+assert_eq!(spans[1], "y");
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SharedSpans(pub Vec<RcSubstring>);
+
+// The form SharedSpans is written in
+#[derive(Serialize)]
+struct Table<'a> {
+    sources: Vec<&'a str>,
+    spans: Vec<(usize, usize, usize)>,
+}
+
+// The form SharedSpans is read from
+#[derive(Deserialize)]
+#[serde(rename = "Table")]
+struct OwnedTable {
+    sources: Vec<String>,
+    spans: Vec<(usize, usize, usize)>,
+}
+
+impl Serialize for SharedSpans {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut ids: HashMap<SourceId, usize> = HashMap::new();
+        let mut table = Table {
+            sources: Vec::new(),
+            spans: Vec::with_capacity(self.0.len()),
+        };
+        for span in &self.0 {
+            let id = *ids.entry(span.source_id()).or_insert_with(|| {
+                table.sources.push(span.rcstring.as_str());
+                table.sources.len() - 1
+            });
+            table.spans.push((id, span.range.start, span.range.end));
+        }
+        table.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SharedSpans {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let table = OwnedTable::deserialize(deserializer)?;
+        let sources: Vec<Rc<String>> = table.sources.into_iter().map(Rc::new).collect();
+        let spans = table
+            .spans
+            .into_iter()
+            .map(|(id, start, end)| {
+                let source = sources
+                    .get(id)
+                    .ok_or_else(|| D::Error::custom(format!("no source {id} for span")))?;
+                let range = start..end;
+                RcSubstringError::check(source, &range).map_err(D::Error::custom)?;
+                Ok(RcSubstring::new(Rc::clone(source), range))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(SharedSpans(spans))
+    }
+}
+
+impl Deref for SharedSpans {
+    type Target = Vec<RcSubstring>;
+
+    fn deref(&self) -> &Vec<RcSubstring> {
+        &self.0
+    }
+}
+
+impl DerefMut for SharedSpans {
+    fn deref_mut(&mut self) -> &mut Vec<RcSubstring> {
+        &mut self.0
+    }
+}
+
+impl From<Vec<RcSubstring>> for SharedSpans {
+    fn from(spans: Vec<RcSubstring>) -> Self {
+        SharedSpans(spans)
+    }
+}
+
+impl From<SharedSpans> for Vec<RcSubstring> {
+    fn from(spans: SharedSpans) -> Self {
+        spans.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_shares_sources() {
+        let a = Rc::new(String::from("first source"));
+        let b = Rc::new(String::from("second"));
+        let spans = SharedSpans(vec![
+            RcSubstring::new(Rc::clone(&b), 0..3),
+            RcSubstring::new(Rc::clone(&a), 6..12),
+            RcSubstring::new(Rc::clone(&b), 3..6),
+            RcSubstring::from_static("static"),
+        ]);
+        let json = serde_json::to_string(&spans).unwrap();
+        assert_eq!(
+            json,
+            r#"{"sources":["second","first source","static"],"spans":[[0,0,3],[1,6,12],[0,3,6],[2,0,6]]}"#
+        );
+        let read: SharedSpans = serde_json::from_str(&json).unwrap();
+        assert_eq!(read, spans);
+        assert!(Rc::ptr_eq(
+            read[0].shared().unwrap(),
+            read[2].shared().unwrap()
+        ));
+        assert!(!Rc::ptr_eq(
+            read[0].shared().unwrap(),
+            read[1].shared().unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_invalid_spans() {
+        let missing = r#"{"sources":["text"],"spans":[[1,0,1]]}"#;
+        let error = serde_json::from_str::<SharedSpans>(missing).unwrap_err();
+        assert!(error.to_string().contains("no source 1"));
+        let out_of_bounds = r#"{"sources":["text"],"spans":[[0,2,5]]}"#;
+        assert!(serde_json::from_str::<SharedSpans>(out_of_bounds).is_err());
+        let not_boundary = r#"{"sources":["é"],"spans":[[0,0,1]]}"#;
+        assert!(serde_json::from_str::<SharedSpans>(not_boundary).is_err());
+    }
+}
@@ -0,0 +1,123 @@
+//! A copy-on-write style string that may be borrowed, shared or owned
+use crate::RcSubstring;
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::ops::Deref;
+
+/**
+Text that is either borrowed, a shared [RcSubstring], or an owned `String`
+
+Like [Cow] but with a third, shared, state. Functions can take `impl Into<SharedCow>` to
+accept any of these cheaply and call [SharedCow::into_shared] only for values that need to
+outlive the borrow.
+
+```rust
+# use rcsubstring::{RcSubstring, SharedCow};
+fn keep_if_long<'a>(text: impl Into<SharedCow<'a>>) -> Option<RcSubstring> {
+    let text = text.into();
+    (text.len() > 3).then(|| text.into_shared())
+}
+assert!(keep_if_long("abc").is_none());
+assert_eq!(keep_if_long(String::from("abcd")).unwrap(), "abcd");
+```
+*/
+#[derive(Debug, Clone)]
+pub enum SharedCow<'a> {
+    /// Text borrowed from elsewhere
+    Borrowed(&'a str),
+    /// Text in a shared `Rc<String>`
+    Shared(RcSubstring),
+    /// An owned string
+    Owned(String),
+}
+
+impl SharedCow<'_> {
+    /// The text as a `str`
+    pub fn as_str(&self) -> &str {
+        match self {
+            SharedCow::Borrowed(text) => text,
+            SharedCow::Shared(substring) => substring,
+            SharedCow::Owned(string) => string,
+        }
+    }
+
+    /// Convert into an [RcSubstring]
+    ///
+    /// A shared value is returned as is and an owned `String` is moved into a new `Rc`
+    /// without copying. Only borrowed text needs to be copied.
+    pub fn into_shared(self) -> RcSubstring {
+        match self {
+            SharedCow::Borrowed(text) => RcSubstring::from(String::from(text)),
+            SharedCow::Shared(substring) => substring,
+            SharedCow::Owned(string) => RcSubstring::from(string),
+        }
+    }
+}
+
+impl Deref for SharedCow<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl Display for SharedCow<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl PartialEq<&str> for SharedCow<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<'a> From<&'a str> for SharedCow<'a> {
+    fn from(text: &'a str) -> Self {
+        SharedCow::Borrowed(text)
+    }
+}
+
+impl From<RcSubstring> for SharedCow<'_> {
+    fn from(substring: RcSubstring) -> Self {
+        SharedCow::Shared(substring)
+    }
+}
+
+impl From<String> for SharedCow<'_> {
+    fn from(string: String) -> Self {
+        SharedCow::Owned(string)
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for SharedCow<'a> {
+    fn from(cow: Cow<'a, str>) -> Self {
+        match cow {
+            Cow::Borrowed(text) => SharedCow::Borrowed(text),
+            Cow::Owned(string) => SharedCow::Owned(string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_into_shared() {
+        let source = Rc::new(String::from("shared text"));
+        let shared = SharedCow::from(RcSubstring::new(Rc::clone(&source), 0..6));
+        assert_eq!(shared, "shared");
+        let shared = shared.into_shared();
+        assert!(Rc::ptr_eq(shared.shared().unwrap(), &source));
+
+        let borrowed = SharedCow::from(&source[7..]);
+        assert_eq!(borrowed.into_shared(), "text");
+        let owned = SharedCow::from(Cow::<str>::Owned(String::from("owned")));
+        assert!(matches!(owned, SharedCow::Owned(_)));
+        assert_eq!(owned.to_string(), "owned");
+    }
+}
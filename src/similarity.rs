@@ -0,0 +1,154 @@
+//! String similarity scores for fuzzy matching
+use crate::RcSubstring;
+
+// Strings of up to this many chars are scored without allocating
+const STACK_CHARS: usize = 64;
+
+// Call f with a zeroed buffer of len items, on the stack if it is short enough,
+// leaving room for the extra item of a row of edit distances
+fn with_buffer<T: Copy + Default, R>(len: usize, f: impl FnOnce(&mut [T]) -> R) -> R {
+    if len <= STACK_CHARS + 1 {
+        let mut buffer = [T::default(); STACK_CHARS + 1];
+        f(&mut buffer[..len])
+    } else {
+        f(&mut vec![T::default(); len])
+    }
+}
+
+impl RcSubstring {
+    /// The Levenshtein edit distance to `other`
+    ///
+    /// This is the fewest chars that must be inserted, deleted or replaced to turn one into
+    /// the other. Only allocates if `other` has more than 64 chars.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let word = RcSubstring::from(String::from("kitten"));
+    /// assert_eq!(word.levenshtein("sitting"), 3);
+    /// ```
+    pub fn levenshtein(&self, other: &str) -> usize {
+        let other_len = other.chars().count();
+        with_buffer(other_len + 1, |row: &mut [usize]| {
+            // row[j] is the distance between the chars of self so far and the first j of other
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = j;
+            }
+            for (i, a) in self.chars().enumerate() {
+                let mut diagonal = row[0];
+                row[0] = i + 1;
+                for (j, b) in other.chars().enumerate() {
+                    let replace = diagonal + (a != b) as usize;
+                    diagonal = row[j + 1];
+                    row[j + 1] = replace.min(row[j] + 1).min(diagonal + 1);
+                }
+            }
+            row[other_len]
+        })
+    }
+
+    /// The Jaro-Winkler similarity to `other`, from 0.0 for nothing in common to 1.0 for equal
+    ///
+    /// The Jaro similarity is raised for a common prefix of up to 4 chars, with the
+    /// usual scaling factor of 0.1. Only allocates if either string has more than 64 chars.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let name = RcSubstring::from(String::from("MARTHA"));
+    /// assert!((name.jaro_winkler("MARHTA") - 0.961).abs() < 0.001);
+    /// assert_eq!(name.jaro_winkler("MARTHA"), 1.0);
+    /// ```
+    pub fn jaro_winkler(&self, other: &str) -> f64 {
+        let jaro = jaro(self, other);
+        let prefix = self
+            .chars()
+            .zip(other.chars())
+            .take(4)
+            .take_while(|(a, b)| a == b)
+            .count();
+        jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+    }
+}
+
+// The Jaro similarity of a and b
+fn jaro(a: &str, b: &str) -> f64 {
+    let a_len = a.chars().count();
+    let b_len = b.chars().count();
+    if a_len == 0 || b_len == 0 {
+        return if a_len == b_len { 1.0 } else { 0.0 };
+    }
+    // Chars only match if they are this close together
+    let window = (a_len.max(b_len) / 2).saturating_sub(1);
+    with_buffer(a_len, |a_matched: &mut [bool]| {
+        with_buffer(b_len, |b_matched: &mut [bool]| {
+            let mut matches = 0;
+            for (i, c) in a.chars().enumerate() {
+                let start = i.saturating_sub(window);
+                let candidates = b
+                    .chars()
+                    .enumerate()
+                    .skip(start)
+                    .take(i + window + 1 - start);
+                for (j, d) in candidates {
+                    if !b_matched[j] && c == d {
+                        a_matched[i] = true;
+                        b_matched[j] = true;
+                        matches += 1;
+                        break;
+                    }
+                }
+            }
+            if matches == 0 {
+                return 0.0;
+            }
+            let a_chars = a.chars().zip(a_matched.iter()).filter(|(_, m)| **m);
+            let b_chars = b.chars().zip(b_matched.iter()).filter(|(_, m)| **m);
+            let transpositions = a_chars
+                .zip(b_chars)
+                .filter(|((c, _), (d, _))| c != d)
+                .count();
+            let m = matches as f64;
+            (m / a_len as f64 + m / b_len as f64 + (m - transpositions as f64 / 2.0) / m) / 3.0
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(RcSubstring::from(String::new()).levenshtein(""), 0);
+        assert_eq!(RcSubstring::from(String::from("abc")).levenshtein(""), 3);
+        assert_eq!(RcSubstring::from(String::new()).levenshtein("abc"), 3);
+        assert_eq!(
+            RcSubstring::from(String::from("flaw")).levenshtein("lawn"),
+            2
+        );
+        assert_eq!(
+            RcSubstring::from(String::from("café")).levenshtein("cafe"),
+            1
+        );
+        let long = "ab".repeat(50);
+        assert_eq!(
+            RcSubstring::from(String::from(&long)).levenshtein(&long[1..]),
+            1
+        );
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        let close = |a: &str, b: &str, expected: f64| {
+            let score = RcSubstring::from(String::from(a)).jaro_winkler(b);
+            assert!((score - expected).abs() < 0.001, "{a} {b} {score}");
+        };
+        close("DWAYNE", "DUANE", 0.840);
+        close("DIXON", "DICKSONX", 0.813);
+        close("abc", "xyz", 0.0);
+        close("", "", 1.0);
+        close("a", "", 0.0);
+        close("é", "é", 1.0);
+        let long = "abcdefghij".repeat(10);
+        close(&long, &long, 1.0);
+    }
+}
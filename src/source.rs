@@ -0,0 +1,153 @@
+//! Loading text into a shared `Rc<String>`
+use crate::{LinesOwned, RcSubstring};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::rc::Rc;
+
+const UTF8_BOM: &str = "\u{FEFF}";
+
+/**
+Text read into an `Rc<String>` with any UTF-8 BOM stripped
+
+```rust
+# use rcsubstring::Source;
+let source = Source::from_reader(&b"\xEF\xBB\xBFhello"[..]).unwrap();
+assert!(source.had_bom());
+assert_eq!(*source.text(), "hello");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Source {
+    text: RcSubstring,
+    had_bom: bool,
+}
+
+impl Source {
+    /// Read the whole file at `path`
+    ///
+    /// Errors if the file can't be read or is not valid UTF-8
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Source> {
+        Source::from_reader(File::open(path)?)
+    }
+
+    /// Read everything from `reader`
+    ///
+    /// A UTF-8 BOM at the start is left out of [Source::text]; nothing else is changed.
+    /// The BOM stays in the underlying `Rc<String>` so no copy is needed to remove it.
+    ///
+    /// Errors if the reader fails or the data is not valid UTF-8
+    pub fn from_reader(mut reader: impl Read) -> io::Result<Source> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let had_bom = text.starts_with(UTF8_BOM);
+        let start = if had_bom { UTF8_BOM.len() } else { 0 };
+        let len = text.len();
+        Ok(Source {
+            text: RcSubstring::new(Rc::new(text), start..len),
+            had_bom,
+        })
+    }
+
+    /// The text that was read, without any BOM
+    pub fn text(&self) -> &RcSubstring {
+        &self.text
+    }
+
+    /// Consume the source returning the text that was read, without any BOM
+    pub fn into_text(self) -> RcSubstring {
+        self.text
+    }
+
+    /// True if the text started with a UTF-8 BOM that has been stripped
+    pub fn had_bom(&self) -> bool {
+        self.had_bom
+    }
+}
+
+impl RcSubstring {
+    /// Read everything from `reader` into a new `Rc<String>`, stripping any UTF-8 BOM
+    ///
+    /// Use [Source::from_reader] if you need to know whether there was a BOM.
+    ///
+    /// Errors if the reader fails or the data is not valid UTF-8
+    pub fn from_reader(reader: impl Read) -> io::Result<RcSubstring> {
+        Source::from_reader(reader).map(Source::into_text)
+    }
+}
+
+/// Read the whole file at `path` into one `Rc<String>`, stripping any UTF-8 BOM
+///
+/// The `Rc` version of [std::fs::read_to_string]. Use [Source::load] if you need to know
+/// whether there was a BOM.
+///
+/// Errors if the file can't be read or is not valid UTF-8
+pub fn read_to_rc(path: impl AsRef<Path>) -> io::Result<RcSubstring> {
+    Source::load(path).map(Source::into_text)
+}
+
+/// Read the whole file at `path` and iterate over its lines
+///
+/// Unlike [io::BufRead::lines] the file is read into one `Rc<String>` shared by all the
+/// lines, rather than allocating a `String` for each. Lines are split as for [str::lines],
+/// after stripping any UTF-8 BOM.
+///
+/// Errors if the file can't be read or is not valid UTF-8
+///
+/// ```rust,no_run
+/// # fn main() -> std::io::Result<()> {
+/// for line in rcsubstring::read_lines_rc("input.txt")? {
+///     println!("{line}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_lines_rc(path: impl AsRef<Path>) -> io::Result<LinesOwned> {
+    read_to_rc(path).map(|text| text.lines_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_reader() {
+        let text = RcSubstring::from_reader(&b"no bom"[..]).unwrap();
+        assert_eq!(text, "no bom");
+        let source = Source::from_reader(&b"no bom"[..]).unwrap();
+        assert!(!source.had_bom());
+        let error = RcSubstring::from_reader(&b"\xFF"[..]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_load() {
+        let path =
+            std::env::temp_dir().join(format!("rcsubstring-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "\u{FEFF}line 1\r\nline 2").unwrap();
+        let source = Source::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let source = source.unwrap();
+        assert!(source.had_bom());
+        assert_eq!(*source.text(), "line 1\r\nline 2");
+        assert!(Source::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_read_lines_rc() {
+        let path =
+            std::env::temp_dir().join(format!("rcsubstring-lines-{}.txt", std::process::id()));
+        std::fs::write(&path, "\u{FEFF}one\r\ntwo\n").unwrap();
+        let text = read_to_rc(&path);
+        let lines = read_lines_rc(&path).map(Iterator::collect::<Vec<_>>);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(text.unwrap(), "one\r\ntwo\n");
+        let lines = lines.unwrap();
+        assert_eq!(lines, ["one", "two"]);
+        assert!(Rc::ptr_eq(
+            lines[0].shared().unwrap(),
+            lines[1].shared().unwrap()
+        ));
+        assert!(read_lines_rc(&path).is_err());
+    }
+}
@@ -0,0 +1,213 @@
+//! Creating many [RcSubstring]s of one `Rc<String>` at once
+use crate::{RcSubstring, RcSubstringError, SpansError, SpansErrorKind};
+use std::ops::Range;
+use std::rc::Rc;
+
+/**
+Checks a batch of ranges and creates an [RcSubstring] for each
+
+Every range is checked to be in bounds and on char boundaries, and optionally that the ranges
+are in order or don't overlap. The first failure is reported as a [SpansError] saying which
+range failed and why.
+
+```rust
+# use rcsubstring::Spans;
+# use std::rc::Rc;
+let source = Rc::new(String::from("let x = 1;"));
+let tokens = Spans::from_ranges(&source, [0..3, 4..5, 6..7, 8..9]).unwrap();
+assert_eq!(tokens[1], "x");
+
+let error = Spans::new()
+    .non_overlapping()
+    .build(&source, [0..3, 2..5])
+    .unwrap_err();
+assert_eq!(error.index(), 1);
+```
+*/
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spans {
+    sorted: bool,
+    non_overlapping: bool,
+}
+
+impl Spans {
+    /// Check only that each range is in bounds and on char boundaries
+    pub fn new() -> Self {
+        Spans::default()
+    }
+
+    /// Also require each range to start at or after the start of the one before
+    pub fn sorted(mut self) -> Self {
+        self.sorted = true;
+        self
+    }
+
+    /// Also require each range to start at or after the end of the one before
+    pub fn non_overlapping(mut self) -> Self {
+        self.non_overlapping = true;
+        self
+    }
+
+    /// Create an [RcSubstring] of `rc` for each of `ranges`, checking them as configured
+    #[track_caller]
+    pub fn build(
+        &self,
+        rc: &Rc<String>,
+        ranges: impl IntoIterator<Item = Range<usize>>,
+    ) -> Result<Vec<RcSubstring>, SpansError> {
+        let ranges = ranges.into_iter();
+        let mut spans = Vec::with_capacity(ranges.size_hint().0);
+        let mut previous: Option<Range<usize>> = None;
+        for (index, range) in ranges.enumerate() {
+            let error = |kind| SpansError {
+                index,
+                range: range.clone(),
+                kind,
+            };
+            RcSubstringError::check(rc, &range).map_err(|e| error(SpansErrorKind::Invalid(e)))?;
+            if let Some(previous) = previous {
+                if self.non_overlapping && range.start < previous.end {
+                    return Err(error(SpansErrorKind::Overlapping { previous }));
+                }
+                if self.sorted && range.start < previous.start {
+                    return Err(error(SpansErrorKind::OutOfOrder { previous }));
+                }
+            }
+            previous = Some(range.clone());
+            spans.push(RcSubstring::new(Rc::clone(rc), range));
+        }
+        Ok(spans)
+    }
+
+    /// Create an [RcSubstring] of `rc` for each of `ranges`, checking each is in bounds and on char boundaries
+    #[track_caller]
+    pub fn from_ranges(
+        rc: &Rc<String>,
+        ranges: impl IntoIterator<Item = Range<usize>>,
+    ) -> Result<Vec<RcSubstring>, SpansError> {
+        Spans::new().build(rc, ranges)
+    }
+}
+
+impl RcSubstring {
+    /// Construct a new RcSubstring, returning an error if `range` is invalid
+    ///
+    /// Unlike [RcSubstring::new] this also checks both ends of `range` are on char boundaries,
+    /// in release builds as well as debug.
+    #[track_caller]
+    pub fn try_new(rcstring: Rc<String>, range: Range<usize>) -> Result<Self, RcSubstringError> {
+        RcSubstringError::check(&rcstring, &range)?;
+        Ok(RcSubstring::new(rcstring, range))
+    }
+
+    /// Construct a new RcSubstring, doing what `on_invalid` says if `range` is invalid
+    ///
+    /// The range is checked as by [RcSubstring::try_new]. This lets an application pick one
+    /// policy for offsets it doesn't trust, eg. from an external tool, and use it everywhere.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the error if `range` is invalid and `on_invalid` is [OnInvalid::Panic]
+    ///
+    /// ```rust
+    /// # use rcsubstring::{OnInvalid, RcSubstring};
+    /// # use std::rc::Rc;
+    /// let rc = Rc::new(String::from("café"));
+    /// let clamped = RcSubstring::new_with(Rc::clone(&rc), 2..10, OnInvalid::Clamp).unwrap();
+    /// assert_eq!(clamped, "fé");
+    /// assert!(RcSubstring::new_with(rc, 2..10, OnInvalid::Error).is_err());
+    /// ```
+    #[track_caller]
+    pub fn new_with(
+        rcstring: Rc<String>,
+        range: Range<usize>,
+        on_invalid: OnInvalid,
+    ) -> Result<Self, RcSubstringError> {
+        let Err(error) = RcSubstringError::check(&rcstring, &range) else {
+            return Ok(RcSubstring::new(rcstring, range));
+        };
+        match on_invalid {
+            OnInvalid::Panic => panic!("{}", error),
+            OnInvalid::Error => Err(error),
+            OnInvalid::Clamp => {
+                let end = rcstring.ceil_char_boundary(range.end.min(rcstring.len()));
+                let start = rcstring.floor_char_boundary(range.start.min(end));
+                Ok(RcSubstring::new(rcstring, start..end))
+            }
+        }
+    }
+}
+
+/// What [RcSubstring::new_with] does with an invalid range
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnInvalid {
+    /// Panic, as [RcSubstring::new] does in debug builds
+    #[default]
+    Panic,
+    /// Make the range valid: ends past the text are moved to its end, ends inside a char are
+    /// moved out to take in the whole char, and a start after the end is moved to the end
+    Clamp,
+    /// Return the error, as [RcSubstring::try_new] does
+    Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RcSubstringErrorKind;
+
+    #[test]
+    fn test_try_new() {
+        let rc = Rc::new(String::from("née"));
+        assert_eq!(RcSubstring::try_new(Rc::clone(&rc), 1..3).unwrap(), "é");
+        assert_eq!(
+            RcSubstring::try_new(rc, 2..4).unwrap_err().kind(),
+            &RcSubstringErrorKind::NotCharBoundary { index: 2 }
+        );
+    }
+
+    #[test]
+    fn test_new_with() {
+        let rc = Rc::new(String::from("añb"));
+        let clamp = |range| RcSubstring::new_with(Rc::clone(&rc), range, OnInvalid::Clamp);
+        assert_eq!(clamp(2..3).unwrap(), "ñ");
+        assert_eq!(clamp(0..100).unwrap(), "añb");
+        let after = clamp(100..101).unwrap();
+        assert_eq!(after.range, 4..4);
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = clamp(3..1).unwrap();
+        assert_eq!(reversed.range, 1..1);
+        let error = RcSubstring::new_with(Rc::clone(&rc), 2..3, OnInvalid::Error).unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &RcSubstringErrorKind::NotCharBoundary { index: 2 }
+        );
+        let valid = RcSubstring::new_with(Rc::clone(&rc), 1..3, OnInvalid::default()).unwrap();
+        assert_eq!(valid, "ñ");
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_new_with_panic() {
+        let rc = Rc::new(String::from("abc"));
+        let _ = RcSubstring::new_with(rc, 0..4, OnInvalid::Panic);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let rc = Rc::new(String::from("abcdef"));
+        assert_eq!(Spans::from_ranges(&rc, [2..4, 0..6]).unwrap().len(), 2);
+        let error = Spans::new().sorted().build(&rc, [2..4, 0..6]).unwrap_err();
+        assert_eq!(error.kind(), &SpansErrorKind::OutOfOrder { previous: 2..4 });
+        assert_eq!(
+            error.to_string(),
+            "range 1 (0..6) starts before the previous range 2..4"
+        );
+        let error = Spans::new()
+            .non_overlapping()
+            .build(&rc, [0..2, 2..3, 3..9])
+            .unwrap_err();
+        assert_eq!(error.range(), 3..9);
+        assert!(std::error::Error::source(&error).is_some());
+    }
+}
@@ -0,0 +1,573 @@
+//! Splitting an [RcSubstring] into owned pieces and lines
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+impl RcSubstring {
+    /// Split this substring on `separator`, as [str::split] does
+    ///
+    /// From the back the pieces are found as [str::rsplit] finds them,
+    /// which only differs when occurrences of `separator` can overlap.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let text = RcSubstring::from(String::from("a, b, c"));
+    /// let parts: Vec<_> = text.split_owned(", ").collect();
+    /// assert_eq!(parts, ["a", "b", "c"]);
+    /// assert_eq!(text.split_owned(", ").next_back().unwrap(), "c");
+    /// ```
+    pub fn split_owned<'a>(&self, separator: &'a str) -> SplitOwned<'a> {
+        SplitOwned {
+            haystack: self.clone(),
+            separator,
+            start: 0,
+            end: self.len(),
+            skip_front: false,
+            skip_back: false,
+            finished: false,
+        }
+    }
+
+    /// Split this substring into lines, as [str::lines] does
+    ///
+    /// Lines end with `\n` or `\r\n`, which are not included, and a final empty line is ignored.
+    pub fn lines_owned(&self) -> LinesOwned {
+        LinesOwned {
+            text: self.clone(),
+            start: 0,
+            end: self.len(),
+        }
+    }
+
+    /// Split this substring on `separator`, with the byte offset of each piece in the source
+    ///
+    /// The offsets are from the start of the whole `Rc<String>`, not this substring.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let source = Rc::new(String::from("key: a,b"));
+    /// let value = RcSubstring::new(Rc::clone(&source), 5..8);
+    /// let parts: Vec<_> = value.split_with_offsets(",").collect();
+    /// assert_eq!(parts[1].0, 7);
+    /// assert_eq!(parts[1].1, "b");
+    /// ```
+    pub fn split_with_offsets<'a>(&self, separator: &'a str) -> WithOffsets<SplitOwned<'a>> {
+        WithOffsets {
+            inner: self.split_owned(separator),
+        }
+    }
+
+    /// The lines of this substring, with the byte offset of each in the source
+    ///
+    /// The offsets are from the start of the whole `Rc<String>`, not this substring.
+    pub fn lines_with_offsets(&self) -> WithOffsets<LinesOwned> {
+        WithOffsets {
+            inner: self.lines_owned(),
+        }
+    }
+
+    /// Split this substring into fields as awk does by default
+    ///
+    /// Fields are separated by runs of whitespace, and whitespace at the start and end is ignored.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let record = RcSubstring::from(String::from("  GET /index.html\t200 "));
+    /// let fields: Vec<_> = record.fields().collect();
+    /// assert_eq!(fields, ["GET", "/index.html", "200"]);
+    /// ```
+    pub fn fields(&self) -> Fields<'static> {
+        Fields(FieldsInner::Whitespace {
+            text: self.clone(),
+            start: 0,
+            end: self.len(),
+        })
+    }
+
+    /// Split this substring into fields separated by `separator`, as awk does with `-F`
+    ///
+    /// Unlike [RcSubstring::fields] empty fields are kept, except that an empty substring
+    /// has no fields at all. As in awk a separator of a single space means the default
+    /// whitespace splitting. Longer separators are matched literally, not as regexes.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let record = RcSubstring::from(String::from("root:x:0:0::/root"));
+    /// let fields: Vec<_> = record.fields_sep(":").collect();
+    /// assert_eq!(fields[4], "");
+    /// assert_eq!(fields[5], "/root");
+    /// ```
+    pub fn fields_sep<'a>(&self, separator: &'a str) -> Fields<'a> {
+        if separator == " " {
+            return self.fields();
+        }
+        let mut split = self.split_owned(separator);
+        split.finished = self.is_empty();
+        Fields(FieldsInner::Separator(split))
+    }
+
+    /// The lines of this substring, with the 1-based number of each line in the source
+    ///
+    /// Lines are numbered from the start of the whole `Rc<String>`, so if this substring starts
+    /// part way through the source its first line has the number of the line it starts on.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// # use std::rc::Rc;
+    /// let source = Rc::new(String::from("one\ntwo\nthree\n"));
+    /// let tail = RcSubstring::new(Rc::clone(&source), 4..source.len());
+    /// let lines: Vec<_> = tail.lines_numbered().collect();
+    /// assert_eq!(lines[0].0, 2);
+    /// assert_eq!(lines[1].0, 3);
+    /// assert_eq!(lines[1].1, "three");
+    /// ```
+    pub fn lines_numbered(&self) -> LinesNumbered {
+        let before = &self.rcstring.as_str()[..self.range.start];
+        LinesNumbered {
+            lines: self.lines_owned(),
+            front: 1 + before.bytes().filter(|&b| b == b'\n').count(),
+            back: None,
+        }
+    }
+}
+
+/**
+Iterator over the parts of an [RcSubstring] between occurrences of a separator
+
+Created by [RcSubstring::split_owned]
+*/
+#[derive(Debug, Clone)]
+pub struct SplitOwned<'a> {
+    haystack: RcSubstring,
+    separator: &'a str,
+    start: usize,
+    end: usize,
+    // An empty separator matches everywhere, so these stop it matching
+    // again where it last matched from that end
+    skip_front: bool,
+    skip_back: bool,
+    finished: bool,
+}
+
+impl SplitOwned<'_> {
+    // The part of the unsplit text that a separator may still be found in
+    fn window(&self) -> Option<Range<usize>> {
+        let rest = &self.haystack[self.start..self.end];
+        let mut window = self.start..self.end;
+        if self.skip_front {
+            window.start += rest.chars().next()?.len_utf8();
+        }
+        if self.skip_back {
+            window.end -= rest.chars().next_back()?.len_utf8();
+        }
+        (window.start <= window.end).then_some(window)
+    }
+
+    // The last piece, once no more separators are found
+    fn finish(&mut self) -> RcSubstring {
+        self.finished = true;
+        self.haystack.reslice(self.start..self.end)
+    }
+}
+
+impl Iterator for SplitOwned<'_> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let found = self.window().and_then(|window| {
+            let i = self.haystack[window.clone()].find(self.separator)?;
+            Some(window.start + i)
+        });
+        match found {
+            Some(i) => {
+                let piece = self.haystack.reslice(self.start..i);
+                self.start = i + self.separator.len();
+                self.skip_front = self.separator.is_empty();
+                Some(piece)
+            }
+            None => Some(self.finish()),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.finished {
+            return (0, Some(0));
+        }
+        // An empty separator splits before the first char and after the last
+        let most = self.end - self.start + 1 + self.separator.is_empty() as usize;
+        (1, Some(most))
+    }
+}
+
+impl DoubleEndedIterator for SplitOwned<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let found = self.window().and_then(|window| {
+            let i = self.haystack[window.clone()].rfind(self.separator)?;
+            Some(window.start + i)
+        });
+        match found {
+            Some(i) => {
+                let piece = self.haystack.reslice(i + self.separator.len()..self.end);
+                self.end = i;
+                self.skip_back = self.separator.is_empty();
+                Some(piece)
+            }
+            None => Some(self.finish()),
+        }
+    }
+}
+
+impl FusedIterator for SplitOwned<'_> {}
+
+/**
+Iterator over the lines of an [RcSubstring]
+
+Created by [RcSubstring::lines_owned]
+*/
+#[derive(Debug, Clone)]
+pub struct LinesOwned {
+    text: RcSubstring,
+    start: usize,
+    end: usize,
+}
+
+impl LinesOwned {
+    // The line in range, without the \r of a \r\n if it ended with one
+    fn line(&self, range: Range<usize>, terminated: bool) -> RcSubstring {
+        let mut end = range.end;
+        if terminated && self.text[range.clone()].ends_with('\r') {
+            end -= 1;
+        }
+        self.text.reslice(range.start..end)
+    }
+}
+
+impl Iterator for LinesOwned {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        let start = self.start;
+        match self.text[start..self.end].find('\n') {
+            Some(i) => {
+                self.start += i + 1;
+                Some(self.line(start..start + i, true))
+            }
+            None => {
+                self.start = self.end;
+                Some(self.line(start..self.end, false))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.start;
+        (remaining.min(1), Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for LinesOwned {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        let rest = &self.text[self.start..self.end];
+        let terminated = rest.ends_with('\n');
+        let end = self.end - terminated as usize;
+        let start = match self.text[self.start..end].rfind('\n') {
+            Some(i) => self.start + i + 1,
+            None => self.start,
+        };
+        self.end = start;
+        Some(self.line(start..end, terminated))
+    }
+}
+
+impl FusedIterator for LinesOwned {}
+
+/**
+Iterator over the awk-style fields of an [RcSubstring]
+
+Created by [RcSubstring::fields] or [RcSubstring::fields_sep]
+*/
+#[derive(Debug, Clone)]
+pub struct Fields<'a>(FieldsInner<'a>);
+
+#[derive(Debug, Clone)]
+enum FieldsInner<'a> {
+    Whitespace {
+        text: RcSubstring,
+        start: usize,
+        end: usize,
+    },
+    Separator(SplitOwned<'a>),
+}
+
+impl Iterator for Fields<'_> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            FieldsInner::Whitespace { text, start, end } => {
+                let rest = &text[*start..*end];
+                let trimmed = rest.trim_start();
+                *start += rest.len() - trimmed.len();
+                if trimmed.is_empty() {
+                    return None;
+                }
+                let len = trimmed.find(char::is_whitespace).unwrap_or(trimmed.len());
+                let field = text.reslice(*start..*start + len);
+                *start += len;
+                Some(field)
+            }
+            FieldsInner::Separator(split) => split.next(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match &self.0 {
+            // Each field but the last needs at least one byte and a separator
+            FieldsInner::Whitespace { start, end, .. } => (0, Some((end - start).div_ceil(2))),
+            FieldsInner::Separator(split) => (0, split.size_hint().1),
+        }
+    }
+}
+
+impl DoubleEndedIterator for Fields<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            FieldsInner::Whitespace { text, start, end } => {
+                let rest = &text[*start..*end];
+                let trimmed = rest.trim_end();
+                *end -= rest.len() - trimmed.len();
+                if trimmed.is_empty() {
+                    return None;
+                }
+                let field_start = trimmed.rfind(char::is_whitespace).map_or(0, |i| {
+                    i + trimmed[i..].chars().next().map_or(0, char::len_utf8)
+                });
+                let field = text.reslice(*start + field_start..*end);
+                *end = *start + field_start;
+                Some(field)
+            }
+            FieldsInner::Separator(split) => split.next_back(),
+        }
+    }
+}
+
+impl FusedIterator for Fields<'_> {}
+
+/**
+Iterator over the lines of an [RcSubstring] and their line numbers in the source
+
+Created by [RcSubstring::lines_numbered]
+*/
+#[derive(Debug, Clone)]
+pub struct LinesNumbered {
+    lines: LinesOwned,
+    front: usize,
+    // Number of the next line from the back, once counted
+    back: Option<usize>,
+}
+
+impl Iterator for LinesNumbered {
+    type Item = (usize, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        self.front += 1;
+        Some((self.front - 1, line))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for LinesNumbered {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next_back()?;
+        let number = match self.back {
+            Some(number) => number,
+            None => {
+                // Count the lines before this one that are still to come from the front
+                let before = &self.lines.text[self.lines.start..self.lines.end];
+                self.front + before.bytes().filter(|&b| b == b'\n').count()
+            }
+        };
+        self.back = Some(number - 1);
+        Some((number, line))
+    }
+}
+
+impl FusedIterator for LinesNumbered {}
+
+/**
+Iterator adding the byte offset in the source to each [RcSubstring] of another iterator
+
+Created by [RcSubstring::split_with_offsets] or [RcSubstring::lines_with_offsets]
+*/
+#[derive(Debug, Clone)]
+pub struct WithOffsets<I> {
+    inner: I,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for WithOffsets<I> {
+    type Item = (usize, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let piece = self.inner.next()?;
+        Some((piece.range.start, piece))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator<Item = RcSubstring>> DoubleEndedIterator for WithOffsets<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let piece = self.inner.next_back()?;
+        Some((piece.range.start, piece))
+    }
+}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for WithOffsets<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_split_matches_str() {
+        for (text, separator) in [
+            ("a,b,,c,", ","),
+            ("", ","),
+            ("abc", ""),
+            ("", ""),
+            ("aaaa", "aa"),
+            ("aaa", "aa"),
+            ("éaé", "a"),
+            ("no match", "x"),
+        ] {
+            let substring = RcSubstring::from(String::from(text));
+            let parts: Vec<_> = substring.split_owned(separator).collect();
+            assert_eq!(parts, text.split(separator).collect::<Vec<_>>());
+            let parts: Vec<_> = substring.split_owned(separator).rev().collect();
+            assert_eq!(parts, text.rsplit(separator).collect::<Vec<_>>());
+            let (low, high) = substring.split_owned(separator).size_hint();
+            assert!(low <= parts.len() && parts.len() <= high.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_split_both_ends() {
+        let text = RcSubstring::from(String::from("ab"));
+        let mut split = text.split_owned("");
+        assert_eq!(split.next().unwrap(), "");
+        assert_eq!(split.next_back().unwrap(), "");
+        assert_eq!(split.next().unwrap(), "a");
+        assert_eq!(split.next_back().unwrap(), "b");
+        assert!(split.next().is_none());
+        assert!(split.next_back().is_none());
+
+        let text = RcSubstring::from(String::from("1::2::3"));
+        let mut split = text.split_owned("::");
+        assert_eq!(split.next_back().unwrap(), "3");
+        assert_eq!(split.next().unwrap(), "1");
+        assert_eq!(split.next().unwrap(), "2");
+        assert!(split.next_back().is_none());
+    }
+
+    #[test]
+    fn test_lines_match_str() {
+        for text in [
+            "",
+            "\n",
+            "a\nb",
+            "a\r\nb\r\n",
+            "\n\nc\n",
+            "bare\r",
+            "x\r\r\n",
+        ] {
+            let substring = RcSubstring::from(String::from(text));
+            let lines: Vec<_> = substring.lines_owned().collect();
+            assert_eq!(lines, text.lines().collect::<Vec<_>>());
+            let lines: Vec<_> = substring.lines_owned().rev().collect();
+            assert_eq!(lines, text.lines().rev().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_lines_numbered() {
+        let source = Rc::new(String::from("a\nb\r\n\nc\nd"));
+        let middle = RcSubstring::new(Rc::clone(&source), 3..source.len());
+        let lines: Vec<_> = middle.lines_numbered().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!((lines[0].0, lines[0].1.as_str()), (2, ""));
+        assert_eq!((lines[3].0, lines[3].1.as_str()), (5, "d"));
+        let mut rev: Vec<_> = middle.lines_numbered().rev().collect();
+        rev.reverse();
+        assert_eq!(rev, lines);
+
+        let mut numbered = RcSubstring::from(Rc::clone(&source)).lines_numbered();
+        assert_eq!(numbered.next_back().unwrap().0, 5);
+        assert_eq!(numbered.next().unwrap().0, 1);
+        assert_eq!(numbered.next_back().unwrap().0, 4);
+        assert_eq!(numbered.next().unwrap().0, 2);
+        assert_eq!(numbered.next().unwrap().0, 3);
+        assert!(numbered.next_back().is_none());
+    }
+
+    #[test]
+    fn test_fields() {
+        for text in ["", "   ", " a  b\t\u{3000}c\n", "one", "é é"] {
+            let substring = RcSubstring::from(String::from(text));
+            let fields: Vec<_> = substring.fields().collect();
+            assert_eq!(fields, text.split_whitespace().collect::<Vec<_>>());
+            let fields: Vec<_> = substring.fields().rev().collect();
+            assert_eq!(fields, text.split_whitespace().rev().collect::<Vec<_>>());
+        }
+        let record = RcSubstring::from(String::from("a b c d"));
+        let mut fields = record.fields();
+        assert_eq!(fields.next_back().unwrap(), "d");
+        assert_eq!(fields.next().unwrap(), "a");
+        assert_eq!(fields.next_back().unwrap(), "c");
+        assert_eq!(fields.next().unwrap(), "b");
+        assert!(fields.next().is_none());
+        assert!(fields.next_back().is_none());
+    }
+
+    #[test]
+    fn test_fields_sep() {
+        let empty = RcSubstring::from(String::new());
+        assert_eq!(empty.fields_sep(",").count(), 0);
+        let record = RcSubstring::from(String::from(" a,,b, "));
+        let fields: Vec<_> = record.fields_sep(",").collect();
+        assert_eq!(fields, [" a", "", "b", " "]);
+        let fields: Vec<_> = record.fields_sep(" ").collect();
+        assert_eq!(fields, ["a,,b,"]);
+    }
+
+    #[test]
+    fn test_offsets_are_absolute() {
+        let source = Rc::new(String::from("head\nline one\nline two\n"));
+        let body = RcSubstring::new(Rc::clone(&source), 5..source.len());
+        let lines: Vec<_> = body.lines_with_offsets().collect();
+        assert_eq!(lines[0].0, 5);
+        assert_eq!(lines[1].0, 14);
+        assert_eq!(lines[1].1, "line two");
+        let (offset, word) = body.split_with_offsets(" ").next_back().unwrap();
+        assert_eq!(&source[offset..offset + word.len()], "two\n");
+    }
+}
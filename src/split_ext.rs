@@ -0,0 +1,406 @@
+//! [RcSplitExt], adaptors for iterators of [RcSubstring]s such as tokenizer output
+use crate::concat::join;
+use crate::{ConcatAdjacent, RcSubstring, compact_all};
+use std::iter::FusedIterator;
+use std::ops::Deref;
+use std::rc::Rc;
+
+/**
+Adaptors for any iterator of [RcSubstring]s, so the usual clean up of split or tokenizer
+output can be chained without collecting in between
+
+```rust
+# use rcsubstring::{RcSplitExt, RcSubstring};
+let csv = RcSubstring::from(String::from(" a , , b ,c"));
+let fields = csv.split_owned(",").trim_each().filter_nonempty();
+assert_eq!(fields.join_rc("|"), "a|b|c");
+```
+*/
+pub trait RcSplitExt: Iterator<Item = RcSubstring> + Sized {
+    /// Merge runs of substrings that each directly follow the one before, as
+    /// [RcSubstring::concat_adjacent_iter] does
+    fn merge_adjacent(self) -> ConcatAdjacent<Self> {
+        RcSubstring::concat_adjacent_iter(self)
+    }
+
+    /// Skip empty substrings
+    fn filter_nonempty(self) -> FilterNonempty<Self> {
+        FilterNonempty { iter: self }
+    }
+
+    /// Trim whitespace from both ends of each substring, sharing the text
+    fn trim_each(self) -> TrimEach<Self> {
+        TrimEach { iter: self }
+    }
+
+    /// Join the substrings with `separator` between each
+    ///
+    /// If each follows the one before with just `separator` between them in the same
+    /// text, the result covers them without copying. Otherwise the text is copied once into
+    /// a new `Rc<String>`. Joining nothing gives an empty substring.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSplitExt, RcSubstring};
+    /// # use std::rc::Rc;
+    /// let source = Rc::new(String::from("a, b, c"));
+    /// let joined = RcSubstring::from(Rc::clone(&source)).split_owned(", ").join_rc(", ");
+    /// assert_eq!(joined, "a, b, c");
+    /// assert_eq!(joined.len(), source.len());
+    /// ```
+    fn join_rc(self, separator: &str) -> RcSubstring {
+        join(self, separator)
+    }
+
+    /// Each substring paired with the one after it, for bigrams
+    ///
+    /// Each substring is cloned once, to be the first of a pair after being the second.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSplitExt, RcSubstring};
+    /// let text = RcSubstring::from(String::from("to be or"));
+    /// let bigrams: Vec<_> = text.split_owned(" ").pairs().collect();
+    /// assert_eq!(bigrams[0].0, "to");
+    /// assert_eq!(bigrams[1].1, "or");
+    /// assert_eq!(bigrams.len(), 2);
+    /// ```
+    fn pairs(self) -> Pairs<Self> {
+        Pairs {
+            iter: self,
+            previous: None,
+        }
+    }
+
+    /// Each run of `n` consecutive substrings, overlapping as [slice::windows] does
+    ///
+    /// Each substring is kept once in a buffer that the windows are slices of, so a step
+    /// only clones the substring it adds. If a window is kept after the next is taken, the
+    /// buffer is started again with the last `n - 1` substrings. There are no windows if
+    /// there are fewer than `n` substrings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSplitExt, RcSubstring};
+    /// let text = RcSubstring::from(String::from("a b c d"));
+    /// let windows: Vec<_> = text.split_owned(" ").windows_spans(3).collect();
+    /// assert_eq!(windows, [["a", "b", "c"], ["b", "c", "d"]]);
+    /// ```
+    #[track_caller]
+    fn windows_spans(self, n: usize) -> WindowsSpans<Self> {
+        assert!(n > 0, "window size must be non-zero");
+        WindowsSpans {
+            iter: self,
+            buffer: Rc::new(Vec::with_capacity(2 * n)),
+            n,
+        }
+    }
+
+    /// Collect the substrings compacted into one new `Rc<String>`, as by [compact_all]
+    ///
+    /// The sources they were substrings of are not kept alive by the result.
+    fn collect_compacted(self) -> Vec<RcSubstring> {
+        let mut spans: Vec<RcSubstring> = self.collect();
+        compact_all(&mut spans);
+        spans
+    }
+}
+
+impl<I: Iterator<Item = RcSubstring>> RcSplitExt for I {}
+
+/**
+Iterator over the non-empty substrings of another iterator
+
+Created by [RcSplitExt::filter_nonempty]
+*/
+#[derive(Debug, Clone)]
+pub struct FilterNonempty<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for FilterNonempty<I> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        self.iter.find(|substring| !substring.is_empty())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<I: DoubleEndedIterator<Item = RcSubstring>> DoubleEndedIterator for FilterNonempty<I> {
+    fn next_back(&mut self) -> Option<RcSubstring> {
+        self.iter.rfind(|substring| !substring.is_empty())
+    }
+}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for FilterNonempty<I> {}
+
+/**
+Iterator over the substrings of another iterator with whitespace trimmed from each
+
+Created by [RcSplitExt::trim_each]
+*/
+#[derive(Debug, Clone)]
+pub struct TrimEach<I> {
+    iter: I,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for TrimEach<I> {
+    type Item = RcSubstring;
+
+    fn next(&mut self) -> Option<RcSubstring> {
+        let substring = self.iter.next()?;
+        Some(substring.trim_matches_owned(char::is_whitespace))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: DoubleEndedIterator<Item = RcSubstring>> DoubleEndedIterator for TrimEach<I> {
+    fn next_back(&mut self) -> Option<RcSubstring> {
+        let substring = self.iter.next_back()?;
+        Some(substring.trim_matches_owned(char::is_whitespace))
+    }
+}
+
+impl<I: ExactSizeIterator<Item = RcSubstring>> ExactSizeIterator for TrimEach<I> {}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for TrimEach<I> {}
+
+/**
+Iterator over each substring of another iterator paired with the next
+
+Created by [RcSplitExt::pairs]
+*/
+#[derive(Debug, Clone)]
+pub struct Pairs<I> {
+    iter: I,
+    previous: Option<RcSubstring>,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for Pairs<I> {
+    type Item = (RcSubstring, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = match self.previous.take() {
+            Some(previous) => previous,
+            None => self.iter.next()?,
+        };
+        let second = self.iter.next()?;
+        self.previous = Some(second.clone());
+        Some((first, second))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (least, most) = self.iter.size_hint();
+        // Without a previous substring the first from iter doesn't start a pair
+        let unpaired = self.previous.is_none() as usize;
+        (
+            least.saturating_sub(unpaired),
+            most.map(|most| most.saturating_sub(unpaired)),
+        )
+    }
+}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for Pairs<I> {}
+
+/**
+Iterator over the overlapping windows of consecutive substrings of another iterator
+
+Created by [RcSplitExt::windows_spans]
+*/
+#[derive(Debug, Clone)]
+pub struct WindowsSpans<I> {
+    iter: I,
+    // The last window is at the end, after up to n substrings not yet dropped
+    buffer: Rc<Vec<RcSubstring>>,
+    n: usize,
+}
+
+impl<I: Iterator<Item = RcSubstring>> Iterator for WindowsSpans<I> {
+    type Item = SpanWindow;
+
+    fn next(&mut self) -> Option<SpanWindow> {
+        let n = self.n;
+        if Rc::get_mut(&mut self.buffer).is_none() {
+            // The last window is still in use, so it can't be added to
+            let kept = &self.buffer[self.buffer.len().saturating_sub(n - 1)..];
+            let mut buffer = Vec::with_capacity(2 * n);
+            buffer.extend_from_slice(kept);
+            self.buffer = Rc::new(buffer);
+        }
+        let buffer = Rc::get_mut(&mut self.buffer).expect("buffer was just made unique");
+        if buffer.len() == 2 * n {
+            buffer.drain(..n + 1);
+        }
+        let needed = if buffer.len() < n {
+            n - buffer.len()
+        } else {
+            1
+        };
+        for _ in 0..needed {
+            buffer.push(self.iter.next()?);
+        }
+        Some(SpanWindow {
+            start: buffer.len() - n,
+            buffer: Rc::clone(&self.buffer),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (least, most) = self.iter.size_hint();
+        let missing = self.n - 1 - self.buffer.len().min(self.n - 1);
+        (
+            least.saturating_sub(missing),
+            most.map(|most| most.saturating_sub(missing)),
+        )
+    }
+}
+
+impl<I: FusedIterator<Item = RcSubstring>> FusedIterator for WindowsSpans<I> {}
+
+/**
+A window of consecutive substrings, which dereferences to a slice of them
+
+Created by [WindowsSpans]
+*/
+#[derive(Clone)]
+pub struct SpanWindow {
+    buffer: Rc<Vec<RcSubstring>>,
+    start: usize,
+}
+
+impl Deref for SpanWindow {
+    type Target = [RcSubstring];
+
+    fn deref(&self) -> &[RcSubstring] {
+        &self.buffer[self.start..]
+    }
+}
+
+impl std::fmt::Debug for SpanWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> PartialEq<[T; N]> for SpanWindow
+where
+    RcSubstring: PartialEq<T>,
+{
+    fn eq(&self, other: &[T; N]) -> bool {
+        **self == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SourceId;
+    use std::rc::Rc;
+
+    fn words(text: &str) -> Vec<RcSubstring> {
+        RcSubstring::from(String::from(text))
+            .split_owned(" ")
+            .collect()
+    }
+
+    #[test]
+    fn test_adaptors() {
+        let trimmed: Vec<_> = [" a\t", "", "b"]
+            .map(|text| RcSubstring::from(String::from(text)))
+            .into_iter()
+            .trim_each()
+            .filter_nonempty()
+            .rev()
+            .collect();
+        assert_eq!(trimmed, ["b", "a"]);
+        let text = RcSubstring::from(String::from("ab cd"));
+        let merged: Vec<_> = [0..1, 1..2, 3..5]
+            .into_iter()
+            .map(|range| text.subslice(range))
+            .merge_adjacent()
+            .collect();
+        assert_eq!(merged, ["ab", "cd"]);
+    }
+
+    #[test]
+    fn test_join_rc() {
+        let source = Rc::new(String::from("x, y, z"));
+        let parts: Vec<_> = RcSubstring::from(Rc::clone(&source))
+            .split_owned(", ")
+            .collect();
+        let shared = parts.clone().into_iter().join_rc(", ");
+        assert!(Rc::ptr_eq(shared.shared().unwrap(), &source));
+        assert_eq!(shared.range, 0..7);
+        let skipped = [parts[0].clone(), parts[2].clone()]
+            .into_iter()
+            .join_rc(", ");
+        assert_eq!(skipped, "x, z");
+        assert_ne!(skipped.source_id(), SourceId::of_rc(&source));
+        assert_eq!(parts.clone().into_iter().join_rc("-"), "x-y-z");
+        assert_eq!(words("one").into_iter().join_rc(", "), "one");
+        assert_eq!(std::iter::empty().join_rc(", "), "");
+    }
+
+    #[test]
+    fn test_pairs() {
+        let mut pairs = words("a b c").into_iter().pairs();
+        assert_eq!(pairs.size_hint(), (2, Some(2)));
+        let (a, b) = pairs.next().unwrap();
+        assert_eq!((a.range, b.range.clone()), (0..1, 2..3));
+        assert_eq!(pairs.size_hint(), (1, Some(1)));
+        let (b_again, c) = pairs.next().unwrap();
+        assert_eq!((b_again.range, c.range), (b.range, 4..5));
+        assert_eq!(pairs.next(), None);
+        assert_eq!(words("alone").into_iter().pairs().count(), 0);
+    }
+
+    #[test]
+    fn test_windows_spans() {
+        let mut windows = words("a b c d").into_iter().windows_spans(2);
+        assert_eq!(windows.size_hint(), (3, Some(3)));
+        assert_eq!(windows.next().unwrap(), ["a", "b"]);
+        assert_eq!(windows.size_hint(), (2, Some(2)));
+        assert_eq!(windows.collect::<Vec<_>>(), [["b", "c"], ["c", "d"]]);
+        let ones: Vec<_> = words("x y").into_iter().windows_spans(1).collect();
+        assert_eq!(ones, [["x"], ["y"]]);
+        assert_eq!(words("a b").into_iter().windows_spans(3).count(), 0);
+    }
+
+    #[test]
+    fn test_windows_share_buffer() {
+        let mut windows = words("a b c d e f").into_iter().windows_spans(2);
+        let first = windows.next().unwrap().as_ptr();
+        // Dropped windows leave the buffer to be added to, rather than copied
+        let second = windows.next().unwrap();
+        assert_eq!(second.as_ptr(), first.wrapping_add(1));
+        let kept = [second, windows.next().unwrap()];
+        assert_eq!(kept, [["b", "c"], ["c", "d"]]);
+        let rest: Vec<_> = windows.map(|window| window.to_vec()).collect();
+        assert_eq!(rest, [["d", "e"], ["e", "f"]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "window size must be non-zero")]
+    fn test_empty_windows() {
+        words("a").into_iter().windows_spans(0);
+    }
+
+    #[test]
+    fn test_collect_compacted() {
+        let source = Rc::new("padding ".repeat(100) + "kept");
+        let kept = RcSubstring::from(Rc::clone(&source))
+            .split_owned(" ")
+            .filter(|word| word.starts_with('k'))
+            .collect_compacted();
+        assert_eq!(kept, ["kept"]);
+        assert_eq!(Rc::strong_count(&source), 1);
+    }
+}
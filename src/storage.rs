@@ -0,0 +1,222 @@
+//! The kinds of shared text a [Substring] can be of
+use crate::{Backing, RcSubstring, RcSubstringError, Substring};
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub(crate) mod sealed {
+    pub(crate) trait Sealed {
+        // How a substring holds the text
+        type Repr: Clone + Text;
+        // The name substrings of this storage are shown with in Debug output
+        const TYPE_NAME: &'static str;
+
+        fn into_repr(self) -> Self::Repr;
+    }
+
+    pub(crate) trait Text {
+        fn as_str(&self) -> &str;
+
+        // True if both are the same text, not just equal text
+        fn same_text(&self, other: &Self) -> bool;
+    }
+}
+
+use sealed::{Sealed, Text};
+
+/**
+Shared text that a [Substring] can be a range of
+
+Cloning must be cheap and give the same text, not a copy of it. The trait is sealed;
+it is implemented for `Rc<String>`, `Rc<str>`, `Rc<Box<str>>`, `Arc<String>`, `Arc<str>`
+and `&'static str`.
+*/
+// Sealed is crate private so that RcSubstring's storage can be a private type
+#[allow(private_bounds)]
+pub trait StringStorage: Clone + Sealed {
+    /// The whole of the text
+    fn as_str(&self) -> &str;
+}
+
+impl Sealed for Rc<String> {
+    // So one RcSubstring type can be of static, arena and inline text as well
+    type Repr = Backing;
+    const TYPE_NAME: &'static str = "RcSubstring";
+
+    fn into_repr(self) -> Backing {
+        Backing::Shared(self)
+    }
+}
+
+impl StringStorage for Rc<String> {
+    fn as_str(&self) -> &str {
+        self
+    }
+}
+
+impl Text for Backing {
+    fn as_str(&self) -> &str {
+        Backing::as_str(self)
+    }
+
+    fn same_text(&self, other: &Self) -> bool {
+        Backing::same_text(self, other)
+    }
+}
+
+macro_rules! string_storage {
+    ($($storage:ty),*) => {
+        $(
+            impl Sealed for $storage {
+                type Repr = Self;
+                const TYPE_NAME: &'static str = "Substring";
+
+                fn into_repr(self) -> Self {
+                    self
+                }
+            }
+
+            impl Text for $storage {
+                fn as_str(&self) -> &str {
+                    self
+                }
+
+                fn same_text(&self, other: &Self) -> bool {
+                    std::ptr::eq(Text::as_str(self), Text::as_str(other))
+                }
+            }
+
+            impl StringStorage for $storage {
+                fn as_str(&self) -> &str {
+                    self
+                }
+            }
+
+            impl Substring<$storage> {
+                /// The storage this is a substring of
+                pub fn storage(&self) -> &$storage {
+                    &self.rcstring
+                }
+
+                /// Consume the substring returning its storage and range
+                pub fn into_parts(self) -> ($storage, Range<usize>) {
+                    (self.rcstring, self.range)
+                }
+            }
+        )*
+    };
+}
+
+string_storage!(Rc<str>, Rc<Box<str>>, Arc<String>, Arc<str>, &'static str);
+
+impl<S: StringStorage> Substring<S> {
+    /// Construct a substring of the whole of `storage`
+    #[track_caller]
+    pub fn whole(storage: S) -> Self {
+        let range = 0..storage.as_str().len();
+        Substring::from_parts(storage.into_repr(), range)
+    }
+
+    /// A substring of this substring, with `range` relative to it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds for this substring or not on char boundaries, as
+    /// [RcSubstring::subslice] does, even in release builds
+    #[track_caller]
+    pub fn substring(&self, range: Range<usize>) -> Self {
+        if let Err(error) = RcSubstringError::check(self, &range) {
+            panic!("{}", error);
+        }
+        self.reslice(range)
+    }
+
+    /// The range of this substring within its storage
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+impl From<Substring<&'static str>> for RcSubstring {
+    #[track_caller]
+    fn from(substring: Substring<&'static str>) -> Self {
+        RcSubstring::from_static(substring.rcstring).reslice(substring.range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn words<S: StringStorage>(storage: S) -> Vec<Substring<S>> {
+        let whole = Substring::whole(storage);
+        let mut words = Vec::new();
+        let mut start = 0;
+        for (i, c) in whole.char_indices().chain([(whole.len(), ' ')]) {
+            if c == ' ' {
+                words.push(whole.substring(start..i));
+                start = i + 1;
+            }
+        }
+        words
+    }
+
+    #[test]
+    fn test_storage_kinds() {
+        assert_eq!(words(Rc::new(String::from("a bc"))), ["a", "bc"]);
+        assert_eq!(words(Rc::<str>::from("a bc")), ["a", "bc"]);
+        assert_eq!(words(Rc::new(Box::<str>::from("a bc"))), ["a", "bc"]);
+        assert_eq!(words(Arc::new(String::from("a bc"))), ["a", "bc"]);
+        assert_eq!(words(Arc::<str>::from("a bc")), ["a", "bc"]);
+        assert_eq!(words("a bc"), ["a", "bc"]);
+    }
+
+    #[test]
+    #[allow(clippy::mutable_key_type)]
+    fn test_shares_storage() {
+        let source: Arc<str> = Arc::from("one two");
+        let two = Substring::new(Arc::clone(&source), 4..7);
+        assert!(Arc::ptr_eq(two.storage(), &source));
+        assert_eq!(two.range(), 4..7);
+        let w = two.substring(1..2);
+        assert_eq!(w.range(), 5..6);
+        assert_eq!(w, "w");
+        assert!(w.rcstring.same_text(&two.rcstring));
+        let copy = Substring::whole(Arc::<str>::from("one two"));
+        assert!(!copy.rcstring.same_text(&two.rcstring));
+        let set: HashSet<_> = [two.clone(), copy.substring(4..7)].into_iter().collect();
+        assert_eq!(set.len(), 1);
+        assert!(w > two);
+        assert_eq!(format!("{:?}", w.storage()), r#""one two""#);
+        let (storage, range) = w.into_parts();
+        assert_eq!(&storage[range], "w");
+    }
+
+    #[test]
+    fn test_rc_substring_is_substring() {
+        let source = Rc::new(String::from("one two"));
+        let two: Substring = Substring::new(Rc::clone(&source), 4..7);
+        let two: RcSubstring = two;
+        assert!(Rc::ptr_eq(two.shared().unwrap(), &source));
+        assert_eq!(two.range(), 4..7);
+        assert_eq!(two.substring(0..1), "t");
+        assert!(two.substring(0..1) < two);
+        let one = RcSubstring::from(Substring::new("one two", 0..3));
+        assert!(one.shared().is_none());
+        assert_eq!(one, "one");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_substring() {
+        let _ = Substring::whole("text").substring(2..5).len();
+    }
+
+    #[test]
+    #[should_panic(expected = "index 5 out of bounds for length 2")]
+    fn test_substring_past_end() {
+        // Inside the storage, but not inside the substring
+        let _ = Substring::new("text", 1..3).substring(0..5);
+    }
+}
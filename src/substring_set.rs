@@ -0,0 +1,115 @@
+//! A frozen set of [RcSubstring]s for fast membership tests
+use crate::{IntoRcSubstring, RcSubstring};
+use std::rc::Rc;
+
+/**
+A set of substrings built once, for keyword tables and stop word lists
+
+The substrings are kept sorted by text without duplicates and binary searched, with no
+hashing. Cloning is cheap, so one set can be shared by many parsers.
+
+```rust
+# use rcsubstring::SubstringSet;
+let keywords = SubstringSet::new(["let", "fn", "if", "fn"]);
+assert!(keywords.contains("fn"));
+assert!(!keywords.contains("main"));
+assert_eq!(keywords.len(), 3);
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SubstringSet {
+    substrings: Rc<[RcSubstring]>,
+}
+
+impl SubstringSet {
+    /// Create a set of the given substrings
+    ///
+    /// Of substrings with the same text, the first given is kept.
+    pub fn new<I>(substrings: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoRcSubstring,
+    {
+        let mut substrings: Vec<RcSubstring> = substrings
+            .into_iter()
+            .map(IntoRcSubstring::into_rc_substring)
+            .collect();
+        // A stable sort so dedup keeps the first of each text
+        substrings.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        substrings.dedup_by(|a, b| a.as_str() == b.as_str());
+        SubstringSet {
+            substrings: substrings.into(),
+        }
+    }
+
+    /// True if `text` is in the set
+    pub fn contains(&self, text: &str) -> bool {
+        self.get(text).is_some()
+    }
+
+    /// The substring in the set equal to `text`
+    ///
+    /// Useful for getting a shared copy of a keyword without allocating.
+    pub fn get(&self, text: &str) -> Option<&RcSubstring> {
+        let i = self
+            .substrings
+            .binary_search_by(|substring| substring.as_str().cmp(text))
+            .ok()?;
+        Some(&self.substrings[i])
+    }
+
+    /// The substrings in order of their text
+    pub fn iter(&self) -> std::slice::Iter<'_, RcSubstring> {
+        self.substrings.iter()
+    }
+
+    /// The number of substrings
+    pub fn len(&self) -> usize {
+        self.substrings.len()
+    }
+
+    /// True if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.substrings.is_empty()
+    }
+}
+
+impl<T: IntoRcSubstring> FromIterator<T> for SubstringSet {
+    fn from_iter<I: IntoIterator<Item = T>>(substrings: I) -> Self {
+        SubstringSet::new(substrings)
+    }
+}
+
+impl<'a> IntoIterator for &'a SubstringSet {
+    type Item = &'a RcSubstring;
+    type IntoIter = std::slice::Iter<'a, RcSubstring>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set() {
+        let source = Rc::new(String::from("the a an the"));
+        let words = RcSubstring::from(Rc::clone(&source));
+        let stop_words: SubstringSet = words.split_owned(" ").collect();
+        assert_eq!(stop_words.len(), 3);
+        let sorted: Vec<_> = stop_words.iter().cloned().collect();
+        assert_eq!(sorted, ["a", "an", "the"]);
+        assert_eq!(stop_words.get("the").unwrap().range, 0..3);
+        assert!(Rc::ptr_eq(
+            stop_words.get("an").unwrap().shared().unwrap(),
+            &source
+        ));
+        assert!(!stop_words.contains("then"));
+        assert!(!stop_words.contains(""));
+        let shared = stop_words.clone();
+        assert_eq!((&shared).into_iter().count(), 3);
+        assert!(SubstringSet::default().is_empty());
+    }
+}
@@ -0,0 +1,190 @@
+//! A small fixed tokenizer over an [RcSubstring]
+use crate::RcSubstring;
+use std::iter::FusedIterator;
+
+/// The kind of a token from [RcSubstring::simple_tokens]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Digits, such as `42`
+    Integer,
+    /// Digits with a fraction or exponent, such as `1.5` or `2e-3`
+    Float,
+    /// A letter or `_` followed by letters, digits or `_`
+    Identifier,
+    /// A double-quoted string, including the quotes, with backslash escapes left as they are
+    String,
+    /// One or two ASCII punctuation chars, such as `+`, `(` or `==`
+    Punct,
+    /// A string with no closing quote, or a char that starts no other token
+    Error,
+}
+
+// Two-char operators, which are otherwise split into single chars
+const OPERATORS: [&str; 16] = [
+    "==", "!=", "<=", ">=", "&&", "||", "->", "=>", "::", "+=", "-=", "*=", "/=", "<<", ">>", "..",
+];
+
+impl RcSubstring {
+    /// Split this substring into simple tokens, skipping whitespace
+    ///
+    /// Recognises integers, floats, identifiers, double-quoted strings and punctuation,
+    /// which is enough for prototypes and simple config formats. Anything else gives a
+    /// [TokenKind::Error] token, so the whole text is still covered.
+    ///
+    /// ```rust
+    /// # use rcsubstring::{RcSubstring, TokenKind};
+    /// let text = RcSubstring::from(String::from(r#"width >= 2.5 && name == "a \"b\"""#));
+    /// let tokens: Vec<_> = text.simple_tokens().collect();
+    /// assert_eq!(tokens[0].0, TokenKind::Identifier);
+    /// assert_eq!(tokens[1].1, ">=");
+    /// assert_eq!(tokens[2].0, TokenKind::Float);
+    /// assert_eq!(tokens[6].0, TokenKind::String);
+    /// assert_eq!(tokens[6].1, r#""a \"b\"""#);
+    /// ```
+    pub fn simple_tokens(&self) -> SimpleTokens {
+        SimpleTokens {
+            text: self.clone(),
+            pos: 0,
+        }
+    }
+}
+
+/**
+Iterator over the tokens of an [RcSubstring]
+
+Created by [RcSubstring::simple_tokens]
+*/
+#[derive(Debug, Clone)]
+pub struct SimpleTokens {
+    text: RcSubstring,
+    pos: usize,
+}
+
+impl Iterator for SimpleTokens {
+    type Item = (TokenKind, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.text[self.pos..];
+        let trimmed = rest.trim_start();
+        self.pos += rest.len() - trimmed.len();
+        let first = trimmed.chars().next()?;
+        let (kind, len) = scan(trimmed, first);
+        let token = self.text.reslice(self.pos..self.pos + len);
+        self.pos += len;
+        Some((kind, token))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.text.len() - self.pos))
+    }
+}
+
+impl FusedIterator for SimpleTokens {}
+
+// The kind and length of the token at the start of text, which starts with first
+fn scan(text: &str, first: char) -> (TokenKind, usize) {
+    let bytes = text.as_bytes();
+    if first.is_ascii_digit() {
+        return scan_number(bytes);
+    }
+    if first.is_alphabetic() || first == '_' {
+        let len = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(text.len());
+        return (TokenKind::Identifier, len);
+    }
+    if first == '"' {
+        let mut escaped = false;
+        for (i, b) in bytes.iter().enumerate().skip(1) {
+            match b {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => return (TokenKind::String, i + 1),
+                _ => {}
+            }
+        }
+        return (TokenKind::Error, text.len());
+    }
+    if first.is_ascii_punctuation() {
+        let two = OPERATORS.iter().any(|op| text.starts_with(op));
+        return (TokenKind::Punct, if two { 2 } else { 1 });
+    }
+    (TokenKind::Error, first.len_utf8())
+}
+
+// An integer or float at the start of bytes, which starts with a digit
+fn scan_number(bytes: &[u8]) -> (TokenKind, usize) {
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count()
+    };
+    let mut len = digits(0);
+    let mut kind = TokenKind::Integer;
+    if bytes.get(len) == Some(&b'.') && bytes.get(len + 1).is_some_and(u8::is_ascii_digit) {
+        len = digits(len + 1);
+        kind = TokenKind::Float;
+    }
+    if matches!(bytes.get(len), Some(b'e' | b'E')) {
+        let sign = matches!(bytes.get(len + 1), Some(b'+' | b'-')) as usize;
+        if bytes.get(len + 1 + sign).is_some_and(u8::is_ascii_digit) {
+            len = digits(len + 1 + sign);
+            kind = TokenKind::Float;
+        }
+    }
+    (kind, len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(text: &str) -> Vec<(TokenKind, String)> {
+        RcSubstring::from(String::from(text))
+            .simple_tokens()
+            .map(|(kind, token)| (kind, token.to_string()))
+            .collect()
+    }
+
+    fn kinds(text: &str) -> Vec<TokenKind> {
+        tokens(text).into_iter().map(|(kind, _)| kind).collect()
+    }
+
+    #[test]
+    fn test_numbers() {
+        use TokenKind::*;
+        assert_eq!(
+            kinds("1 23 4.5 6e7 8.9E-10"),
+            [Integer, Integer, Float, Float, Float]
+        );
+        assert_eq!(
+            kinds("1. 2e x1"),
+            [Integer, Punct, Integer, Identifier, Identifier]
+        );
+        assert_eq!(tokens("3.14.15")[0].1, "3.14");
+    }
+
+    #[test]
+    fn test_identifiers_strings_and_punct() {
+        use TokenKind::*;
+        let text: Vec<_> = tokens(r#"_héllo9 "x\\" (a)->b"#)
+            .into_iter()
+            .map(|(_, token)| token)
+            .collect();
+        assert_eq!(text, ["_héllo9", r#""x\\""#, "(", "a", ")", "->", "b"]);
+        assert_eq!(kinds(r#"_héllo9 "x\\" (a)->b"#)[1], String);
+        assert_eq!(
+            kinds("a<=b<c"),
+            [Identifier, Punct, Identifier, Punct, Identifier]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let errors = tokens("€ \"open");
+        assert_eq!(errors[0], (TokenKind::Error, String::from("€")));
+        assert_eq!(errors[1], (TokenKind::Error, String::from("\"open")));
+        assert!(tokens("  \n\t").is_empty());
+    }
+}
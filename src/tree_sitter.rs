@@ -0,0 +1,124 @@
+//! Extraction of `tree-sitter` node text as [RcSubstring]s
+use crate::RcSubstring;
+use ::tree_sitter::{Node, TreeCursor};
+use std::iter::FusedIterator;
+use std::rc::Rc;
+
+/// The text of a tree-sitter node as an [RcSubstring]
+///
+/// `rc` must hold the text the tree was parsed from, as node positions are byte offsets into it.
+pub fn node_text(rc: &Rc<String>, node: &Node) -> RcSubstring {
+    RcSubstring::new(Rc::clone(rc), node.byte_range())
+}
+
+/**
+Iterator over a tree-sitter node and all its descendants in pre-order
+
+Yields the kind of each node with its text as an [RcSubstring], so the text can be
+kept after the tree and the parse source have both gone.
+Anonymous nodes (eg. punctuation) are included; filter on [Node::is_named] via
+[NodeTexts::named] to skip them.
+
+```rust
+# use rcsubstring::NodeTexts;
+# use std::rc::Rc;
+let source = Rc::new(String::from("[1, true]"));
+let mut parser = tree_sitter::Parser::new();
+parser.set_language(&tree_sitter_json::LANGUAGE.into()).unwrap();
+let tree = parser.parse(source.as_str(), None).unwrap();
+let values: Vec<_> = NodeTexts::new(&source, tree.root_node())
+    .named()
+    .filter(|(kind, _)| *kind == "number" || *kind == "true")
+    .map(|(_, text)| text)
+    .collect();
+drop(tree);
+drop(source);
+assert_eq!(values, ["1", "true"]);
+```
+*/
+pub struct NodeTexts<'tree> {
+    source: Rc<String>,
+    cursor: TreeCursor<'tree>,
+    depth: usize,
+    named_only: bool,
+    finished: bool,
+}
+
+impl<'tree> NodeTexts<'tree> {
+    /// Walk `node` and its descendants, where `source` holds the text the tree was parsed from
+    pub fn new(source: &Rc<String>, node: Node<'tree>) -> Self {
+        NodeTexts {
+            source: Rc::clone(source),
+            cursor: node.walk(),
+            depth: 0,
+            named_only: false,
+            finished: false,
+        }
+    }
+
+    /// Only yield named nodes
+    pub fn named(mut self) -> Self {
+        self.named_only = true;
+        self
+    }
+
+    // Move the cursor to the next node in pre-order without leaving the starting node
+    fn advance(&mut self) {
+        if self.cursor.goto_first_child() {
+            self.depth += 1;
+            return;
+        }
+        while self.depth > 0 {
+            if self.cursor.goto_next_sibling() {
+                return;
+            }
+            self.cursor.goto_parent();
+            self.depth -= 1;
+        }
+        self.finished = true;
+    }
+}
+
+impl<'tree> Iterator for NodeTexts<'tree> {
+    type Item = (&'tree str, RcSubstring);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let node = self.cursor.node();
+            self.advance();
+            if node.is_named() || !self.named_only {
+                return Some((node.kind(), node_text(&self.source, &node)));
+            }
+        }
+        None
+    }
+}
+
+impl FusedIterator for NodeTexts<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::tree_sitter::Parser;
+
+    #[test]
+    fn test_walk_subtree() {
+        let source = Rc::new(String::from(r#"{"a": [1, 2], "b": null}"#));
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_json::LANGUAGE.into())
+            .unwrap();
+        let tree = parser.parse(source.as_str(), None).unwrap();
+        let object = tree.root_node().child(0).unwrap();
+        let array = object.child(1).unwrap().child(2).unwrap();
+        assert_eq!(node_text(&source, &array), "[1, 2]");
+
+        let nodes: Vec<_> = NodeTexts::new(&source, array).collect();
+        let kinds: Vec<_> = nodes.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds, ["array", "[", "number", ",", "number", "]"]);
+        assert_eq!(nodes[4].1, "2");
+
+        let named: Vec<_> = NodeTexts::new(&source, array).named().collect();
+        assert_eq!(named.len(), 3);
+    }
+}
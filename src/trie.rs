@@ -0,0 +1,240 @@
+//! A prefix trie keyed by [RcSubstring]s
+use crate::{IntoRcSubstring, RcSubstring};
+use std::collections::BTreeMap;
+use std::iter::FusedIterator;
+
+#[derive(Debug, Clone)]
+struct Node<V> {
+    children: BTreeMap<char, usize>,
+    entry: Option<(RcSubstring, V)>,
+}
+
+impl<V> Node<V> {
+    fn new() -> Self {
+        Node {
+            children: BTreeMap::new(),
+            entry: None,
+        }
+    }
+}
+
+/**
+A prefix trie mapping [RcSubstring] keys to values
+
+Each key is kept as the [RcSubstring] it was inserted with, sharing its source, so no
+`String` is allocated per key. Lookups take a plain `&str`.
+
+```rust
+# use rcsubstring::{RcSubstring, SubstringTrie};
+# use std::rc::Rc;
+let source = Rc::new(String::from("if in int"));
+let mut keywords = SubstringTrie::new();
+keywords.insert(RcSubstring::new(Rc::clone(&source), 0..2), "if");
+keywords.insert(RcSubstring::new(Rc::clone(&source), 3..5), "in");
+keywords.insert(RcSubstring::new(Rc::clone(&source), 6..9), "int");
+assert_eq!(keywords.get("in"), Some(&"in"));
+let (key, value) = keywords.longest_prefix_of("integer").unwrap();
+assert_eq!(key, "int");
+assert_eq!(*value, "int");
+```
+*/
+#[derive(Debug, Clone)]
+pub struct SubstringTrie<V> {
+    nodes: Vec<Node<V>>,
+    len: usize,
+}
+
+impl<V> Default for SubstringTrie<V> {
+    fn default() -> Self {
+        SubstringTrie::new()
+    }
+}
+
+impl<V> SubstringTrie<V> {
+    /// Create an empty trie
+    pub fn new() -> Self {
+        SubstringTrie {
+            nodes: vec![Node::new()],
+            len: 0,
+        }
+    }
+
+    /// The number of keys
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// True if there are no keys
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Insert `value` for `key`, returning the old value if `key` was already present
+    ///
+    /// If `key` was already present the original key is kept.
+    pub fn insert(&mut self, key: impl IntoRcSubstring, value: V) -> Option<V> {
+        let key = key.into_rc_substring();
+        let mut node = 0;
+        for c in key.chars() {
+            node = match self.nodes[node].children.get(&c) {
+                Some(&child) => child,
+                None => {
+                    self.nodes.push(Node::new());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(c, child);
+                    child
+                }
+            };
+        }
+        match &mut self.nodes[node].entry {
+            Some((_, old)) => Some(std::mem::replace(old, value)),
+            entry => {
+                *entry = Some((key, value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    // The node reached by following key, if any
+    fn find(&self, key: &str) -> Option<&Node<V>> {
+        let mut node = 0;
+        for c in key.chars() {
+            node = *self.nodes[node].children.get(&c)?;
+        }
+        Some(&self.nodes[node])
+    }
+
+    /// The value for `key`
+    pub fn get(&self, key: &str) -> Option<&V> {
+        self.find(key)?.entry.as_ref().map(|(_, value)| value)
+    }
+
+    /// The stored key equal to `key` and its value
+    pub fn get_key_value(&self, key: &str) -> Option<(RcSubstring, &V)> {
+        let (key, value) = self.find(key)?.entry.as_ref()?;
+        Some((key.clone(), value))
+    }
+
+    /// True if `key` is in the trie
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The longest key that `text` starts with, and its value
+    pub fn longest_prefix_of(&self, text: &str) -> Option<(RcSubstring, &V)> {
+        let mut node = &self.nodes[0];
+        let mut longest = node.entry.as_ref();
+        for c in text.chars() {
+            match node.children.get(&c) {
+                Some(&child) => node = &self.nodes[child],
+                None => break,
+            }
+            if node.entry.is_some() {
+                longest = node.entry.as_ref();
+            }
+        }
+        longest.map(|(key, value)| (key.clone(), value))
+    }
+
+    /// Iterate over the keys and values in order of the keys
+    pub fn iter(&self) -> TrieIter<'_, V> {
+        TrieIter {
+            trie: self,
+            stack: vec![0],
+            remaining: self.len,
+        }
+    }
+}
+
+/**
+Iterator over the keys and values of a [SubstringTrie] in order of the keys
+
+Created by [SubstringTrie::iter]
+*/
+#[derive(Debug, Clone)]
+pub struct TrieIter<'a, V> {
+    trie: &'a SubstringTrie<V>,
+    stack: Vec<usize>,
+    remaining: usize,
+}
+
+impl<'a, V> Iterator for TrieIter<'a, V> {
+    type Item = (&'a RcSubstring, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(node) = self.stack.pop() {
+            let node = &self.trie.nodes[node];
+            self.stack.extend(node.children.values().rev());
+            if let Some((key, value)) = &node.entry {
+                self.remaining -= 1;
+                return Some((key, value));
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<V> ExactSizeIterator for TrieIter<'_, V> {}
+
+impl<V> FusedIterator for TrieIter<'_, V> {}
+
+impl<'a, V> IntoIterator for &'a SubstringTrie<V> {
+    type Item = (&'a RcSubstring, &'a V);
+    type IntoIter = TrieIter<'a, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn trie(keys: &[&str]) -> SubstringTrie<usize> {
+        let mut trie = SubstringTrie::new();
+        for (i, key) in keys.iter().enumerate() {
+            trie.insert(*key, i);
+        }
+        trie
+    }
+
+    #[test]
+    fn test_insert_and_get() {
+        let source = Rc::new(String::from("key"));
+        let mut trie = SubstringTrie::new();
+        assert_eq!(trie.insert(RcSubstring::from(Rc::clone(&source)), 1), None);
+        assert_eq!(
+            trie.insert(RcSubstring::from(String::from("key")), 2),
+            Some(1)
+        );
+        assert_eq!(trie.len(), 1);
+        let (key, value) = trie.get_key_value("key").unwrap();
+        assert!(Rc::ptr_eq(key.shared().unwrap(), &source));
+        assert_eq!(*value, 2);
+        assert!(!trie.contains_key("ke"));
+    }
+
+    #[test]
+    fn test_longest_prefix_of() {
+        let trie = trie(&["é", "éa", "éabc", ""]);
+        assert_eq!(trie.longest_prefix_of("éab").unwrap().0, "éa");
+        assert_eq!(*trie.longest_prefix_of("éabcd").unwrap().1, 2);
+        assert_eq!(trie.longest_prefix_of("x").unwrap().0, "");
+        assert!(SubstringTrie::<()>::new().longest_prefix_of("x").is_none());
+    }
+
+    #[test]
+    fn test_iter_in_order() {
+        let trie = trie(&["b", "ab", "a", "ba", "c"]);
+        let keys: Vec<_> = trie.iter().map(|(key, _)| key.to_string()).collect();
+        assert_eq!(keys, ["a", "ab", "b", "ba", "c"]);
+        assert_eq!(trie.iter().len(), 5);
+    }
+}
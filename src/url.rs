@@ -0,0 +1,218 @@
+//! Splitting URLs into their components as [RcSubstring]s, checked with the `url` crate
+use crate::RcSubstring;
+use ::url::{ParseError, Url};
+use std::ops::Range;
+
+// The schemes the URL standard treats specially, which allow backslashes for slashes
+const SPECIAL_SCHEMES: &[&str] = &["http", "https", "ws", "wss", "ftp", "file"];
+
+impl RcSubstring {
+    /// Parse this text as an absolute URL, returning its components as substrings of it
+    ///
+    /// The `url` crate checks the URL, but the components are the text as written rather
+    /// than in the normalized form [Url] gives them, so they share the source: a host
+    /// keeps its case, and a path that is empty stays empty rather than becoming `/`.
+    /// Leading and trailing spaces and control chars are skipped, as the parser does.
+    /// Tabs and newlines inside the URL, which the parser drops, are kept.
+    ///
+    /// ```rust
+    /// # use rcsubstring::RcSubstring;
+    /// let link = RcSubstring::from(String::from("https://user@Example.com:8080/a/b?q=1#top"));
+    /// let spans = link.parse_url_components().unwrap();
+    /// assert_eq!(*spans.scheme(), "https");
+    /// assert_eq!(*spans.host().unwrap(), "Example.com");
+    /// assert_eq!(*spans.port().unwrap(), "8080");
+    /// assert_eq!(*spans.path(), "/a/b");
+    /// assert_eq!(*spans.query().unwrap(), "q=1");
+    /// assert_eq!(*spans.fragment().unwrap(), "top");
+    /// assert_eq!(spans.url().host_str(), Some("example.com"));
+    /// ```
+    pub fn parse_url_components(&self) -> Result<UrlSpans, ParseError> {
+        let url = Url::parse(self)?;
+        let trim = |c: char| c <= ' ';
+        let start = self.len() - self.trim_start_matches(trim).len();
+        let end = self.trim_end_matches(trim).len();
+        let text = &self[..end];
+        let special = SPECIAL_SCHEMES.contains(&url.scheme());
+        let is_slash = |c: char| c == '/' || (special && c == '\\');
+
+        // A successful parse means there is a scheme before the first colon
+        let colon = start + text[start..].find(':').unwrap();
+        let scheme = start..colon;
+        let mut pos = colon + 1;
+
+        let mut host = None;
+        let mut port = None;
+        let after_scheme = &text[pos..];
+        let slashes = after_scheme.len() - after_scheme.trim_start_matches(is_slash).len();
+        // Other special schemes take any number of slashes, or none, before the host
+        let any_slashes = special && url.scheme() != "file";
+        if any_slashes || slashes >= 2 {
+            pos += if any_slashes { slashes } else { 2 };
+            let authority_len = text[pos..]
+                .find(|c| is_slash(c) || c == '?' || c == '#')
+                .unwrap_or(text.len() - pos);
+            let authority = pos..pos + authority_len;
+            let host_start = text[authority.clone()]
+                .rfind('@')
+                .map_or(authority.start, |at| authority.start + at + 1);
+            let host_text = &text[host_start..authority.end];
+            // A colon inside the brackets of an IPv6 address is not a port
+            let port_colon = match host_text.rfind(']') {
+                Some(bracket) => host_text[bracket..].find(':').map(|i| bracket + i),
+                None => host_text.find(':'),
+            };
+            let host_end = port_colon.map_or(authority.end, |i| host_start + i);
+            if host_end > host_start {
+                host = Some(host_start..host_end);
+            }
+            port = port_colon
+                .map(|i| host_start + i + 1..authority.end)
+                .filter(|range| !range.is_empty());
+            pos = authority.end;
+        }
+
+        let path_len = text[pos..].find(['?', '#']).unwrap_or(text.len() - pos);
+        let path = pos..pos + path_len;
+        pos = path.end;
+        let mut query = None;
+        if text[pos..].starts_with('?') {
+            let len = text[pos + 1..].find('#').unwrap_or(text.len() - pos - 1);
+            query = Some(pos + 1..pos + 1 + len);
+            pos += 1 + len;
+        }
+        let fragment = text[pos..].starts_with('#').then(|| pos + 1..text.len());
+
+        let span = |range: Range<usize>| self.reslice(range);
+        Ok(UrlSpans {
+            scheme: span(scheme),
+            host: host.map(span),
+            port: port.map(span),
+            path: span(path),
+            query: query.map(span),
+            fragment: fragment.map(span),
+            url,
+        })
+    }
+}
+
+/**
+The components of a URL as substrings of its text
+
+Created by [RcSubstring::parse_url_components]
+*/
+#[derive(Debug, Clone)]
+pub struct UrlSpans {
+    scheme: RcSubstring,
+    host: Option<RcSubstring>,
+    port: Option<RcSubstring>,
+    path: RcSubstring,
+    query: Option<RcSubstring>,
+    fragment: Option<RcSubstring>,
+    url: Url,
+}
+
+impl UrlSpans {
+    /// The scheme, without the `:`
+    pub fn scheme(&self) -> &RcSubstring {
+        &self.scheme
+    }
+
+    /// The host, with the brackets of an IPv6 address, if there is one
+    pub fn host(&self) -> Option<&RcSubstring> {
+        self.host.as_ref()
+    }
+
+    /// The port, without the `:`, if one is given
+    pub fn port(&self) -> Option<&RcSubstring> {
+        self.port.as_ref()
+    }
+
+    /// The path, which can be empty
+    pub fn path(&self) -> &RcSubstring {
+        &self.path
+    }
+
+    /// The query, without the `?`, if there is one
+    pub fn query(&self) -> Option<&RcSubstring> {
+        self.query.as_ref()
+    }
+
+    /// The fragment, without the `#`, if there is one
+    pub fn fragment(&self) -> Option<&RcSubstring> {
+        self.fragment.as_ref()
+    }
+
+    /// The URL as parsed, in its normalized form
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    fn components(text: &str) -> [Option<String>; 6] {
+        let spans = RcSubstring::from(String::from(text))
+            .parse_url_components()
+            .unwrap();
+        let text = |span: Option<&RcSubstring>| span.map(|span| span.to_string());
+        [
+            text(Some(spans.scheme())),
+            text(spans.host()),
+            text(spans.port()),
+            text(Some(spans.path())),
+            text(spans.query()),
+            text(spans.fragment()),
+        ]
+    }
+
+    fn some(parts: [&str; 6]) -> [Option<String>; 6] {
+        parts.map(|part| (part != "-").then(|| String::from(part)))
+    }
+
+    #[test]
+    fn test_components() {
+        assert_eq!(
+            components(" HTTP://[::1]:80?#"),
+            some(["HTTP", "[::1]", "80", "", "", ""])
+        );
+        assert_eq!(
+            components("http:\\\\host\\p\\q"),
+            some(["http", "host", "-", "\\p\\q", "-", "-"])
+        );
+        assert_eq!(
+            components("mailto:someone@example.com?subject=hi"),
+            some(["mailto", "-", "-", "someone@example.com", "subject=hi", "-"])
+        );
+        assert_eq!(
+            components("file:///etc/hosts"),
+            some(["file", "-", "-", "/etc/hosts", "-", "-"])
+        );
+        assert_eq!(
+            components("git://a:b@[::1]/repo.git#main\n"),
+            some(["git", "[::1]", "-", "/repo.git", "-", "main"])
+        );
+        assert_eq!(
+            components("https://host:/p"),
+            some(["https", "host", "-", "/p", "-", "-"])
+        );
+    }
+
+    #[test]
+    fn test_shared_and_errors() {
+        let source = Rc::new(String::from("https://example.com/x"));
+        let spans = RcSubstring::from(Rc::clone(&source))
+            .parse_url_components()
+            .unwrap();
+        assert!(Rc::ptr_eq(spans.path().shared().unwrap(), &source));
+        assert_eq!(spans.path().range, 19..21);
+        let relative = RcSubstring::from_static("/just/a/path");
+        assert_eq!(
+            relative.parse_url_components().unwrap_err(),
+            ParseError::RelativeUrlWithoutBase
+        );
+    }
+}
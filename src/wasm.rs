@@ -0,0 +1,36 @@
+//! Conversions between [RcSubstring] and JavaScript strings with `wasm-bindgen`
+use crate::RcSubstring;
+use ::js_sys::JsString;
+use ::wasm_bindgen::JsValue;
+
+impl From<RcSubstring> for JsValue {
+    /// Copy the text into a new JavaScript string
+    fn from(substring: RcSubstring) -> Self {
+        JsValue::from_str(&substring)
+    }
+}
+
+impl From<RcSubstring> for JsString {
+    /// Copy the text into a new JavaScript string
+    fn from(substring: RcSubstring) -> Self {
+        JsString::from(substring.as_str())
+    }
+}
+
+impl RcSubstring {
+    /// Decode a JavaScript string once into a new `Rc<String>` and wrap the whole of it
+    ///
+    /// Slices of the result then share the decoded text rather than each
+    /// crossing the wasm boundary.
+    /// Unpaired surrogates in `text` are replaced with U+FFFD.
+    ///
+    /// ```rust,no_run
+    /// # use rcsubstring::RcSubstring;
+    /// # use js_sys::JsString;
+    /// let text = RcSubstring::from_js_string(&JsString::from("one two"));
+    /// assert_eq!(text, "one two");
+    /// ```
+    pub fn from_js_string(text: &JsString) -> RcSubstring {
+        RcSubstring::from(String::from(text))
+    }
+}
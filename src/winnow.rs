@@ -0,0 +1,221 @@
+//! Stream trait implementations so `winnow` parsers can consume an [RcSubstring] directly
+use crate::RcSubstring;
+use ::winnow::stream::{
+    Compare, CompareResult, FindSlice, Needed, Offset, Stream, StreamIsPartial,
+};
+use std::iter::FusedIterator;
+use std::ops::Range;
+
+impl Stream for RcSubstring {
+    type Token = char;
+    type Slice = RcSubstring;
+    type IterOffsets = CharOffsets;
+    type Checkpoint = StreamCheckpoint;
+
+    fn iter_offsets(&self) -> Self::IterOffsets {
+        CharOffsets {
+            substring: self.clone(),
+            pos: 0,
+            end: self.len(),
+        }
+    }
+
+    fn eof_offset(&self) -> usize {
+        self.len()
+    }
+
+    fn next_token(&mut self) -> Option<Self::Token> {
+        let c = self.peek_token()?;
+        self.set_range(self.range.start + c.len_utf8()..self.range.end);
+        Some(c)
+    }
+
+    fn peek_token(&self) -> Option<Self::Token> {
+        self.chars().next()
+    }
+
+    fn offset_for<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Token) -> bool,
+    {
+        self.find(predicate)
+    }
+
+    fn offset_at(&self, tokens: usize) -> Result<usize, Needed> {
+        let mut offsets = self.char_indices().map(|(i, _)| i).chain([self.len()]);
+        offsets.nth(tokens).ok_or(Needed::Unknown)
+    }
+
+    fn next_slice(&mut self, offset: usize) -> Self::Slice {
+        let slice = self.peek_slice(offset);
+        self.set_range(self.range.start + offset..self.range.end);
+        slice
+    }
+
+    fn peek_slice(&self, offset: usize) -> Self::Slice {
+        assert!(
+            self.is_char_boundary(offset),
+            "offset {offset} is not a char boundary of RcSubstring"
+        );
+        self.reslice(0..offset)
+    }
+
+    fn checkpoint(&self) -> Self::Checkpoint {
+        StreamCheckpoint {
+            range: self.range.clone(),
+        }
+    }
+
+    fn reset(&mut self, checkpoint: &Self::Checkpoint) {
+        self.set_range(checkpoint.range.clone());
+    }
+
+    fn trace(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", &**self)
+    }
+}
+
+impl StreamIsPartial for RcSubstring {
+    type PartialState = ();
+
+    fn complete(&mut self) -> Self::PartialState {}
+
+    fn restore_partial(&mut self, _state: Self::PartialState) {}
+
+    fn is_partial_supported() -> bool {
+        false
+    }
+}
+
+impl Offset for RcSubstring {
+    /// Offset of `self` from the earlier `start` in the same source
+    fn offset_from(&self, start: &Self) -> usize {
+        self.range.start - start.range.start
+    }
+}
+
+impl Offset<StreamCheckpoint> for RcSubstring {
+    fn offset_from(&self, start: &StreamCheckpoint) -> usize {
+        self.range.start - start.range.start
+    }
+}
+
+impl<T> Compare<T> for RcSubstring
+where
+    for<'a> &'a str: Compare<T>,
+{
+    fn compare(&self, t: T) -> CompareResult {
+        (&**self).compare(t)
+    }
+}
+
+impl<T> FindSlice<T> for RcSubstring
+where
+    for<'a> &'a str: FindSlice<T>,
+{
+    fn find_slice(&self, substr: T) -> Option<Range<usize>> {
+        (&**self).find_slice(substr)
+    }
+}
+
+/// A saved position in an [RcSubstring] being parsed with `winnow`
+///
+/// Used as [Stream::Checkpoint]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamCheckpoint {
+    range: Range<usize>,
+}
+
+impl Offset for StreamCheckpoint {
+    fn offset_from(&self, start: &Self) -> usize {
+        self.range.start - start.range.start
+    }
+}
+
+/// Owning iterator over the chars of an [RcSubstring] and their byte offsets
+///
+/// Used as [Stream::IterOffsets] as winnow needs an iterator that doesn't borrow the input
+#[derive(Debug, Clone)]
+pub struct CharOffsets {
+    substring: RcSubstring,
+    pos: usize,
+    end: usize,
+}
+
+impl Iterator for CharOffsets {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pos = self.pos;
+        let c = self.substring[pos..self.end].chars().next()?;
+        self.pos += c.len_utf8();
+        Some((pos, c))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.pos;
+        (len.div_ceil(4), Some(len))
+    }
+}
+
+impl DoubleEndedIterator for CharOffsets {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let c = self.substring[self.pos..self.end].chars().next_back()?;
+        self.end -= c.len_utf8();
+        Some((self.end, c))
+    }
+}
+
+impl FusedIterator for CharOffsets {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::winnow::ascii::{alpha1, digit1};
+    use ::winnow::combinator::{separated_pair, terminated};
+    use ::winnow::prelude::*;
+    use ::winnow::token::take_until;
+
+    fn assignment(input: &mut RcSubstring) -> ModalResult<(RcSubstring, RcSubstring)> {
+        terminated(separated_pair(alpha1, " = ", digit1), ';').parse_next(input)
+    }
+
+    #[test]
+    fn test_parser() {
+        let mut input = RcSubstring::from(String::from("answer = 42; more"));
+        let (name, value) = assignment(&mut input).unwrap();
+        assert_eq!(name, "answer");
+        assert_eq!(value, "42");
+        assert_eq!(value.range, 9..11);
+        assert_eq!(input, " more");
+
+        let mut input = RcSubstring::from(String::from("answer: 42;"));
+        assert!(assignment(&mut input).is_err());
+    }
+
+    #[test]
+    fn test_take_until() {
+        let mut input = RcSubstring::from(String::from("héllo, world"));
+        let word: ModalResult<RcSubstring> = take_until(0.., ",").parse_next(&mut input);
+        assert_eq!(word.unwrap(), "héllo");
+        assert_eq!(input, ", world");
+
+        fn exclamation(input: &mut RcSubstring) -> ModalResult<RcSubstring> {
+            ("lo", take_until(0.., "!"), "!").take().parse_next(input)
+        }
+        let value = exclamation
+            .parse(RcSubstring::from(String::from("lo yes!")))
+            .unwrap();
+        assert_eq!(value, "lo yes!");
+    }
+
+    #[test]
+    fn test_iter_offsets() {
+        let input = RcSubstring::from(String::from("aé"));
+        assert_eq!(
+            input.iter_offsets().rev().collect::<Vec<_>>(),
+            [(1, 'é'), (0, 'a')]
+        );
+        assert_eq!(input.iter_offsets().size_hint(), (1, Some(3)));
+    }
+}